@@ -1,9 +1,65 @@
 use std::io::{self, IoSlice, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
 
+use mud_transformer::opt::mssp::MsspRecord;
 use mud_transformer::output::OutputDrain;
-use mud_transformer::{Transformer, TransformerConfig};
+use mud_transformer::{TelnetSource, Transformer, TransformerConfig};
 
 use crate::config::DEFAULT_BUFFER_SIZE;
+use crate::probe::{MsspProbe, PLAINTEXT_REQUEST, Step};
+
+/// Connects to a MUD, requests its MSSP record, and disconnects.
+///
+/// The record is requested through telnet negotiation if the server offers MSSP. Otherwise, once
+/// the server has sent some text, it is requested with a plaintext `MSSP-REQUEST`. Returns
+/// `None` if the server closes the connection without sending a record.
+///
+/// Returns an error of kind [`TimedOut`](io::ErrorKind::TimedOut) or
+/// [`WouldBlock`](io::ErrorKind::WouldBlock) if no record is received within `timeout`.
+pub fn probe_mssp<A: ToSocketAddrs>(addr: A, timeout: Duration) -> io::Result<Option<MsspRecord>> {
+    let deadline = Instant::now() + timeout;
+    let mut last_error = None;
+    let mut connection = None;
+    for addr in addr.to_socket_addrs()? {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        match TcpStream::connect_timeout(&addr, remaining) {
+            Ok(stream) => {
+                connection = Some(stream);
+                break;
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+    let Some(stream) = connection else {
+        return Err(last_error.unwrap_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "no addresses to connect to")
+        }));
+    };
+    stream.set_write_timeout(Some(timeout))?;
+    let mut stream = MudStream::new(stream, MsspProbe::config());
+    let mut probe = MsspProbe::default();
+    let record = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        stream.get_ref().set_read_timeout(Some(remaining))?;
+        let Some(outputs) = stream.read()? else {
+            break None;
+        };
+        match probe.apply(outputs) {
+            Step::Continue => (),
+            Step::Request => stream.send_command(PLAINTEXT_REQUEST)?,
+            Step::Done(record) => break Some(*record),
+        }
+    };
+    stream.disconnect()?;
+    Ok(record)
+}
 
 #[derive(Debug)]
 pub struct MudStream<T> {
@@ -61,6 +117,10 @@ where
             return Ok(None);
         }
 
+        if let Some(mut drain) = self.transformer.drain_input() {
+            drain.write_all_to(&mut self.stream)?;
+        }
+
         let n = self.stream.read(&mut self.buf[..self.midpoint])?;
         if n == 0 {
             self.done = true;
@@ -75,7 +135,42 @@ where
         }
         Ok(Some(self.transformer.drain_output()))
     }
+
+    /// Sends a line of user input to the server. See [`Transformer::send_command`].
+    pub fn send_command(&mut self, command: &str) -> io::Result<()> {
+        self.transformer.send_command(command);
+        if let Some(mut drain) = self.transformer.drain_input() {
+            drain.write_all_to(&mut self.stream)?;
+        }
+        Ok(())
+    }
+
+    /// Asks the server to enable or disable a telnet option. See
+    /// [`Transformer::request_option`].
+    pub fn request_option(
+        &mut self,
+        source: TelnetSource,
+        code: u8,
+        enable: bool,
+    ) -> io::Result<()> {
+        self.transformer.request_option(source, code, enable);
+        if let Some(mut drain) = self.transformer.drain_input() {
+            drain.write_all_to(&mut self.stream)?;
+        }
+        Ok(())
+    }
+
+    /// Ends any compression of input to the server and flushes the remaining input to the
+    /// stream. This should be called before the connection is closed.
+    pub fn disconnect(&mut self) -> io::Result<()> {
+        self.transformer.end_compression();
+        if let Some(mut drain) = self.transformer.drain_input() {
+            drain.write_all_to(&mut self.stream)?;
+        }
+        self.stream.flush()
+    }
 }
+
 impl<T> Write for MudStream<T>
 where
     T: Write,
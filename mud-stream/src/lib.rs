@@ -3,5 +3,8 @@ pub mod blocking;
 
 mod config;
 
+#[cfg(any(feature = "sync", feature = "async"))]
+mod probe;
+
 #[cfg(feature = "async")]
 pub mod nonblocking;
@@ -3,11 +3,40 @@ use std::io::IoSlice;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+use mud_transformer::opt::mssp::MsspRecord;
 use mud_transformer::output::OutputDrain;
-use mud_transformer::{Transformer, TransformerConfig};
+use mud_transformer::{TelnetSource, Transformer, TransformerConfig};
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpStream, ToSocketAddrs};
 
 use crate::config::DEFAULT_BUFFER_SIZE;
+use crate::probe::{MsspProbe, PLAINTEXT_REQUEST, Step};
+
+/// Connects to a MUD, requests its MSSP record, and disconnects.
+///
+/// The record is requested through telnet negotiation if the server offers MSSP. Otherwise, once
+/// the server has sent some text, it is requested with a plaintext `MSSP-REQUEST`. Returns
+/// `None` if the server closes the connection without sending a record.
+///
+/// Servers that never respond will keep the connection open, so this should usually be wrapped
+/// in a timeout, such as `tokio::time::timeout`.
+pub async fn probe_mssp<A: ToSocketAddrs>(addr: A) -> io::Result<Option<MsspRecord>> {
+    let stream = TcpStream::connect(addr).await?;
+    let mut stream = MudStream::new(stream, MsspProbe::config());
+    let mut probe = MsspProbe::default();
+    let record = loop {
+        let Some(outputs) = stream.read().await? else {
+            break None;
+        };
+        match probe.apply(outputs) {
+            Step::Continue => (),
+            Step::Request => stream.send_command(PLAINTEXT_REQUEST).await?,
+            Step::Done(record) => break Some(*record),
+        }
+    };
+    stream.disconnect().await?;
+    Ok(record)
+}
 
 pub struct MudStream<T> {
     done: bool,
@@ -64,6 +93,10 @@ where
             return Ok(None);
         }
 
+        if let Some(mut drain) = self.transformer.drain_input() {
+            self.stream.write_all_buf(&mut drain).await?;
+        }
+
         let n = self.stream.read(&mut self.buf[..self.midpoint]).await?;
         if n == 0 {
             self.done = true;
@@ -78,6 +111,40 @@ where
         }
         Ok(Some(self.transformer.drain_output()))
     }
+
+    /// Sends a line of user input to the server. See [`Transformer::send_command`].
+    pub async fn send_command(&mut self, command: &str) -> io::Result<()> {
+        self.transformer.send_command(command);
+        if let Some(mut drain) = self.transformer.drain_input() {
+            self.stream.write_all_buf(&mut drain).await?;
+        }
+        Ok(())
+    }
+
+    /// Asks the server to enable or disable a telnet option. See
+    /// [`Transformer::request_option`].
+    pub async fn request_option(
+        &mut self,
+        source: TelnetSource,
+        code: u8,
+        enable: bool,
+    ) -> io::Result<()> {
+        self.transformer.request_option(source, code, enable);
+        if let Some(mut drain) = self.transformer.drain_input() {
+            self.stream.write_all_buf(&mut drain).await?;
+        }
+        Ok(())
+    }
+
+    /// Ends any compression of input to the server, flushes the remaining input to the stream,
+    /// and shuts down the stream.
+    pub async fn disconnect(&mut self) -> io::Result<()> {
+        self.transformer.end_compression();
+        if let Some(mut drain) = self.transformer.drain_input() {
+            self.stream.write_all_buf(&mut drain).await?;
+        }
+        self.stream.shutdown().await
+    }
 }
 
 impl<T> AsyncWrite for MudStream<T>
@@ -0,0 +1,132 @@
+use mud_transformer::opt::mssp::{self, MsspRecord};
+use mud_transformer::output::{Output, OutputFragment, TelnetFragment};
+use mud_transformer::{TelnetSource, TelnetVerb, TransformerConfig};
+
+/// Sent to servers that do not offer MSSP through telnet negotiation.
+pub(crate) const PLAINTEXT_REQUEST: &str = "MSSP-REQUEST";
+
+pub(crate) enum Step {
+    Continue,
+    Request,
+    Done(Box<MsspRecord>),
+}
+
+/// Tracks the progress of a request for a server's MSSP record.
+#[derive(Debug, Default)]
+pub(crate) struct MsspProbe {
+    negotiated: bool,
+    requested: bool,
+    text: String,
+}
+
+impl MsspProbe {
+    pub fn config() -> TransformerConfig {
+        let mut config = TransformerConfig::new();
+        config.will.insert(mssp::OPT);
+        config
+    }
+
+    /// Processes received output. If the server has sent text without offering MSSP, a plaintext
+    /// request should be sent.
+    pub fn apply<I: IntoIterator<Item = Output>>(&mut self, outputs: I) -> Step {
+        let mut received_text = false;
+        for output in outputs {
+            match output.fragment {
+                OutputFragment::Telnet(TelnetFragment::Negotiation {
+                    source: TelnetSource::Server,
+                    verb: TelnetVerb::Will,
+                    code: mssp::OPT,
+                }) => self.negotiated = true,
+                OutputFragment::Telnet(TelnetFragment::Subnegotiation {
+                    code: mssp::OPT,
+                    data,
+                }) => return Step::Done(Box::new(MsspRecord::decode(&data))),
+                OutputFragment::Text(fragment) if self.requested => {
+                    self.text.push_str(&fragment.text);
+                }
+                OutputFragment::LineBreak if self.requested => self.text.push('\n'),
+                OutputFragment::Text(_) => received_text = true,
+                _ => (),
+            }
+        }
+        if let Some(record) = MsspRecord::parse_plaintext(&self.text) {
+            return Step::Done(Box::new(record));
+        }
+        if received_text && !self.negotiated && !self.requested {
+            self.requested = true;
+            return Step::Request;
+        }
+        Step::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mud_transformer::Transformer;
+    use mud_transformer::escape::telnet;
+
+    use super::*;
+
+    fn apply(probe: &mut MsspProbe, transformer: &mut Transformer, bytes: &[u8]) -> Step {
+        transformer.receive(bytes, &mut [0; 1024]);
+        probe.apply(transformer.flush_output())
+    }
+
+    fn record() -> MsspRecord {
+        let mut record = MsspRecord::new();
+        record.insert("NAME", &["Elsewhere"]);
+        record.insert("PLAYERS", &["3"]);
+        record
+    }
+
+    #[test]
+    fn negotiated() {
+        let mut transformer = Transformer::new(MsspProbe::config());
+        let mut probe = MsspProbe::default();
+        let step = apply(
+            &mut probe,
+            &mut transformer,
+            &[telnet::IAC, telnet::WILL, mssp::OPT],
+        );
+        assert!(matches!(step, Step::Continue));
+        let step = apply(&mut probe, &mut transformer, b"Welcome!\r\n");
+        assert!(matches!(step, Step::Continue));
+        let mut encoded = Vec::new();
+        record().encode(&mut encoded).unwrap();
+        let Step::Done(received) = apply(&mut probe, &mut transformer, &encoded) else {
+            panic!("expected MSSP record");
+        };
+        assert_eq!(*received, record());
+    }
+
+    #[test]
+    fn plaintext_request_once() {
+        let mut transformer = Transformer::new(MsspProbe::config());
+        let mut probe = MsspProbe::default();
+        let step = apply(&mut probe, &mut transformer, b"Welcome!\r\n");
+        assert!(matches!(step, Step::Request));
+        let step = apply(&mut probe, &mut transformer, b"By what name are you known?");
+        assert!(matches!(step, Step::Continue));
+    }
+
+    #[test]
+    fn plaintext_across_reads() {
+        let mut transformer = Transformer::new(MsspProbe::config());
+        let mut probe = MsspProbe::default();
+        apply(&mut probe, &mut transformer, b"Welcome!\r\n");
+        let step = apply(
+            &mut probe,
+            &mut transformer,
+            b"MSSP-REPLY-START\r\nNAME\tElsewhere\r\n",
+        );
+        assert!(matches!(step, Step::Continue));
+        let Step::Done(received) = apply(
+            &mut probe,
+            &mut transformer,
+            b"PLAYERS\t3\r\nMSSP-REPLY-END\r\n",
+        ) else {
+            panic!("expected MSSP record");
+        };
+        assert_eq!(*received, record());
+    }
+}
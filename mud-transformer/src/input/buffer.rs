@@ -1,11 +1,17 @@
 use std::{fmt, io};
 
+use log::error;
+
 use super::drain::InputDrain;
+use crate::opt::mccp2::Compressor;
 
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub(crate) struct BufferedInput {
     buf: Vec<u8>,
     cursor: usize,
+    /// Input waiting to be compressed, while MCCP3 is active.
+    uncompressed: Vec<u8>,
+    compress: Option<Box<Compressor>>,
 }
 
 impl Default for BufferedInput {
@@ -19,10 +25,61 @@ impl BufferedInput {
         Self {
             buf: Vec::new(),
             cursor: 0,
+            uncompressed: Vec::new(),
+            compress: None,
+        }
+    }
+
+    pub const fn compressing(&self) -> bool {
+        self.compress.is_some()
+    }
+
+    /// Compresses all input written from now on, until [`end_compression`](Self::end_compression).
+    pub fn start_compression(&mut self) {
+        if self.compress.is_none() {
+            self.compress = Some(Box::default());
+        }
+    }
+
+    /// Ends the compressed stream, if compression is active. Input written from now on is not
+    /// compressed.
+    pub fn end_compression(&mut self) {
+        self.flush_compressed(true);
+        self.compress = None;
+    }
+
+    fn flush_compressed(&mut self, finish: bool) {
+        let Some(compress) = &mut self.compress else {
+            return;
+        };
+        let mut result = compress.write(&self.uncompressed, &mut self.buf);
+        if result.is_ok() {
+            result = if finish {
+                compress.finish(&mut self.buf)
+            } else {
+                compress.flush(&mut self.buf)
+            };
+        }
+        if let Err(e) = result {
+            error!(target: "mud.compress", "{e:?}");
+            self.compress = None;
+        }
+        self.uncompressed.clear();
+    }
+
+    #[inline]
+    fn target(&mut self) -> &mut Vec<u8> {
+        if self.compress.is_some() {
+            &mut self.uncompressed
+        } else {
+            &mut self.buf
         }
     }
 
     pub fn drain(&mut self) -> Option<InputDrain<'_>> {
+        if !self.uncompressed.is_empty() {
+            self.flush_compressed(false);
+        }
         if self.buf.is_empty() {
             return None;
         }
@@ -35,12 +92,12 @@ impl BufferedInput {
 
     #[inline]
     pub fn write(&mut self, bytes: &[u8]) {
-        self.buf.extend_from_slice(bytes);
+        self.target().extend_from_slice(bytes);
     }
 
     #[inline]
     pub fn write_str(&mut self, s: &str) {
-        self.buf.extend_from_slice(s.as_bytes());
+        self.target().extend_from_slice(s.as_bytes());
     }
 
     #[inline]
@@ -60,7 +117,7 @@ impl fmt::Write for BufferedInput {
 impl io::Write for BufferedInput {
     #[inline]
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.buf.extend_from_slice(buf);
+        self.target().extend_from_slice(buf);
         Ok(buf.len())
     }
 
@@ -71,12 +128,12 @@ impl io::Write for BufferedInput {
 
     #[inline]
     fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
-        self.buf.write_vectored(bufs)
+        self.target().write_vectored(bufs)
     }
 
     #[inline]
     fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
-        self.buf.extend_from_slice(buf);
+        self.target().extend_from_slice(buf);
         Ok(())
     }
 
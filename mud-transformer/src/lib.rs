@@ -11,7 +11,7 @@ pub mod opt;
 pub use opt::naws::subnegotiate as naws;
 
 mod protocol;
-pub use protocol::{TelnetSource, TelnetVerb};
+pub use protocol::{OptionState, OptionStatus, TelnetSource, TelnetVerb};
 
 pub mod output;
 
@@ -20,7 +20,10 @@ pub mod responses;
 pub mod term;
 
 mod transformer;
-pub use transformer::{ByteSet, TabBehavior, Tag, Transformer, TransformerConfig, UseMxp};
+pub use transformer::{
+    ByteSet, Encoding, LinkPolicy, LinkViolation, OptionContext, TabBehavior, Tag,
+    TelnetOptionHandler, Transformer, TransformerConfig, UseMxp,
+};
 
 fn count_bytes(haystack: &[u8], needle: u8) -> usize {
     haystack.iter().fold(0, |n, c| n + u32::from(*c == needle)) as usize
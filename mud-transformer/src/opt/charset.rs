@@ -3,12 +3,11 @@ use std::io::Write;
 use std::str;
 use std::{fmt, io};
 
-use flagset::{FlagSet, flags};
 use mxp::escape::telnet;
 
 pub use crate::protocol::ToBeBytes;
 use crate::protocol::{Negotiate, write_escaping_iac};
-use crate::transformer::TransformerConfig;
+use crate::transformer::{Encoding, TransformerConfig};
 
 /// [RFC 2066](https://datatracker.ietf.org/doc/html/rfc2066): CHARSET
 pub const OPT: u8 = 42;
@@ -77,6 +76,7 @@ impl<'a> Request<'a> {
             None => (false, request),
         };
         let rest = match rest {
+            _ if !ttable => rest,
             [] => return Err(DecodeError::NoVersion),
             [1 | b'1', rest @ ..] => rest,
             [version, ..] => return Err(DecodeError::UnsupportedVersion(*version)),
@@ -203,70 +203,77 @@ impl Response<'_> {
             }
             Self::Rejected => writer.write_all(&[REJECTED])?,
         }
-        writer.write_all(&[telnet::IAC, telnet::SB])
-    }
-}
-
-flags! {
-    enum Charset: u8 {
-        Ascii,
-        Utf8
+        writer.write_all(&[telnet::IAC, telnet::SE])
     }
 }
 
+/// Charset chosen in response to a [`Request`], from the character sets the transformer can
+/// decode. UTF-8 is preferred, followed by [`TransformerConfig::encoding`], followed by the server's
+/// order of preference.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub(crate) struct Charsets {
-    set: FlagSet<Charset>,
+pub(crate) struct Charsets<'a> {
+    accepted: Option<&'a str>,
+    encoding: Option<Encoding>,
 }
 
-impl Charsets {
-    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+impl<'a> Charsets<'a> {
+    const REJECTED: Self = Self {
+        accepted: None,
+        encoding: None,
+    };
+
+    pub fn decode(data: &'a [u8], config: &TransformerConfig) -> Result<Self, DecodeError> {
         let request = Request::decode(data)?;
-        let mut set = FlagSet::default();
-        for fragment in request {
-            if fragment.eq_ignore_ascii_case("UTF-8") {
-                set |= Charset::Utf8;
-            } else if fragment.eq_ignore_ascii_case("US-ASCII") {
-                set |= Charset::Ascii;
+        let mut best = Self::REJECTED;
+        let mut best_rank = u8::MAX;
+        for name in request {
+            let encoding = Encoding::from_name(name);
+            let rank = match encoding {
+                Some(Encoding::Utf8) if config.disable_utf8 => continue,
+                Some(Encoding::Utf8) => 0,
+                Some(encoding) if encoding == config.encoding => 1,
+                Some(_) => 2,
+                None if is_ascii(name) => 3,
+                None => continue,
+            };
+            if rank < best_rank {
+                best_rank = rank;
+                best = Self {
+                    accepted: Some(name),
+                    encoding,
+                };
             }
         }
-        Ok(Charsets { set })
+        Ok(best)
     }
-}
 
-impl Charsets {
-    pub const fn new() -> Self {
-        Self {
-            set: FlagSet::empty(),
-        }
+    pub const fn is_accepted(self) -> bool {
+        self.accepted.is_some()
     }
-}
 
-impl Default for Charsets {
-    fn default() -> Self {
-        Self::new()
+    /// Returns the negotiated encoding, or `None` if the server only uses ASCII.
+    pub const fn encoding(self) -> Option<Encoding> {
+        self.encoding
     }
 }
 
-impl fmt::Display for Charsets {
+fn is_ascii(name: &str) -> bool {
+    name.eq_ignore_ascii_case("US-ASCII") || name.eq_ignore_ascii_case("ASCII")
+}
+
+impl fmt::Display for Charsets<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.set.contains(Charset::Utf8) {
-            f.write_str("\x02UTF-8")
-        } else if self.set.contains(Charset::Ascii) {
-            f.write_str("\x02US-ASCII")
-        } else {
-            f.write_str("\x03")
+        match self.accepted {
+            Some(charset) => write!(f, "\x02{charset}"),
+            None => f.write_str("\x03"),
         }
     }
 }
 
-impl Negotiate for Charsets {
+impl Negotiate for Charsets<'_> {
     const OPT: u8 = OPT;
 
-    fn negotiate<W: fmt::Write>(mut self, mut f: W, config: &TransformerConfig) -> fmt::Result {
-        if config.disable_utf8 {
-            self.set -= Charset::Utf8;
-        }
+    fn negotiate<W: fmt::Write>(self, mut f: W, _config: &TransformerConfig) -> fmt::Result {
         write!(f, "{self}")
     }
 }
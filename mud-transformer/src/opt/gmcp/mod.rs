@@ -7,6 +7,18 @@ use std::str::Utf8Error;
 use serde::{Deserialize, Serialize};
 
 use crate::escape::telnet;
+use crate::protocol::Negotiate;
+use crate::transformer::TransformerConfig;
+
+#[cfg(feature = "json")]
+pub mod package;
+#[cfg(feature = "json")]
+pub use package::Package;
+
+#[cfg(feature = "json")]
+mod state;
+#[cfg(feature = "json")]
+pub use state::State;
 
 /// Generic Mud Communication Protocol
 ///
@@ -127,3 +139,68 @@ impl Message<String> {
         })
     }
 }
+
+/// Messages sent by the client to announce itself and the packages it supports.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Handshake<'a> {
+    /// `Core.Hello`, with the client name and version.
+    Hello,
+    /// `Core.Supports.Set`, with [`TransformerConfig::gmcp_packages`].
+    Set,
+    /// `Core.Supports.Add`, with packages that have been added since `Core.Supports.Set`.
+    Add(&'a [String]),
+    /// `Core.Supports.Remove`, with packages that have been removed since `Core.Supports.Set`.
+    Remove(&'a [String]),
+}
+
+impl Negotiate for Handshake<'_> {
+    const OPT: u8 = OPT;
+
+    fn negotiate<W: fmt::Write>(self, mut f: W, config: &TransformerConfig) -> fmt::Result {
+        let packages = match self {
+            Self::Hello => {
+                f.write_str("Core.Hello {\"client\":")?;
+                write_json_str(&mut f, &config.app_name)?;
+                f.write_str(",\"version\":")?;
+                write_json_str(&mut f, &config.version)?;
+                return f.write_char('}');
+            }
+            Self::Set => {
+                f.write_str("Core.Supports.Set ")?;
+                &config.gmcp_packages[..]
+            }
+            Self::Add(packages) => {
+                f.write_str("Core.Supports.Add ")?;
+                packages
+            }
+            Self::Remove(packages) => {
+                f.write_str("Core.Supports.Remove ")?;
+                packages
+            }
+        };
+        f.write_char('[')?;
+        for (i, package) in packages.iter().enumerate() {
+            if i != 0 {
+                f.write_char(',')?;
+            }
+            write_json_str(&mut f, package)?;
+        }
+        f.write_char(']')
+    }
+}
+
+fn write_json_str<W: fmt::Write>(mut f: W, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", u32::from(c))?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
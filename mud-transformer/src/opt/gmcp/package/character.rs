@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::lenient;
+
+/// `Char.Vitals`: current and maximum statistics of the player's character.
+///
+/// Games send only the statistics they use, and may send partial updates containing only the
+/// values that have changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vitals {
+    /// Health.
+    #[serde(default, deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hp: Option<i64>,
+    /// Maximum health.
+    #[serde(default, rename = "maxhp", deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_hp: Option<i64>,
+    /// Mana.
+    #[serde(default, deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mp: Option<i64>,
+    /// Maximum mana.
+    #[serde(default, rename = "maxmp", deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_mp: Option<i64>,
+    /// Endurance.
+    #[serde(default, deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ep: Option<i64>,
+    /// Maximum endurance.
+    #[serde(default, rename = "maxep", deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_ep: Option<i64>,
+    /// Willpower.
+    #[serde(default, deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wp: Option<i64>,
+    /// Maximum willpower.
+    #[serde(default, rename = "maxwp", deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_wp: Option<i64>,
+    /// Progress towards the next level, as a percentage.
+    #[serde(default, rename = "nl", deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_level: Option<i64>,
+    /// Summary of the statistics, formatted for display.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub string: Option<String>,
+    /// Game-specific statistics.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// `Char.Status`: general information about the player's character.
+///
+/// Games may send partial updates containing only the values that have changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Status {
+    /// Short name.
+    #[serde(default, deserialize_with = "lenient::opt_string")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Name with titles.
+    #[serde(default, deserialize_with = "lenient::opt_string")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fullname: Option<String>,
+    #[serde(default, deserialize_with = "lenient::opt_string")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub race: Option<String>,
+    #[serde(default, deserialize_with = "lenient::opt_string")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gender: Option<String>,
+    #[serde(default, deserialize_with = "lenient::opt_string")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default, deserialize_with = "lenient::opt_string")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub specialisation: Option<String>,
+    /// Level, which some games follow with progress towards the next level, e.g. `"42 (24%)"`.
+    #[serde(default, deserialize_with = "lenient::opt_string")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    /// Experience, which some games send as progress towards the next level, e.g. `"24%"`.
+    #[serde(default, deserialize_with = "lenient::opt_string")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xp: Option<String>,
+    #[serde(default, deserialize_with = "lenient::opt_string")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(default, deserialize_with = "lenient::opt_string")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub house: Option<String>,
+    #[serde(default, deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gold: Option<i64>,
+    #[serde(default, deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bank: Option<i64>,
+    /// Current target of attacks.
+    #[serde(default, deserialize_with = "lenient::opt_string")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Game-specific information.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// An item in a location tracked by `Char.Items`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Item {
+    /// Unique identifier, used to refer to the item in commands.
+    #[serde(deserialize_with = "lenient::string")]
+    pub id: String,
+    /// Short description.
+    #[serde(default)]
+    pub name: String,
+    /// Attribute flags, such as `w` for worn, `c` for container, or `m` for monster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attrib: Option<String>,
+    /// Name of an icon representing the item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Game-specific information.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl Item {
+    /// Returns true if the item's attributes include a flag.
+    pub fn has_attribute(&self, flag: char) -> bool {
+        self.attrib
+            .as_ref()
+            .is_some_and(|attrib| attrib.contains(flag))
+    }
+}
+
+/// `Char.Items.List`: every item in a location.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemList {
+    /// `"inv"` for the inventory, `"room"` for the current room, or `"rep"` followed by the ID of a
+    /// container.
+    pub location: String,
+    #[serde(default)]
+    pub items: Vec<Item>,
+}
+
+/// `Char.Items.Add`, `Char.Items.Remove`, or `Char.Items.Update`: a change to a single item in a
+/// location.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemChange {
+    /// See [`ItemList::location`].
+    pub location: String,
+    pub item: Item,
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::lenient;
+
+/// Category of media played through `Client.Media`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaType {
+    #[default]
+    Sound,
+    Music,
+    Video,
+}
+
+/// `Client.Media.Default`: sets the URL from which media files are downloaded when no URL is
+/// specified.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaDefault {
+    pub url: String,
+}
+
+/// `Client.Media.Load`: downloads a media file to be played later.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaLoad {
+    /// File name, relative to the URL.
+    pub name: String,
+    /// Base URL to download the file from, or the default URL if `None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// `Client.Media.Play`: plays a media file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaPlay {
+    /// File name, relative to the URL.
+    pub name: String,
+    /// Base URL to download the file from, or the default URL if `None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<MediaType>,
+    /// Label used to stop groups of media, such as `"combat"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Volume from 1 to 100.
+    #[serde(default, deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume: Option<i64>,
+    /// Milliseconds over which to increase the volume when the media starts.
+    #[serde(default, rename = "fadein", deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fade_in: Option<i64>,
+    /// Milliseconds over which to decrease the volume before the media ends.
+    #[serde(default, rename = "fadeout", deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fade_out: Option<i64>,
+    /// Position in milliseconds from which to begin playing.
+    #[serde(default, deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<i64>,
+    /// Position in milliseconds at which to stop playing.
+    #[serde(default, deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finish: Option<i64>,
+    /// Number of times to play the media, or -1 to repeat indefinitely.
+    #[serde(default, deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loops: Option<i64>,
+    /// Priority from 1 to 100. Media with lower priority is stopped in favor of this media.
+    #[serde(default, deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+    /// If true, music that is already playing continues instead of restarting.
+    #[serde(default, rename = "continue", skip_serializing_if = "Option::is_none")]
+    pub continue_playing: Option<bool>,
+    /// Unique identifier. Media with the same key stops the media already playing with it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Description of the media for screen readers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    /// Other options.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// `Client.Media.Stop`: stops media that matches every specified field, or all media if no
+/// fields are specified.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaStop {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<MediaType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Stops media with this priority or lower.
+    #[serde(default, deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// If true, fades out media over five seconds or [`fade_out`](Self::fade_out) milliseconds,
+    /// instead of stopping it immediately.
+    #[serde(default, rename = "fadeaway", skip_serializing_if = "Option::is_none")]
+    pub fade_away: Option<bool>,
+    /// Milliseconds over which to decrease the volume, if [`fade_away`](Self::fade_away) is true.
+    #[serde(default, rename = "fadeout", deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fade_out: Option<i64>,
+}
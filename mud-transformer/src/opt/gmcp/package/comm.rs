@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// `Comm.Channel.Text`: a message received on a communication channel.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelText {
+    /// Name of the channel, such as `"say"` or `"tell Tecton"`.
+    pub channel: String,
+    /// Name of the speaker.
+    #[serde(default)]
+    pub talker: String,
+    /// Text of the message as it is displayed by the game, which may include ANSI formatting.
+    pub text: String,
+}
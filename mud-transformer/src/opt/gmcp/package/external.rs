@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::lenient;
+
+/// `External.Discord.Info`: the game's Discord server and application.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscordInfo {
+    /// Invitation link to the game's Discord server.
+    #[serde(default, rename = "inviteurl", skip_serializing_if = "Option::is_none")]
+    pub invite_url: Option<String>,
+    /// ID of the game's Discord application, for setting Rich Presence.
+    #[serde(
+        default,
+        rename = "applicationid",
+        deserialize_with = "lenient::opt_string"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application_id: Option<String>,
+}
+
+/// `External.Discord.Status`: the player's Discord Rich Presence.
+///
+/// Games may send partial updates containing only the values that have changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscordStatus {
+    /// Name of the game.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game: Option<String>,
+    /// What the player is doing, such as `"Exploring the forest"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// The player's current party status, such as `"In a group"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(default, rename = "largeimage", deserialize_with = "lenient::strings")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub large_image: Vec<String>,
+    #[serde(default, rename = "largeimagetext")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub large_image_text: Option<String>,
+    #[serde(default, rename = "smallimage", deserialize_with = "lenient::strings")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub small_image: Vec<String>,
+    #[serde(default, rename = "smallimagetext")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub small_image_text: Option<String>,
+    #[serde(default, rename = "partysize", deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub party_size: Option<i64>,
+    #[serde(default, rename = "partymax", deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub party_max: Option<i64>,
+    /// Unix timestamp at which the current activity began.
+    #[serde(default, rename = "starttime", deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<i64>,
+    /// Unix timestamp at which the current activity will end.
+    #[serde(default, rename = "endtime", deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<i64>,
+    /// Other presence fields.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
@@ -0,0 +1,74 @@
+//! Deserializers for values that games encode inconsistently, such as numbers sent as strings.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde::de::{Deserializer, Error};
+use serde_json::Value;
+
+fn to_int<E: Error>(value: &Value) -> Result<Option<i64>, E> {
+    match value {
+        Value::Null => Ok(None),
+        Value::Number(n) => n
+            .as_i64()
+            .map(Some)
+            .ok_or_else(|| E::custom(format!("invalid integer: {n}"))),
+        Value::String(s) if s.trim().is_empty() => Ok(None),
+        Value::String(s) => s
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| E::custom(format!("invalid integer: {s:?}"))),
+        _ => Err(E::custom(format!("expected integer, got {value}"))),
+    }
+}
+
+fn to_string<E: Error>(value: Value) -> Result<Option<String>, E> {
+    match value {
+        Value::Null => Ok(None),
+        Value::String(s) => Ok(Some(s)),
+        Value::Number(n) => Ok(Some(n.to_string())),
+        Value::Bool(b) => Ok(Some(b.to_string())),
+        _ => Err(E::custom(format!("expected string, got {value}"))),
+    }
+}
+
+/// An integer, which may be sent as a string.
+pub fn int<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+    to_int(&Value::deserialize(deserializer)?)
+}
+
+/// A string, which may be sent as a number.
+pub fn string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(to_string(Value::deserialize(deserializer)?)?.unwrap_or_default())
+}
+
+/// An optional string, which may be sent as a number.
+pub fn opt_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    to_string(Value::deserialize(deserializer)?)
+}
+
+/// A list of strings, which may be sent as a single string.
+pub fn strings<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Array(values) => values
+            .into_iter()
+            .filter_map(|value| to_string(value).transpose())
+            .collect(),
+        value => Ok(to_string(value)?.into_iter().collect()),
+    }
+}
+
+/// A map of integers, which may be sent as strings.
+pub fn int_map<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, i64>, D::Error> {
+    let map = BTreeMap::<String, Value>::deserialize(deserializer)?;
+    let mut ints = BTreeMap::new();
+    for (key, value) in map {
+        if let Some(n) = to_int(&value)? {
+            ints.insert(key, n);
+        }
+    }
+    Ok(ints)
+}
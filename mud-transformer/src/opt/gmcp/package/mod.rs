@@ -0,0 +1,163 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::Message;
+
+mod lenient;
+
+mod character;
+pub use character::{Item, ItemChange, ItemList, Status, Vitals};
+
+mod client;
+pub use client::{MediaDefault, MediaLoad, MediaPlay, MediaStop, MediaType};
+
+mod comm;
+pub use comm::ChannelText;
+
+mod external;
+pub use external::{DiscordInfo, DiscordStatus};
+
+mod room;
+pub use room::RoomInfo;
+
+/// A GMCP message from the server, parsed according to its package.
+///
+/// Package names are matched case-insensitively. Messages from packages without a typed
+/// definition are kept as raw JSON in [`Package::Other`].
+///
+/// # Examples
+///
+/// ```
+/// use mud_transformer::opt::gmcp::{Message, Package};
+///
+/// let message = Message::decode(br#"Char.Vitals {"hp":"4500","maxhp":"5000"}"#).unwrap();
+/// let Ok(Package::CharVitals(vitals)) = Package::from_message(&message) else {
+///     panic!("expected Char.Vitals");
+/// };
+/// assert_eq!(vitals.hp, Some(4500));
+/// assert_eq!(vitals.max_hp, Some(5000));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Package {
+    CharVitals(Vitals),
+    CharStatus(Status),
+    CharItemsList(ItemList),
+    CharItemsAdd(ItemChange),
+    CharItemsRemove(ItemChange),
+    CharItemsUpdate(ItemChange),
+    RoomInfo(RoomInfo),
+    CommChannelText(ChannelText),
+    ClientMediaDefault(MediaDefault),
+    ClientMediaLoad(MediaLoad),
+    ClientMediaPlay(MediaPlay),
+    ClientMediaStop(MediaStop),
+    ExternalDiscordInfo(DiscordInfo),
+    ExternalDiscordStatus(DiscordStatus),
+    /// `Core.Goodbye`, with the reason the server is closing the connection.
+    CoreGoodbye(String),
+    /// A message from a package without a typed definition. `data` is [`Value::Null`] if the
+    /// message has no data.
+    Other {
+        command: String,
+        data: Value,
+    },
+}
+
+impl Package {
+    /// Parses the data of a message according to its command.
+    pub fn from_message<S: AsRef<str>>(message: &Message<S>) -> serde_json::Result<Self> {
+        let command = message.command.as_ref();
+        let data = match &message.data {
+            Some(data) => serde_json::from_str(data.as_ref())?,
+            None => Value::Null,
+        };
+        Ok(match command.to_ascii_lowercase().as_str() {
+            "char.vitals" => Self::CharVitals(parse(data)?),
+            "char.status" => Self::CharStatus(parse(data)?),
+            "char.items.list" => Self::CharItemsList(parse(data)?),
+            "char.items.add" => Self::CharItemsAdd(parse(data)?),
+            "char.items.remove" => Self::CharItemsRemove(parse(data)?),
+            "char.items.update" => Self::CharItemsUpdate(parse(data)?),
+            "room.info" => Self::RoomInfo(parse(data)?),
+            "comm.channel.text" => Self::CommChannelText(parse(data)?),
+            "client.media.default" => Self::ClientMediaDefault(parse(data)?),
+            "client.media.load" => Self::ClientMediaLoad(parse(data)?),
+            "client.media.play" => Self::ClientMediaPlay(parse(data)?),
+            "client.media.stop" => Self::ClientMediaStop(parse_or_default(data)?),
+            "external.discord.info" => Self::ExternalDiscordInfo(parse(data)?),
+            "external.discord.status" => Self::ExternalDiscordStatus(parse(data)?),
+            "core.goodbye" => Self::CoreGoodbye(parse_or_default(data)?),
+            _ => Self::Other {
+                command: command.to_owned(),
+                data,
+            },
+        })
+    }
+
+    /// The GMCP command of the message, such as `"Char.Vitals"`.
+    pub fn command(&self) -> &str {
+        match self {
+            Self::CharVitals(_) => "Char.Vitals",
+            Self::CharStatus(_) => "Char.Status",
+            Self::CharItemsList(_) => "Char.Items.List",
+            Self::CharItemsAdd(_) => "Char.Items.Add",
+            Self::CharItemsRemove(_) => "Char.Items.Remove",
+            Self::CharItemsUpdate(_) => "Char.Items.Update",
+            Self::RoomInfo(_) => "Room.Info",
+            Self::CommChannelText(_) => "Comm.Channel.Text",
+            Self::ClientMediaDefault(_) => "Client.Media.Default",
+            Self::ClientMediaLoad(_) => "Client.Media.Load",
+            Self::ClientMediaPlay(_) => "Client.Media.Play",
+            Self::ClientMediaStop(_) => "Client.Media.Stop",
+            Self::ExternalDiscordInfo(_) => "External.Discord.Info",
+            Self::ExternalDiscordStatus(_) => "External.Discord.Status",
+            Self::CoreGoodbye(_) => "Core.Goodbye",
+            Self::Other { command, .. } => command,
+        }
+    }
+
+    /// Encodes the package as a message, for servers and proxies that send GMCP.
+    pub fn to_message(&self) -> serde_json::Result<Message<String>> {
+        let command = self.command().to_owned();
+        match self {
+            Self::CharVitals(data) => Message::serialize(command, data),
+            Self::CharStatus(data) => Message::serialize(command, data),
+            Self::CharItemsList(data) => Message::serialize(command, data),
+            Self::CharItemsAdd(data)
+            | Self::CharItemsRemove(data)
+            | Self::CharItemsUpdate(data) => Message::serialize(command, data),
+            Self::RoomInfo(data) => Message::serialize(command, data),
+            Self::CommChannelText(data) => Message::serialize(command, data),
+            Self::ClientMediaDefault(data) => Message::serialize(command, data),
+            Self::ClientMediaLoad(data) => Message::serialize(command, data),
+            Self::ClientMediaPlay(data) => Message::serialize(command, data),
+            Self::ClientMediaStop(data) => Message::serialize(command, data),
+            Self::ExternalDiscordInfo(data) => Message::serialize(command, data),
+            Self::ExternalDiscordStatus(data) => Message::serialize(command, data),
+            Self::CoreGoodbye(data) => Message::serialize(command, data),
+            Self::Other {
+                data: Value::Null, ..
+            } => Ok(Message {
+                command,
+                data: None,
+            }),
+            Self::Other { data, .. } => Message::serialize(command, data),
+        }
+    }
+}
+
+impl<S: AsRef<str>> TryFrom<&Message<S>> for Package {
+    type Error = serde_json::Error;
+
+    fn try_from(message: &Message<S>) -> Result<Self, Self::Error> {
+        Self::from_message(message)
+    }
+}
+
+fn parse<T: DeserializeOwned>(data: Value) -> serde_json::Result<T> {
+    serde_json::from_value(data)
+}
+
+fn parse_or_default<T: DeserializeOwned + Default>(data: Value) -> serde_json::Result<T> {
+    Ok(parse::<Option<T>>(data)?.unwrap_or_default())
+}
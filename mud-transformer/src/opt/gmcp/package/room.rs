@@ -0,0 +1,38 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::lenient;
+
+/// `Room.Info`: the room the player's character is in.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomInfo {
+    /// Unique room number.
+    #[serde(default, deserialize_with = "lenient::int")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub area: Option<String>,
+    /// Terrain type, such as `"forest"` or `"urban"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+    /// Map coordinates, usually formatted as `"area,x,y,z"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coords: Option<String>,
+    /// URL of a map image, followed by the room's position in it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map: Option<String>,
+    /// Room features, such as `"shop"` or `"bank"`.
+    #[serde(default, deserialize_with = "lenient::strings")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+    /// Room numbers of adjacent rooms, keyed by direction, such as `"n"` or `"up"`.
+    #[serde(default, deserialize_with = "lenient::int_map")]
+    pub exits: BTreeMap<String, i64>,
+    /// Game-specific information.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
@@ -0,0 +1,145 @@
+use log::error;
+use serde_json::{Map, Value};
+
+use super::{Message, OPT};
+use crate::output::{Output, OutputFragment, TelnetFragment};
+
+/// Merged view of the data received through GMCP, keyed by package.
+///
+/// Each message is stored under its package name, split on dots, so `Char.Vitals {"hp":100}` is
+/// stored at `Char.Vitals.hp`. Objects are merged into the existing data, which allows servers to
+/// send only the fields that have changed. Any other value, including arrays, replaces the
+/// existing value.
+///
+/// Updates return the paths of the values that changed, so that a client can refresh only the
+/// parts of its interface that depend on them.
+///
+/// # Examples
+///
+/// ```
+/// use mud_transformer::opt::gmcp::{Message, State};
+///
+/// let mut state = State::new();
+/// let message = Message::decode(br#"Char.Vitals {"hp":100,"mp":50}"#).unwrap();
+/// state.update(&message).unwrap();
+/// let message = Message::decode(br#"Char.Vitals {"hp":90}"#).unwrap();
+/// let changed = state.update(&message).unwrap();
+///
+/// assert_eq!(changed, ["Char.Vitals.hp"]);
+/// assert_eq!(state.get("Char.Vitals.mp"), Some(&50.into()));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct State {
+    packages: Map<String, Value>,
+}
+
+impl State {
+    /// Creates an empty state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value at a dot-separated path, such as `Char.Vitals` or `Char.Vitals.hp`.
+    pub fn get(&self, path: &str) -> Option<&Value> {
+        let mut keys = path.split('.');
+        let mut value = self.packages.get(keys.next()?)?;
+        for key in keys {
+            value = value.as_object()?.get(key)?;
+        }
+        Some(value)
+    }
+
+    /// Top-level packages, such as `Char` and `Room`.
+    pub fn packages(&self) -> &Map<String, Value> {
+        &self.packages
+    }
+
+    /// Returns true if no data has been received.
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    /// Removes all data.
+    pub fn clear(&mut self) {
+        self.packages.clear();
+    }
+
+    /// Merges the data of a GMCP message into the state, returning the paths of the values that
+    /// changed. Messages without data, such as `Core.Ping`, change nothing.
+    pub fn update<S: AsRef<str>>(
+        &mut self,
+        message: &Message<S>,
+    ) -> serde_json::Result<Vec<String>> {
+        let mut changed = Vec::new();
+        let Some(data) = &message.data else {
+            return Ok(changed);
+        };
+        let data: Value = serde_json::from_str(data.as_ref())?;
+        let command = message.command.as_ref();
+        let mut keys = command.split('.');
+        let Some(first) = keys.next() else {
+            return Ok(changed);
+        };
+        let mut target = self.packages.entry(first).or_insert(Value::Null);
+        for key in keys {
+            if !target.is_object() {
+                *target = Value::Object(Map::new());
+            }
+            target = object_entry(target, key);
+        }
+        let mut path = command.to_owned();
+        merge(target, data, &mut path, &mut changed);
+        Ok(changed)
+    }
+
+    /// Updates the state if the output is a GMCP subnegotiation, returning the paths of the
+    /// values that changed. Invalid messages are logged and ignored.
+    pub fn apply(&mut self, output: &Output) -> Vec<String> {
+        let OutputFragment::Telnet(TelnetFragment::Subnegotiation { code: OPT, data }) =
+            &output.fragment
+        else {
+            return Vec::new();
+        };
+        let result = match Message::decode(data) {
+            Ok(message) => self.update(&message),
+            Err(e) => {
+                error!(target: "mud.telnet", "Error decoding GMCP message: {e}");
+                return Vec::new();
+            }
+        };
+        result.unwrap_or_else(|e| {
+            error!(target: "mud.telnet", "Error parsing GMCP data: {e}");
+            Vec::new()
+        })
+    }
+}
+
+fn object_entry<'a>(value: &'a mut Value, key: &str) -> &'a mut Value {
+    match value {
+        Value::Object(map) => map.entry(key).or_insert(Value::Null),
+        _ => unreachable!("value must be an object"),
+    }
+}
+
+fn merge(target: &mut Value, source: Value, path: &mut String, changed: &mut Vec<String>) {
+    let Value::Object(source) = source else {
+        if *target != source {
+            *target = source;
+            changed.push(path.clone());
+        }
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+        if source.is_empty() {
+            changed.push(path.clone());
+        }
+    }
+    let len = path.len();
+    for (key, value) in source {
+        path.push('.');
+        path.push_str(&key);
+        merge(object_entry(target, &key), value, path, changed);
+        path.truncate(len);
+    }
+}
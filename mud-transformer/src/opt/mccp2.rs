@@ -1,13 +1,23 @@
 use std::fmt;
 use std::io::BufRead;
 
-pub use zlib_rs::{Inflate, InflateError, InflateFlush, ReturnCode, Status};
+pub use zlib_rs::{
+    Deflate, DeflateError, DeflateFlush, Inflate, InflateError, InflateFlush, ReturnCode, Status,
+};
+
+use crate::escape::telnet;
 
 /// MUD Client Compression Protocol v2
 ///
 /// https://tintin.mudhalla.net/protocols/mccp/
 pub const OPT: u8 = 86;
 
+/// Subnegotiation sent by the server immediately before the compressed stream begins.
+pub const START: [u8; 5] = [telnet::IAC, telnet::SB, OPT, telnet::IAC, telnet::SE];
+
+/// zlib's default compression level.
+const DEFAULT_LEVEL: i32 = -1;
+
 /// The state that is used to decompress an input.
 pub(crate) struct Decompress {
     inner: Inflate,
@@ -68,6 +78,139 @@ impl Decompress {
     }
 }
 
+/// Compresses data for the receiving end of an MCCP connection, such as a client connected to a
+/// proxy or test server.
+///
+/// After sending [`start`](Self::start), everything the server sends must pass through
+/// [`compress`](Self::compress), which flushes after each call so that the client can display
+/// the output immediately. Data that does not need to be displayed right away can be passed to
+/// [`write`](Self::write) instead and flushed later. [`finish`](Self::finish) ends the compressed
+/// stream, after which data is sent uncompressed until compression is started again.
+///
+/// # Examples
+///
+/// ```
+/// use mud_transformer::opt::mccp2::Compressor;
+///
+/// let mut compressor = Compressor::new();
+/// let mut output = Vec::new();
+/// compressor.start(&mut output);
+/// compressor.compress(b"Welcome!\r\n", &mut output).unwrap();
+/// compressor.finish(&mut output).unwrap();
+/// ```
+pub struct Compressor {
+    inner: Deflate,
+    finished: bool,
+}
+
+impl fmt::Debug for Compressor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Compressor")
+            .field("total_in", &self.inner.total_in())
+            .field("total_out", &self.inner.total_out())
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Compressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compressor {
+    /// Creates a new compressor with zlib's default compression level.
+    pub fn new() -> Self {
+        Self::from_level(DEFAULT_LEVEL)
+    }
+
+    /// Creates a new compressor with a compression level from 0 (none) to 9 (best). Levels
+    /// above 9 are treated as 9.
+    pub fn with_level(level: u8) -> Self {
+        Self::from_level(i32::from(level.min(9)))
+    }
+
+    fn from_level(level: i32) -> Self {
+        Self {
+            inner: Deflate::new(level, true, 15),
+            finished: false,
+        }
+    }
+
+    /// Total number of uncompressed bytes consumed by the current stream.
+    pub fn total_in(&self) -> u64 {
+        self.inner.total_in()
+    }
+
+    /// Total number of compressed bytes produced by the current stream.
+    pub fn total_out(&self) -> u64 {
+        self.inner.total_out()
+    }
+
+    /// Appends `IAC SB MCCP2 IAC SE` to `output`. Everything sent after it must be compressed.
+    ///
+    /// If a previous stream was ended with [`finish`](Self::finish), a new one is begun.
+    pub fn start(&mut self, output: &mut Vec<u8>) {
+        if self.finished {
+            self.inner.reset();
+            self.finished = false;
+        }
+        output.extend_from_slice(&START);
+    }
+
+    /// Compresses `input` and appends the result to `output`, flushing so that the receiver can
+    /// decompress everything sent so far.
+    pub fn compress(&mut self, input: &[u8], output: &mut Vec<u8>) -> Result<(), DeflateError> {
+        self.deflate(input, output, DeflateFlush::SyncFlush)
+    }
+
+    /// Compresses `input` without flushing. Some or all of the result may be held back until the
+    /// next call to [`flush`](Self::flush), [`compress`](Self::compress), or
+    /// [`finish`](Self::finish).
+    pub fn write(&mut self, input: &[u8], output: &mut Vec<u8>) -> Result<(), DeflateError> {
+        self.deflate(input, output, DeflateFlush::NoFlush)
+    }
+
+    /// Flushes pending compressed data to `output`.
+    pub fn flush(&mut self, output: &mut Vec<u8>) -> Result<(), DeflateError> {
+        self.deflate(&[], output, DeflateFlush::SyncFlush)
+    }
+
+    /// Ends the compressed stream, appending pending compressed data and the stream's trailer to
+    /// `output`. Does nothing if the stream has already ended.
+    pub fn finish(&mut self, output: &mut Vec<u8>) -> Result<(), DeflateError> {
+        if self.finished {
+            return Ok(());
+        }
+        self.deflate(&[], output, DeflateFlush::Finish)?;
+        self.finished = true;
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn deflate(
+        &mut self,
+        mut input: &[u8],
+        output: &mut Vec<u8>,
+        flush: DeflateFlush,
+    ) -> Result<(), DeflateError> {
+        let mut buf = [0; 1024];
+        loop {
+            let total_in = self.inner.total_in();
+            let total_out = self.inner.total_out();
+            let status = self.inner.compress(input, &mut buf, flush)?;
+            let new_in = (self.inner.total_in() - total_in) as usize;
+            let new_out = (self.inner.total_out() - total_out) as usize;
+            input = &input[new_in..];
+            output.extend_from_slice(&buf[..new_out]);
+            if status != Status::Ok || (input.is_empty() && new_out < buf.len()) {
+                return Ok(());
+            }
+        }
+    }
+}
+
 #[allow(clippy::enum_variant_names)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(i32)]
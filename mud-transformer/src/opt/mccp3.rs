@@ -0,0 +1,7 @@
+/// MUD Client Compression Protocol v3
+///
+/// Input sent to the server is compressed in the same format as MCCP2 output, using
+/// [`mccp2::Compressor`](super::mccp2::Compressor).
+///
+/// https://tintin.mudhalla.net/protocols/mccp/
+pub const OPT: u8 = 87;
@@ -67,15 +67,14 @@ impl KnownVariable {
         write!(f, "\0{}\x01{}", self.as_str(), val)
     }
 
-    fn negotiate<W: fmt::Write>(self, f: W, config: &TransformerConfig) -> fmt::Result {
+    fn negotiate<W: fmt::Write>(
+        self,
+        f: W,
+        config: &TransformerConfig,
+        charset: &str,
+    ) -> fmt::Result {
         match self {
-            Self::Charset => {
-                if config.disable_utf8 {
-                    self.write(f, "ASCII")
-                } else {
-                    self.write(f, "UTF-8")
-                }
-            }
+            Self::Charset => self.write(f, charset),
             Self::ClientName => self.write(f, &config.terminal_identification),
             Self::ClientVersion => self.write(f, &config.version),
             Self::IpAddress => {
@@ -95,6 +94,7 @@ impl KnownVariable {
 pub(crate) struct Variables {
     set: FlagSet<KnownVariable>,
     prefix: &'static str,
+    charset: &'static str,
 }
 
 impl Default for Variables {
@@ -115,6 +115,7 @@ where
             Variables {
                 set,
                 prefix: "\0", // IS
+                charset: "UTF-8",
             }
         }
 
@@ -127,9 +128,33 @@ impl Variables {
         Self {
             set: FlagSet::empty(),
             prefix: "\0",
+            charset: "UTF-8",
         }
     }
 
+    #[must_use = "function returns a new value"]
+    pub const fn with_charset(mut self, charset: &'static str) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    /// Updates the charset to report. If the server has requested `CHARSET` and the charset has
+    /// changed, returns an update to send to the server.
+    pub fn update_charset(&mut self, charset: &'static str) -> Option<Self> {
+        if self.charset == charset {
+            return None;
+        }
+        self.charset = charset;
+        if !self.set.contains(KnownVariable::Charset) {
+            return None;
+        }
+        Some(Self {
+            set: KnownVariable::Charset.into(),
+            prefix: "\x02", // INFO
+            charset,
+        })
+    }
+
     pub fn is_empty(self) -> bool {
         self.set.is_empty()
     }
@@ -141,9 +166,6 @@ impl Variables {
     pub fn changes(self, a: &TransformerConfig, b: &TransformerConfig) -> Self {
         let mut changes = FlagSet::default();
 
-        if self.set.contains(KnownVariable::Charset) && a.disable_utf8 != b.disable_utf8 {
-            changes |= KnownVariable::Charset;
-        }
         if self.set.contains(KnownVariable::ClientName)
             && a.terminal_identification != b.terminal_identification
         {
@@ -165,6 +187,7 @@ impl Variables {
         Self {
             set: changes,
             prefix: "\x02", // INFO
+            charset: self.charset,
         }
     }
 }
@@ -175,7 +198,7 @@ impl Negotiate for Variables {
     fn negotiate<W: fmt::Write>(self, mut f: W, config: &TransformerConfig) -> fmt::Result {
         f.write_str(self.prefix)?;
         for variable in self.set {
-            variable.negotiate(&mut f, config)?;
+            variable.negotiate(&mut f, config, self.charset)?;
         }
         Ok(())
     }
@@ -24,6 +24,9 @@ pub use mssp::OPT as MSSP; // 70
 pub mod mccp2;
 pub use mccp2::OPT as MCCP2; // 86
 
+pub mod mccp3;
+pub use mccp3::OPT as MCCP3; // 87
+
 pub mod msp;
 pub use msp::OPT as MSP; // 90
 
@@ -179,11 +182,6 @@ pub const FORWARD_X: u8 = 49;
 /// https://tintin.mudhalla.net/protocols/mccp/
 pub const MCCP: u8 = 85;
 
-/// MUD Client Compression Protocol v3
-///
-/// https://tintin.mudhalla.net/protocols/mccp/
-pub const MCCP3: u8 = 87;
-
 /// Zenith MUD Protocol
 ///
 /// https://discworld.starturtle.net/external/protocols/zmp.html
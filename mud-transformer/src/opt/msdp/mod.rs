@@ -8,6 +8,9 @@ use bytes::{Buf, Bytes};
 
 use crate::escape::telnet;
 
+mod session;
+pub use session::{Change, Session};
+
 /// MUD Server Data Protocol
 ///
 /// https://tintin.mudhalla.net/protocols/msdp/
@@ -50,6 +53,14 @@ impl fmt::Debug for Value {
 }
 
 impl Value {
+    /// Returns the bytes of the value if it is a string.
+    pub fn as_bytes(&self) -> Option<&Bytes> {
+        match self {
+            Self::String(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
     pub fn encode<W: Write>(&self, mut writer: W) -> io::Result<()> {
         self.write_to(&mut writer)
     }
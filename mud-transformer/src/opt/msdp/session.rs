@@ -0,0 +1,249 @@
+use std::collections::{BTreeSet, HashMap};
+use std::vec;
+
+use bytes::Bytes;
+
+use super::{Command, Data, OPT, Value};
+use crate::output::{Output, OutputFragment, TelnetFragment};
+
+const REPORTABLE_VARIABLES: &[u8] = b"REPORTABLE_VARIABLES";
+
+/// A value received through MSDP that differs from the previous value of the variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// Name of the variable.
+    pub variable: Bytes,
+    /// Keys of the nested tables containing the value that changed, or empty if the variable is
+    /// not a table.
+    pub path: Vec<Bytes>,
+}
+
+/// Tracks the MSDP variables the client has subscribed to and their latest values.
+///
+/// Outputs are fed to the session with [`apply`](Self::apply) as they are drained from the
+/// transformer, which returns the values that changed. Commands generated by the session, such as
+/// `REPORT` requests, are collected until they are retrieved with
+/// [`drain_commands`](Self::drain_commands) and sent to the server.
+///
+/// Subscriptions are kept for the lifetime of the session. Whenever MSDP is negotiated, including
+/// after the server disables and re-enables it, every subscribed variable is requested again.
+/// After the connection is lost, [`reset`](Self::reset) prepares the session for a new
+/// connection.
+///
+/// # Examples
+///
+/// ```
+/// use mud_transformer::escape::telnet;
+/// use mud_transformer::opt::msdp;
+/// use mud_transformer::{Transformer, TransformerConfig};
+///
+/// let mut config = TransformerConfig::new();
+/// config.will.insert(msdp::OPT);
+/// let mut transformer = Transformer::new(config);
+/// let mut session = msdp::Session::new();
+/// session.report(["HEALTH"]);
+///
+/// transformer.receive(&[telnet::IAC, telnet::WILL, msdp::OPT], &mut [0; 1024]);
+/// for output in transformer.flush_output() {
+///     session.apply(&output);
+/// }
+/// let commands: Vec<u8> = session.drain_commands().collect();
+/// assert_eq!(commands, b"\xFF\xFA\x45\x01REPORT\x02HEALTH\xFF\xF0");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Session {
+    active: bool,
+    commands: Vec<u8>,
+    reported: Vec<Bytes>,
+    reportable: Vec<Bytes>,
+    values: HashMap<Bytes, Value>,
+}
+
+impl Session {
+    /// Creates a session with no subscriptions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if MSDP has been negotiated with the server.
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    /// Returns the latest value received for a variable.
+    pub fn get<K: AsRef<[u8]>>(&self, variable: K) -> Option<&Value> {
+        self.values.get(variable.as_ref())
+    }
+
+    /// Latest values received for every variable.
+    pub fn values(&self) -> &HashMap<Bytes, Value> {
+        &self.values
+    }
+
+    /// Variables that have been subscribed to with [`report`](Self::report), in order of
+    /// subscription.
+    pub fn reported(&self) -> &[Bytes] {
+        &self.reported
+    }
+
+    /// Variables the server has listed in response to
+    /// [`list_reportable`](Self::list_reportable).
+    pub fn reportable(&self) -> &[Bytes] {
+        &self.reportable
+    }
+
+    /// Subscribes to updates for variables. The server is sent a `REPORT` command for variables
+    /// that were not already subscribed to, now if MSDP is active or else once it is negotiated.
+    pub fn report<I>(&mut self, variables: I)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let added: Vec<Bytes> = variables
+            .into_iter()
+            .filter(|variable| !self.is_reported(variable.as_ref()))
+            .map(|variable| Bytes::copy_from_slice(variable.as_ref()))
+            .collect();
+        if added.is_empty() {
+            return;
+        }
+        if self.active {
+            self.write(Command::report(&added));
+        }
+        self.reported.extend(added);
+    }
+
+    /// Unsubscribes from updates for variables. The server is sent an `UNREPORT` command for
+    /// variables that were subscribed to, if MSDP is active.
+    pub fn unreport<I>(&mut self, variables: I)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let removed: Vec<Bytes> = variables
+            .into_iter()
+            .filter(|variable| self.is_reported(variable.as_ref()))
+            .map(|variable| Bytes::copy_from_slice(variable.as_ref()))
+            .collect();
+        if removed.is_empty() {
+            return;
+        }
+        self.reported.retain(|variable| !removed.contains(variable));
+        if self.active {
+            self.write(Command::unreport(&removed));
+        }
+    }
+
+    /// Asks the server for the variables that can be reported. The response is available from
+    /// [`reportable`](Self::reportable) once it has been received.
+    pub fn list_reportable(&mut self) {
+        self.write(Command::list(REPORTABLE_VARIABLES));
+    }
+
+    /// Removes all commands that have not yet been sent to the server, returning them as bytes.
+    pub fn drain_commands(&mut self) -> vec::Drain<'_, u8> {
+        self.commands.drain(..)
+    }
+
+    /// Prepares the session for a new connection. Received values and unsent commands are
+    /// discarded, and subscriptions are sent again once MSDP is negotiated.
+    pub fn reset(&mut self) {
+        self.active = false;
+        self.commands.clear();
+        self.reportable.clear();
+        self.values.clear();
+    }
+
+    /// Updates the session from an output of the transformer, returning the values that changed.
+    pub fn apply(&mut self, output: &Output) -> Vec<Change> {
+        let OutputFragment::Telnet(fragment) = &output.fragment else {
+            return Vec::new();
+        };
+        match fragment {
+            TelnetFragment::Msdp { enabled: true } => {
+                self.activate();
+                Vec::new()
+            }
+            TelnetFragment::Msdp { enabled: false } => {
+                self.active = false;
+                Vec::new()
+            }
+            TelnetFragment::Subnegotiation { code: OPT, data } => self.receive(data.clone()),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Updates the session from the data of an MSDP subnegotiation, returning the values that
+    /// changed.
+    pub fn receive(&mut self, mut data: Bytes) -> Vec<Change> {
+        let mut changes = Vec::new();
+        while let Some((variable, value)) = Data::take_var_and_value(&mut data) {
+            let value = value.into_value();
+            if variable == REPORTABLE_VARIABLES {
+                self.reportable = match &value {
+                    Value::Array(values) => {
+                        values.iter().filter_map(Value::as_bytes).cloned().collect()
+                    }
+                    Value::String(value) => vec![value.clone()],
+                    Value::Table(_) => Vec::new(),
+                };
+            }
+            let mut path = Vec::new();
+            diff(
+                self.values.get(&variable),
+                Some(&value),
+                &mut path,
+                &mut |path| {
+                    changes.push(Change {
+                        variable: variable.clone(),
+                        path: path.to_vec(),
+                    });
+                },
+            );
+            self.values.insert(variable, value);
+        }
+        changes
+    }
+
+    fn activate(&mut self) {
+        if self.active {
+            return;
+        }
+        self.active = true;
+        if !self.reported.is_empty() {
+            Command::report(&self.reported)
+                .write(&mut self.commands)
+                .unwrap();
+        }
+    }
+
+    fn is_reported(&self, variable: &[u8]) -> bool {
+        self.reported.iter().any(|reported| reported == variable)
+    }
+
+    fn write<I>(&mut self, command: Command<'_, I>)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        command.write(&mut self.commands).unwrap();
+    }
+}
+
+fn diff<F>(old: Option<&Value>, new: Option<&Value>, path: &mut Vec<Bytes>, changed: &mut F)
+where
+    F: FnMut(&[Bytes]),
+{
+    let (Some(Value::Table(old)), Some(Value::Table(new))) = (old, new) else {
+        if old != new {
+            changed(path);
+        }
+        return;
+    };
+    let keys: BTreeSet<&Bytes> = old.keys().chain(new.keys()).collect();
+    for key in keys {
+        path.push(key.clone());
+        diff(old.get(key), new.get(key), path, changed);
+        path.pop();
+    }
+}
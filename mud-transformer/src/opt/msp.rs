@@ -1,8 +1,33 @@
 /// MUD Sound Protocol
 ///
+/// Once MSP is negotiated, `!!SOUND(...)` and `!!MUSIC(...)` triggers at the start of a line are
+/// removed from the text and emitted as [`MxpFragment::Sound`] and [`MxpFragment::Music`]. To
+/// negotiate MSP, add this option to [`TransformerConfig::will`].
+///
 /// https://www.zuggsoft.com/zmud/msp.htm
+///
+/// [`MxpFragment::Sound`]: crate::output::MxpFragment::Sound
+/// [`MxpFragment::Music`]: crate::output::MxpFragment::Music
+/// [`TransformerConfig::will`]: crate::TransformerConfig::will
 pub const OPT: u8 = 90;
 
+const TRIGGERS: [&[u8]; 2] = [b"!!SOUND(", b"!!MUSIC("];
+
+/// Maximum length of a trigger, after which the text is treated as ordinary text.
+pub(crate) const MAX_TRIGGER_LEN: usize = 1024;
+
+/// Returns true if `bytes` are the start of a trigger, or a complete trigger.
+pub(crate) fn is_trigger_prefix(bytes: &[u8]) -> bool {
+    TRIGGERS
+        .iter()
+        .any(|trigger| trigger.starts_with(bytes) || bytes.starts_with(trigger))
+}
+
+/// Returns true if `bytes` are a complete trigger.
+pub(crate) fn is_trigger(bytes: &[u8]) -> bool {
+    bytes.len() > TRIGGERS[0].len() && bytes.ends_with(b")") && is_trigger_prefix(bytes)
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Element<S> {
     Music(mxp::Music<S>),
@@ -3,6 +3,9 @@ use std::{fmt, slice};
 
 use crate::count_bytes;
 
+mod record;
+pub use record::MsspRecord;
+
 /// MUD Server Status Protocol
 ///
 /// https://tintin.mudhalla.net/protocols/mssp/
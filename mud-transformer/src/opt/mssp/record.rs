@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use super::{OPT, VAL, VAR, decode};
+use crate::escape::telnet;
+use crate::protocol::write_escaping_iac;
+
+const PLAINTEXT_START: &str = "MSSP-REPLY-START";
+const PLAINTEXT_END: &str = "MSSP-REPLY-END";
+
+/// A value of a standard MSSP variable.
+trait Parse: Sized {
+    fn parse(value: &str) -> Option<Self>;
+
+    fn format(&self) -> String;
+}
+
+impl Parse for String {
+    fn parse(value: &str) -> Option<Self> {
+        Some(value.to_owned())
+    }
+
+    fn format(&self) -> String {
+        self.clone()
+    }
+}
+
+impl Parse for bool {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "1" => Some(true),
+            "0" => Some(false),
+            _ => None,
+        }
+    }
+
+    fn format(&self) -> String {
+        if *self { "1" } else { "0" }.to_owned()
+    }
+}
+
+macro_rules! impl_parse {
+    ($t:ty) => {
+        impl Parse for $t {
+            fn parse(value: &str) -> Option<Self> {
+                value.trim().parse().ok()
+            }
+
+            fn format(&self) -> String {
+                self.to_string()
+            }
+        }
+    };
+}
+
+impl_parse!(u16);
+impl_parse!(u32);
+impl_parse!(u64);
+impl_parse!(i64);
+
+/// A field of [`MsspRecord`], which holds either a single value or a list of values.
+trait Field {
+    /// Returns false if a value could not be parsed.
+    fn set(&mut self, values: &[&str]) -> bool;
+
+    fn values(&self) -> Vec<String>;
+}
+
+impl<T: Parse> Field for Option<T> {
+    fn set(&mut self, values: &[&str]) -> bool {
+        match values.first().map(|value| T::parse(value)) {
+            Some(Some(value)) => {
+                *self = Some(value);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn values(&self) -> Vec<String> {
+        self.iter().map(T::format).collect()
+    }
+}
+
+impl<T: Parse> Field for Vec<T> {
+    fn set(&mut self, values: &[&str]) -> bool {
+        let parsed: Option<Vec<T>> = values
+            .iter()
+            .filter(|value| !value.is_empty())
+            .map(|value| T::parse(value))
+            .collect();
+        let Some(parsed) = parsed else {
+            return false;
+        };
+        self.extend(parsed);
+        true
+    }
+
+    fn values(&self) -> Vec<String> {
+        self.iter().map(T::format).collect()
+    }
+}
+
+macro_rules! mssp_record {
+    ($($(#[$meta:meta])* $var:literal => $field:ident: $t:ty,)*) => {
+        /// Information about a MUD sent through the MUD Server Status Protocol.
+        ///
+        /// Standard variables are parsed into typed fields. Numbers and booleans (sent as `1` or
+        /// `0`) that cannot be parsed, along with nonstandard variables, are kept as strings in
+        /// [`other`](Self::other). Variables with several values, such as `PORT`, may be sent
+        /// either as one variable with multiple values or as repeated variables.
+        ///
+        /// See <https://tintin.mudhalla.net/protocols/mssp/> for the meaning of each variable.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use mud_transformer::opt::mssp::MsspRecord;
+        ///
+        /// let record = MsspRecord::decode(b"\x01NAME\x02Elsewhere\x01PLAYERS\x0212\x01PORT\x024000\x025000");
+        /// assert_eq!(record.name.as_deref(), Some("Elsewhere"));
+        /// assert_eq!(record.players, Some(12));
+        /// assert_eq!(record.port, [4000, 5000]);
+        /// ```
+        #[derive(Clone, Debug, Default, PartialEq, Eq)]
+        pub struct MsspRecord {
+            $($(#[$meta])* pub $field: $t,)*
+            /// Nonstandard variables and values that could not be parsed, keyed by variable name.
+            pub other: BTreeMap<String, Vec<String>>,
+        }
+
+        impl MsspRecord {
+            /// Sets a variable, appending to its values if it has several. Variable names are
+            /// case-insensitive.
+            pub fn insert<S: AsRef<str>>(&mut self, var: &str, values: &[S]) {
+                let values: Vec<&str> = values.iter().map(AsRef::as_ref).collect();
+                let parsed = match var.to_ascii_uppercase().as_str() {
+                    $($var => self.$field.set(&values),)*
+                    _ => false,
+                };
+                if !parsed {
+                    self.other
+                        .entry(var.to_owned())
+                        .or_default()
+                        .extend(values.iter().map(|&value| value.to_owned()));
+                }
+            }
+
+            /// Every variable that has a value, with its values.
+            pub fn entries(&self) -> Vec<(&str, Vec<String>)> {
+                let mut entries = Vec::new();
+                $(
+                    let values = self.$field.values();
+                    if !values.is_empty() {
+                        entries.push(($var, values));
+                    }
+                )*
+                for (var, values) in &self.other {
+                    entries.push((var.as_str(), values.clone()));
+                }
+                entries
+            }
+        }
+    };
+}
+
+mssp_record! {
+    /// Name of the MUD.
+    "NAME" => name: Option<String>,
+    /// Number of players currently online.
+    "PLAYERS" => players: Option<u32>,
+    /// Unix time at which the MUD was last started.
+    "UPTIME" => uptime: Option<u64>,
+    /// Codebases the MUD is derived from, such as `"Merc 2.2"`.
+    "CODEBASE" => codebase: Vec<String>,
+    /// Email address for contacting the MUD's administration.
+    "CONTACT" => contact: Option<String>,
+    /// Preferred minimum number of hours between crawls, or -1 for the crawler's default.
+    "CRAWL DELAY" => crawl_delay: Option<i64>,
+    /// Year the MUD was created.
+    "CREATED" => created: Option<String>,
+    /// Invitation link to the MUD's Discord server.
+    "DISCORD" => discord: Option<String>,
+    "HOSTNAME" => hostname: Option<String>,
+    /// URL of a 32x32 image representing the MUD.
+    "ICON" => icon: Option<String>,
+    "IP" => ip: Option<String>,
+    "IPV6" => ipv6: Option<String>,
+    /// Language used by the MUD, such as `"English"`.
+    "LANGUAGE" => language: Option<String>,
+    /// Country the server is located in.
+    "LOCATION" => location: Option<String>,
+    /// Minimum age of players.
+    "MINIMUM AGE" => minimum_age: Option<u32>,
+    /// Ports for telnet connections.
+    "PORT" => port: Vec<u16>,
+    /// Hostnames and ports of other MUDs recommended by this one.
+    "REFERRAL" => referral: Vec<String>,
+    /// Port for TLS connections.
+    "SSL" => ssl: Option<u16>,
+    "WEBSITE" => website: Option<String>,
+    /// Family of codebases, such as `"DikuMUD"`.
+    "FAMILY" => family: Vec<String>,
+    /// Genres, such as `"Fantasy"`.
+    "GENRE" => genre: Vec<String>,
+    /// Styles of gameplay, such as `"Hack and Slash"` or `"Roleplaying"`.
+    "GAMEPLAY" => gameplay: Vec<String>,
+    /// Development status, such as `"Alpha"` or `"Live"`.
+    "STATUS" => status: Option<String>,
+    /// Rules system, such as `"D&D"`.
+    "GAMESYSTEM" => gamesystem: Vec<String>,
+    /// Intermud protocols the MUD is connected to.
+    "INTERMUD" => intermud: Vec<String>,
+    /// Subgenres, such as `"High Fantasy"`.
+    "SUBGENRE" => subgenre: Vec<String>,
+    "AREAS" => areas: Option<i64>,
+    "HELPFILES" => helpfiles: Option<i64>,
+    "MOBILES" => mobiles: Option<i64>,
+    "OBJECTS" => objects: Option<i64>,
+    "ROOMS" => rooms: Option<i64>,
+    "CLASSES" => classes: Option<i64>,
+    "LEVELS" => levels: Option<i64>,
+    "RACES" => races: Option<i64>,
+    "SKILLS" => skills: Option<i64>,
+    "ANSI" => ansi: Option<bool>,
+    "GMCP" => gmcp: Option<bool>,
+    "MCCP" => mccp: Option<bool>,
+    "MCP" => mcp: Option<bool>,
+    "MSDP" => msdp: Option<bool>,
+    "MSP" => msp: Option<bool>,
+    "MXP" => mxp: Option<bool>,
+    "PUEBLO" => pueblo: Option<bool>,
+    "UTF-8" => utf8: Option<bool>,
+    "VT100" => vt100: Option<bool>,
+    "XTERM 256 COLORS" => xterm_256_colors: Option<bool>,
+    "XTERM TRUE COLORS" => xterm_true_colors: Option<bool>,
+    "PAY TO PLAY" => pay_to_play: Option<bool>,
+    "PAY FOR PERKS" => pay_for_perks: Option<bool>,
+    "HIRING BUILDERS" => hiring_builders: Option<bool>,
+    "HIRING CODERS" => hiring_coders: Option<bool>,
+}
+
+impl MsspRecord {
+    /// Creates an empty record.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes the data of an MSSP subnegotiation. Invalid UTF-8 is replaced.
+    pub fn decode(data: &[u8]) -> Self {
+        let mut record = Self::new();
+        for (var, val) in decode(data) {
+            let values: Vec<_> = val
+                .split(|&c| c == VAL)
+                .map(String::from_utf8_lossy)
+                .collect();
+            record.insert(&String::from_utf8_lossy(var), &values);
+        }
+        record
+    }
+
+    /// Parses the reply to a plaintext `MSSP-REQUEST`, which lists variables between
+    /// `MSSP-REPLY-START` and `MSSP-REPLY-END`, one per line, with values separated by tabs.
+    ///
+    /// Returns `None` if the text does not contain a complete reply.
+    pub fn parse_plaintext(text: &str) -> Option<Self> {
+        let start = text.find(PLAINTEXT_START)? + PLAINTEXT_START.len();
+        let len = text[start..].find(PLAINTEXT_END)?;
+        let mut record = Self::new();
+        for line in text[start..start + len].lines() {
+            let mut values = line.trim_end_matches('\r').split('\t');
+            let Some(var) = values.next().filter(|var| !var.is_empty()) else {
+                continue;
+            };
+            let values: Vec<&str> = values.collect();
+            record.insert(var, &values);
+        }
+        Some(record)
+    }
+
+    /// Writes the record as an MSSP subnegotiation, including IAC prefix and suffix.
+    ///
+    /// Returns an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput), without writing
+    /// anything, if a variable name or value contains an `MSSP_VAR` or `MSSP_VAL` byte.
+    pub fn encode<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let entries = self.entries();
+        for (var, values) in &entries {
+            check_delimiters(var)?;
+            for value in values {
+                check_delimiters(value)?;
+            }
+        }
+        writer.write_all(&[telnet::IAC, telnet::SB, OPT])?;
+        for (var, values) in entries {
+            writer.write_all(&[VAR])?;
+            write_escaping_iac(&mut writer, var.as_bytes())?;
+            for value in values {
+                writer.write_all(&[VAL])?;
+                write_escaping_iac(&mut writer, value.as_bytes())?;
+            }
+        }
+        writer.write_all(&[telnet::IAC, telnet::SE])
+    }
+
+    /// Writes the record as a reply to a plaintext `MSSP-REQUEST`.
+    pub fn encode_plaintext<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "\r\n{PLAINTEXT_START}\r\n")?;
+        for (var, values) in self.entries() {
+            writer.write_all(var.as_bytes())?;
+            for value in values {
+                write!(writer, "\t{value}")?;
+            }
+            writer.write_all(b"\r\n")?;
+        }
+        write!(writer, "{PLAINTEXT_END}\r\n")
+    }
+}
+
+fn check_delimiters(s: &str) -> io::Result<()> {
+    if s.bytes().any(|c| c == VAR || c == VAL) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("MSSP delimiter in {s:?}"),
+        ));
+    }
+    Ok(())
+}
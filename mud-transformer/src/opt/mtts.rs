@@ -1,7 +1,7 @@
 use std::fmt;
 
 use crate::protocol::Negotiate;
-use crate::transformer::TransformerConfig;
+use crate::transformer::{Encoding, TransformerConfig};
 
 /// MUD Terminal Type Standard
 ///
@@ -52,7 +52,10 @@ pub(crate) const fn bitmask(config: &TransformerConfig) -> u16 {
     }
 
     ANSI | mask(config.console_controls, VT100)
-        | mask(!config.disable_utf8, UTF_8)
+        | mask(
+            !config.disable_utf8 && matches!(config.encoding, Encoding::Utf8),
+            UTF_8,
+        )
         | XTERM_COLORS
         | mask(config.mouse_tracking, MOUSE_TRACKING)
         | OSC_COLOR_PALETTE
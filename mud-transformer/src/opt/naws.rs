@@ -1,6 +1,7 @@
 use std::io::{self, Write};
 
 use crate::escape::telnet;
+use crate::protocol::write_escaping_iac;
 
 /// [RFC 1073](https://datatracker.ietf.org/doc/html/rfc1073): Negotiate About Window Size
 pub const OPT: u8 = 31;
@@ -12,27 +13,26 @@ pub struct WindowSize {
 }
 
 impl WindowSize {
-    pub const fn subnegotiation(self) -> [u8; 9] {
-        let [width_high, width_low] = self.width.to_be_bytes();
-        let [height_high, height_low] = self.height.to_be_bytes();
-        [
-            telnet::IAC,
-            telnet::SB,
-            OPT,
-            width_high,
-            width_low,
-            height_high,
-            height_low,
-            telnet::IAC,
-            telnet::SE,
-        ]
+    /// Returns the NAWS subnegotiation for the window size, including IAC prefix and suffix.
+    /// Dimension bytes equal to `IAC` are escaped.
+    pub fn subnegotiation(self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9);
+        self.encode(&mut buf).unwrap();
+        buf
     }
 
     pub fn encode<W: Write>(self, mut writer: W) -> io::Result<()> {
-        writer.write_all(&self.subnegotiation())
+        let [width_high, width_low] = self.width.to_be_bytes();
+        let [height_high, height_low] = self.height.to_be_bytes();
+        writer.write_all(&[telnet::IAC, telnet::SB, OPT])?;
+        write_escaping_iac(
+            &mut writer,
+            &[width_high, width_low, height_high, height_low],
+        )?;
+        writer.write_all(&[telnet::IAC, telnet::SE])
     }
 }
 
-pub const fn subnegotiate(width: u16, height: u16) -> [u8; 9] {
+pub fn subnegotiate(width: u16, height: u16) -> Vec<u8> {
     WindowSize { width, height }.subnegotiation()
 }
@@ -0,0 +1,276 @@
+use mxp::AudioRepetition;
+
+use super::{MxpFragment, Output, OutputFragment};
+
+/// Channel on which a cue is played. Each channel plays at most one cue at a time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AudioChannel {
+    Sound,
+    Music,
+}
+
+/// A sound effect or piece of music to play.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AudioCue {
+    Sound(mxp::Sound),
+    Music(mxp::Music),
+}
+
+impl AudioCue {
+    pub fn channel(&self) -> AudioChannel {
+        match self {
+            Self::Sound(_) => AudioChannel::Sound,
+            Self::Music(_) => AudioChannel::Music,
+        }
+    }
+
+    /// File name of the cue.
+    pub fn fname(&self) -> &str {
+        match self {
+            Self::Sound(sound) => &sound.fname,
+            Self::Music(music) => &music.fname,
+        }
+    }
+
+    /// URL from which the file can be downloaded if it is not available locally.
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Self::Sound(sound) => sound.url.as_deref(),
+            Self::Music(music) => music.url.as_deref(),
+        }
+    }
+
+    fn repeat(&self) -> AudioRepetition {
+        match self {
+            Self::Sound(sound) => sound.repeat,
+            Self::Music(music) => music.repeat,
+        }
+    }
+}
+
+/// Instruction to the frontend's audio player.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AudioEvent {
+    /// Start playing a cue from the beginning.
+    Start(AudioCue),
+    /// Stop the cue that is playing on a channel.
+    Stop(AudioChannel),
+    /// The cue that is playing was requested again and should continue without restarting.
+    /// Its volume may have changed.
+    Keep(AudioCue),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Playing {
+    cue: AudioCue,
+    /// Number of times the cue has yet to be played, including the current play, or `None` if it
+    /// repeats forever.
+    plays_left: Option<u32>,
+}
+
+impl Playing {
+    fn new(cue: AudioCue) -> Self {
+        let plays_left = match cue.repeat() {
+            AudioRepetition::Forever => None,
+            AudioRepetition::Count(count) => Some(count.get()),
+        };
+        Self { cue, plays_left }
+    }
+}
+
+/// Tracks the sounds and music requested through MXP and MSP, and decides what the frontend
+/// should play.
+///
+/// Outputs are fed to the state with [`apply`](Self::apply), which returns the cues to start,
+/// stop or keep playing. The state does not play audio itself, so the frontend must call
+/// [`finished`](Self::finished) whenever a cue reaches its end, which replays cues that repeat.
+///
+/// The following rules are applied:
+///
+/// - A sound replaces the sound that is playing only if it has a higher priority. In the case of a
+///   tie, the sound that is already playing wins.
+/// - Music always replaces the music that is playing, unless it is the same file and requested
+///   as continual, in which case it continues without restarting.
+/// - Cues are repeated the requested number of times, or forever if the repeat count is -1.
+/// - A request to turn sound or music off with a URL sets the default URL for the channel, which
+///   is used for subsequent cues that do not specify a URL.
+///
+/// # Examples
+///
+/// ```
+/// use mud_transformer::output::{AudioChannel, AudioEvent, AudioState, MxpFragment};
+///
+/// let mut audio = AudioState::new();
+/// let rain = mxp::Sound {
+///     fname: "rain.wav".to_owned(),
+///     priority: 20,
+///     ..Default::default()
+/// };
+/// let thunder = mxp::Sound {
+///     fname: "thunder.wav".to_owned(),
+///     priority: 80,
+///     ..Default::default()
+/// };
+/// audio.apply_fragment(&MxpFragment::Sound(rain));
+/// let events = audio.apply_fragment(&MxpFragment::Sound(thunder));
+/// assert_eq!(events[0], AudioEvent::Stop(AudioChannel::Sound));
+/// assert_eq!(audio.playing(AudioChannel::Sound).unwrap().fname(), "thunder.wav");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudioState {
+    sound: Option<Playing>,
+    music: Option<Playing>,
+    sound_url: Option<String>,
+    music_url: Option<String>,
+}
+
+impl AudioState {
+    /// Creates a state in which nothing is playing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cue that is playing on a channel.
+    pub fn playing(&self, channel: AudioChannel) -> Option<&AudioCue> {
+        let playing = match channel {
+            AudioChannel::Sound => &self.sound,
+            AudioChannel::Music => &self.music,
+        };
+        playing.as_ref().map(|playing| &playing.cue)
+    }
+
+    /// Returns the default URL for cues on a channel that do not specify a URL.
+    pub fn default_url(&self, channel: AudioChannel) -> Option<&str> {
+        match channel {
+            AudioChannel::Sound => self.sound_url.as_deref(),
+            AudioChannel::Music => self.music_url.as_deref(),
+        }
+    }
+
+    /// Updates the state from an output of the transformer, returning instructions for the
+    /// frontend.
+    pub fn apply(&mut self, output: &Output) -> Vec<AudioEvent> {
+        match &output.fragment {
+            OutputFragment::Mxp(fragment) => self.apply_fragment(fragment),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Updates the state from an MXP fragment, returning instructions for the frontend.
+    pub fn apply_fragment(&mut self, fragment: &MxpFragment) -> Vec<AudioEvent> {
+        match fragment {
+            MxpFragment::Sound(sound) if sound.fname.eq_ignore_ascii_case("off") => {
+                match &sound.url {
+                    Some(url) => {
+                        self.sound_url = Some(url.clone());
+                        Vec::new()
+                    }
+                    None => self.stop(AudioChannel::Sound),
+                }
+            }
+            MxpFragment::Music(music) if music.fname.eq_ignore_ascii_case("off") => {
+                match &music.url {
+                    Some(url) => {
+                        self.music_url = Some(url.clone());
+                        Vec::new()
+                    }
+                    None => self.stop(AudioChannel::Music),
+                }
+            }
+            MxpFragment::Sound(sound) => {
+                let mut sound = sound.clone();
+                if sound.url.is_none() {
+                    sound.url.clone_from(&self.sound_url);
+                }
+                self.play_sound(sound)
+            }
+            MxpFragment::Music(music) => {
+                let mut music = music.clone();
+                if music.url.is_none() {
+                    music.url.clone_from(&self.music_url);
+                }
+                self.play_music(music)
+            }
+            MxpFragment::SoundOff => self.stop(AudioChannel::Sound),
+            MxpFragment::MusicOff => self.stop(AudioChannel::Music),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Notifies the state that the cue on a channel has reached its end. If the cue repeats, it is
+    /// started again.
+    pub fn finished(&mut self, channel: AudioChannel) -> Vec<AudioEvent> {
+        let slot = self.channel_mut(channel);
+        let Some(playing) = slot else {
+            return Vec::new();
+        };
+        match &mut playing.plays_left {
+            None => vec![AudioEvent::Start(playing.cue.clone())],
+            Some(plays_left) if *plays_left > 1 => {
+                *plays_left -= 1;
+                vec![AudioEvent::Start(playing.cue.clone())]
+            }
+            Some(_) => {
+                *slot = None;
+                Vec::new()
+            }
+        }
+    }
+
+    /// Stops all cues and forgets default URLs, such as after the connection is lost. Returns
+    /// instructions to stop the cues that were playing.
+    pub fn reset(&mut self) -> Vec<AudioEvent> {
+        let mut events = self.stop(AudioChannel::Sound);
+        events.extend(self.stop(AudioChannel::Music));
+        self.sound_url = None;
+        self.music_url = None;
+        events
+    }
+
+    fn play_sound(&mut self, sound: mxp::Sound) -> Vec<AudioEvent> {
+        if let Some(Playing {
+            cue: AudioCue::Sound(current),
+            ..
+        }) = &self.sound
+            && sound.priority <= current.priority
+        {
+            return Vec::new();
+        }
+        self.start(AudioCue::Sound(sound))
+    }
+
+    fn play_music(&mut self, music: mxp::Music) -> Vec<AudioEvent> {
+        if let Some(playing) = &mut self.music
+            && let AudioCue::Music(current) = &playing.cue
+            && music.continual
+            && music.fname.eq_ignore_ascii_case(&current.fname)
+        {
+            let cue = AudioCue::Music(music);
+            *playing = Playing::new(cue.clone());
+            return vec![AudioEvent::Keep(cue)];
+        }
+        self.start(AudioCue::Music(music))
+    }
+
+    fn start(&mut self, cue: AudioCue) -> Vec<AudioEvent> {
+        let channel = cue.channel();
+        let mut events = self.stop(channel);
+        *self.channel_mut(channel) = Some(Playing::new(cue.clone()));
+        events.push(AudioEvent::Start(cue));
+        events
+    }
+
+    fn stop(&mut self, channel: AudioChannel) -> Vec<AudioEvent> {
+        match self.channel_mut(channel).take() {
+            Some(_) => vec![AudioEvent::Stop(channel)],
+            None => Vec::new(),
+        }
+    }
+
+    fn channel_mut(&mut self, channel: AudioChannel) -> &mut Option<Playing> {
+        match channel {
+            AudioChannel::Sound => &mut self.sound,
+            AudioChannel::Music => &mut self.music,
+        }
+    }
+}
@@ -135,6 +135,11 @@ impl BufferedOutput {
         }
     }
 
+    /// Returns true if no text has been written since the last line break.
+    pub fn at_line_start(&self) -> bool {
+        !self.in_line && self.text_buf.is_empty()
+    }
+
     pub fn last_printed_character(&self) -> Option<char> {
         last_printable_char(&self.text_buf).or(self.last_char)
     }
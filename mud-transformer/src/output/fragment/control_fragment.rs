@@ -56,8 +56,12 @@ pub enum ControlFragment {
         left: Option<u16>,
         right: Option<u16>,
     },
+    /// ICH (Insert Character)
+    InsertCharacters(u16),
     /// DECIC (Insert Column)
     InsertColumns(u16),
+    /// IL (Insert Line)
+    InsertLines(u16),
     /// OSC 52 (Query or Change Clipboard Data)
     ManipulateSelection(term::SelectionData, ByteString),
     /// MC (Media Copy)
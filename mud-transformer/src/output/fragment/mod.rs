@@ -40,6 +40,10 @@ pub enum OutputFragment {
     Hr,
     Image(mxp::Image),
     LineBreak,
+    /// Command sent with [`Transformer::send_command`], echoed locally.
+    ///
+    /// [`Transformer::send_command`]: crate::Transformer::send_command
+    LocalEcho(String),
     Mxp(MxpFragment),
     PageBreak,
     Telnet(TelnetFragment),
@@ -53,6 +57,7 @@ impl OutputFragment {
             self,
             Self::Hr
                 | Self::LineBreak
+                | Self::LocalEcho(_)
                 | Self::PageBreak
                 | Self::Control(ControlFragment::CarriageReturn)
         )
@@ -66,7 +71,7 @@ impl OutputFragment {
     /// Fragment does not target a specific window, so it doesn't need to be associated with an
     /// [`Output::window`] MXP tag.
     pub(super) const fn is_windowless(&self) -> bool {
-        matches!(self, Self::Mxp(_) | Self::Telnet(_))
+        matches!(self, Self::LocalEcho(_) | Self::Mxp(_) | Self::Telnet(_))
     }
 
     /// Fragment does not require the current line of text to be flushed to output as a text
@@ -4,6 +4,7 @@ use super::OutputFragment;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum MxpFragment {
+    Custom(mxp::Custom),
     Entity(EntityFragment),
     Expire(mxp::Expire),
     Filter(mxp::Filter),
@@ -51,6 +52,12 @@ impl From<EntityFragment> for OutputFragment {
     }
 }
 
+impl From<mxp::Custom> for OutputFragment {
+    fn from(value: mxp::Custom) -> Self {
+        Self::Mxp(MxpFragment::Custom(value))
+    }
+}
+
 impl From<mxp::Expire> for OutputFragment {
     fn from(value: mxp::Expire) -> Self {
         Self::Mxp(MxpFragment::Expire(value))
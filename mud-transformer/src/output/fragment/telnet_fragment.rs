@@ -6,6 +6,9 @@ use crate::{TelnetSource, TelnetVerb};
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TelnetFragment {
     GoAhead,
+    Msdp {
+        enabled: bool,
+    },
     Mxp {
         enabled: bool,
     },
@@ -1,3 +1,6 @@
+mod audio;
+pub use audio::{AudioChannel, AudioCue, AudioEvent, AudioState};
+
 mod buffer;
 pub(crate) use buffer::BufferedOutput;
 
@@ -16,5 +19,8 @@ pub use link::{Link, SendTo};
 
 mod register;
 
+mod scrollback;
+pub use scrollback::{MatchSpan, ScrollbackBuffer, ScrollbackLine, SearchMatch};
+
 mod span;
 pub use span::TextStyle;
@@ -0,0 +1,358 @@
+use std::collections::{VecDeque, vec_deque};
+use std::ops::{Index, Range};
+
+use bytestring::ByteString;
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+use super::{MxpFragment, Output, OutputFragment, TextFragment};
+
+type Window = Option<mxp::Dest<ByteString>>;
+
+/// A completed line of text stored in a [`ScrollbackBuffer`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScrollbackLine {
+    fragments: Vec<TextFragment>,
+    text: String,
+    window: Window,
+}
+
+impl ScrollbackLine {
+    fn new(window: Window) -> Self {
+        Self {
+            fragments: Vec::new(),
+            text: String::new(),
+            window,
+        }
+    }
+
+    /// Styled spans of text that make up the line.
+    pub fn fragments(&self) -> &[TextFragment] {
+        &self.fragments
+    }
+
+    /// Plain text of the line, without styling.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// MXP window the line was sent to. If `None`, the line belongs to the main window.
+    pub fn window(&self) -> Option<&mxp::Dest<ByteString>> {
+        self.window.as_ref()
+    }
+
+    /// Returns true if the line contains no text.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    fn window_name(&self) -> Option<&str> {
+        window_name(&self.window)
+    }
+
+    fn push(&mut self, fragment: TextFragment) {
+        self.text.push_str(&fragment.text);
+        self.fragments.push(fragment);
+    }
+
+    fn expire(&mut self, name: Option<&str>) {
+        for fragment in &mut self.fragments {
+            let Some(link) = &fragment.link else {
+                continue;
+            };
+            let expired = match (name, &link.expire) {
+                (_, None) => false,
+                (None, Some(_)) => true,
+                (Some(name), Some(expire)) => expire.eq_ignore_ascii_case(name),
+            };
+            if expired {
+                fragment.link = None;
+            }
+        }
+    }
+
+    /// Maps a byte range of [`text`](Self::text) to the fragments it covers.
+    fn spans(&self, range: &Range<usize>) -> Vec<MatchSpan> {
+        let mut spans = Vec::new();
+        let mut start = 0;
+        for (fragment, text) in self.fragments.iter().enumerate() {
+            let end = start + text.text.len();
+            if end > range.start && start < range.end {
+                spans.push(MatchSpan {
+                    fragment,
+                    range: range.start.max(start) - start..range.end.min(end) - start,
+                });
+            }
+            start = end;
+        }
+        spans
+    }
+}
+
+/// Part of a [`SearchMatch`] contained within a single fragment of a line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchSpan {
+    /// Index of the fragment in [`ScrollbackLine::fragments`].
+    pub fragment: usize,
+    /// Byte range of the match within the fragment's text.
+    pub range: Range<usize>,
+}
+
+/// Text found by searching a [`ScrollbackBuffer`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// Index of the line in the buffer.
+    pub line: usize,
+    /// Byte range of the match within [`ScrollbackLine::text`].
+    pub range: Range<usize>,
+    /// Fragments covered by the match, in order.
+    pub spans: Vec<MatchSpan>,
+}
+
+/// History of the lines of text received from the server.
+///
+/// Outputs are fed to the buffer as they are drained from the transformer. Text is accumulated
+/// until a line break, at which point the completed line is stored along with its styled
+/// fragments and the MXP window it was sent to. Lines sent to different windows are accumulated
+/// separately, so text sent to another window does not interrupt a line in the main window.
+/// Gagged outputs are not stored.
+///
+/// When the buffer is full, the oldest lines are discarded. Lines are indexed from oldest to
+/// newest, so indices shift as lines are discarded or removed.
+///
+/// # Examples
+///
+/// ```
+/// use mud_transformer::output::ScrollbackBuffer;
+/// use mud_transformer::{Transformer, TransformerConfig};
+///
+/// let mut transformer = Transformer::new(TransformerConfig::default());
+/// transformer.receive(b"You see a \x1B[1mlantern\x1B[0m here.\r\n", &mut [0; 1024]);
+/// let mut scrollback = ScrollbackBuffer::new(1000);
+/// scrollback.extend(transformer.flush_output());
+///
+/// assert_eq!(scrollback[0].text(), "You see a lantern here.");
+/// let found = scrollback.search("lantern");
+/// assert_eq!(found[0].spans[0].fragment, 1);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScrollbackBuffer {
+    lines: VecDeque<ScrollbackLine>,
+    pending: Vec<ScrollbackLine>,
+    capacity: usize,
+}
+
+impl ScrollbackBuffer {
+    /// Creates an empty buffer that stores up to `capacity` lines.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            pending: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Maximum number of lines stored in the buffer.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the maximum number of lines, discarding the oldest lines if necessary.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.enforce_capacity();
+    }
+
+    /// Number of completed lines in the buffer.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns true if the buffer contains no completed lines.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Returns the completed line at an index, where 0 is the oldest line.
+    pub fn get(&self, index: usize) -> Option<&ScrollbackLine> {
+        self.lines.get(index)
+    }
+
+    /// Returns the most recent completed line.
+    pub fn last(&self) -> Option<&ScrollbackLine> {
+        self.lines.back()
+    }
+
+    /// Iterates through completed lines, from oldest to newest.
+    pub fn iter(&self) -> vec_deque::Iter<'_, ScrollbackLine> {
+        self.lines.iter()
+    }
+
+    /// Returns the incomplete line of text that has been received for a window, such as a prompt.
+    pub fn pending(&self, window: Option<&str>) -> Option<&ScrollbackLine> {
+        self.pending
+            .iter()
+            .find(|line| line.window_name() == window)
+    }
+
+    /// Removes the completed line at an index.
+    pub fn remove(&mut self, index: usize) -> Option<ScrollbackLine> {
+        self.lines.remove(index)
+    }
+
+    /// Removes every completed line for which `f` returns false.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&ScrollbackLine) -> bool,
+    {
+        self.lines.retain(f);
+    }
+
+    /// Removes all lines, including incomplete ones.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.pending.clear();
+    }
+
+    /// Disables links created with the specified `expire` name, or all named links if `name` is
+    /// `None`. This is done automatically when an MXP `<EXPIRE>` tag is received.
+    pub fn expire(&mut self, name: Option<&str>) {
+        for line in self.lines.iter_mut().chain(&mut self.pending) {
+            line.expire(name);
+        }
+    }
+
+    /// Adds an output to the buffer.
+    pub fn push(&mut self, output: Output) {
+        if output.gag {
+            return;
+        }
+        let window = output.window;
+        match output.fragment {
+            OutputFragment::Text(fragment) => self.pending_mut(window).push(fragment),
+            OutputFragment::LineBreak | OutputFragment::PageBreak | OutputFragment::Hr => {
+                let line = self
+                    .take_pending(window_name(&window))
+                    .unwrap_or_else(|| ScrollbackLine::new(window));
+                self.push_line(line);
+            }
+            OutputFragment::LocalEcho(command) => {
+                self.flush_pending(window_name(&window));
+                let mut line = ScrollbackLine::new(window);
+                line.push(TextFragment::from(command.as_str()));
+                self.push_line(line);
+            }
+            OutputFragment::Mxp(MxpFragment::Expire(expire)) => {
+                self.expire(expire.name.as_deref());
+            }
+            _ => (),
+        }
+    }
+
+    /// Completes the incomplete line of text that has been received for a window, if there is
+    /// one.
+    pub fn flush_pending(&mut self, window: Option<&str>) {
+        if let Some(line) = self.take_pending(window) {
+            self.push_line(line);
+        }
+    }
+
+    /// Finds every occurrence of a string in completed lines, from oldest to newest.
+    pub fn search(&self, needle: &str) -> Vec<SearchMatch> {
+        if needle.is_empty() {
+            return Vec::new();
+        }
+        self.search_with(|text| {
+            text.match_indices(needle)
+                .map(|(start, found)| start..start + found.len())
+        })
+    }
+
+    /// Finds every match of a regular expression in completed lines, from oldest to newest.
+    #[cfg(feature = "regex")]
+    pub fn search_regex(&self, regex: &Regex) -> Vec<SearchMatch> {
+        self.search_with(|text| {
+            regex
+                .find_iter(text)
+                .filter(|found| !found.is_empty())
+                .map(|found| found.range())
+        })
+    }
+
+    fn search_with<'a, F, I>(&'a self, mut find: F) -> Vec<SearchMatch>
+    where
+        F: FnMut(&'a str) -> I,
+        I: Iterator<Item = Range<usize>>,
+    {
+        let mut matches = Vec::new();
+        for (index, line) in self.lines.iter().enumerate() {
+            matches.extend(find(line.text()).map(|range| SearchMatch {
+                line: index,
+                spans: line.spans(&range),
+                range,
+            }));
+        }
+        matches
+    }
+
+    fn pending_mut(&mut self, window: Window) -> &mut ScrollbackLine {
+        let name = window_name(&window);
+        let found = self
+            .pending
+            .iter()
+            .position(|line| line.window_name() == name);
+        let index = found.unwrap_or_else(|| {
+            self.pending.push(ScrollbackLine::new(window));
+            self.pending.len() - 1
+        });
+        &mut self.pending[index]
+    }
+
+    fn take_pending(&mut self, window: Option<&str>) -> Option<ScrollbackLine> {
+        let index = self
+            .pending
+            .iter()
+            .position(|line| line.window_name() == window)?;
+        Some(self.pending.swap_remove(index))
+    }
+
+    fn push_line(&mut self, line: ScrollbackLine) {
+        self.lines.push_back(line);
+        self.enforce_capacity();
+    }
+
+    fn enforce_capacity(&mut self) {
+        let excess = self.lines.len().saturating_sub(self.capacity);
+        self.lines.drain(..excess);
+    }
+}
+
+impl Extend<Output> for ScrollbackBuffer {
+    fn extend<T: IntoIterator<Item = Output>>(&mut self, iter: T) {
+        for output in iter {
+            self.push(output);
+        }
+    }
+}
+
+impl Index<usize> for ScrollbackBuffer {
+    type Output = ScrollbackLine;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.lines[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a ScrollbackBuffer {
+    type Item = &'a ScrollbackLine;
+
+    type IntoIter = vec_deque::Iter<'a, ScrollbackLine>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+fn window_name(window: &Window) -> Option<&str> {
+    window.as_ref()?.name.as_deref()
+}
@@ -33,6 +33,7 @@ pub(crate) struct Interpreter {
     first_byte: u8,
     prefix: u8,
     suffix: u8,
+    console_controls: bool,
 }
 
 impl Default for Interpreter {
@@ -57,9 +58,16 @@ impl Interpreter {
             first_byte: 0,
             prefix: 0,
             suffix: 0,
+            console_controls: false,
         }
     }
 
+    /// If false, insertions are approximated with spaces and line breaks, for clients that do not
+    /// track a screen.
+    pub fn set_console_controls(&mut self, console_controls: bool) {
+        self.console_controls = console_controls;
+    }
+
     pub fn reset(&mut self) {
         self.code = None;
         self.sequence.clear();
@@ -172,10 +180,11 @@ impl Interpreter {
         let n = unwrap_pos(self.code);
         output.append(match code {
             b'm' => return self.interpret_mode(output),
-            b'@' => {
+            b'@' if !self.console_controls => {
                 output.append_repeated(" ", n.into());
                 return Some(Outcome::Done);
             }
+            b'@' => ControlFragment::InsertCharacters(n),
             b'A' => CursorEffect::Up(n).into(),
             b'B' => CursorEffect::Down(n).into(),
             b'C' => CursorEffect::Forward(n).into(),
@@ -183,15 +192,17 @@ impl Interpreter {
             b'E' => CursorEffect::NextLine(n).into(),
             b'F' => CursorEffect::PreviousLine(n).into(),
             b'G' => CursorEffect::HorizontalAbsolute(n).into(),
+            b'H' | b'f' => CursorEffect::Position { row: n, column: 1 }.into(),
             b'I' => CursorEffect::TabForward(n).into(),
             b'J' => self.interpret_erase(EraseTarget::Display, false)?,
             b'K' => self.interpret_erase(EraseTarget::Line, false)?,
-            b'L' => {
+            b'L' if !self.console_controls => {
                 for _ in 0..n {
                     output.append(OutputFragment::LineBreak);
                 }
                 return Some(Outcome::Done);
             }
+            b'L' => ControlFragment::InsertLines(n),
             b'M' => ControlFragment::DeleteLines(n),
             b'P' => ControlFragment::DeleteCharacters(n),
             b'S' => CursorEffect::ScrollUp(n).into(),
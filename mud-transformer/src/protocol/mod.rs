@@ -1,5 +1,9 @@
 pub(crate) mod ansi;
 
+mod negotiation;
+pub use negotiation::{OptionState, OptionStatus};
+pub(crate) use negotiation::{Options, Transition};
+
 mod network;
 pub use network::ToBeBytes;
 
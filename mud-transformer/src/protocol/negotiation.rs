@@ -0,0 +1,209 @@
+use std::fmt;
+
+use bytetable::ByteTable;
+use log::warn;
+
+use super::{TelnetSource, TelnetVerb};
+use crate::opt;
+
+/// State of one side of a telnet option, according to the
+/// [RFC 1143](https://datatracker.ietf.org/doc/html/rfc1143) Q method.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OptionStatus {
+    /// The option is disabled.
+    #[default]
+    No,
+    /// The option is enabled.
+    Yes,
+    /// The option has been asked to be disabled, and is awaiting a reply.
+    WantNo,
+    /// The option has been asked to be enabled, and is awaiting a reply.
+    WantYes,
+}
+
+impl fmt::Display for OptionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::No => "NO".fmt(f),
+            Self::Yes => "YES".fmt(f),
+            Self::WantNo => "WANTNO".fmt(f),
+            Self::WantYes => "WANTYES".fmt(f),
+        }
+    }
+}
+
+/// Negotiation state of a telnet option, for both the client ("us") and the server ("him").
+///
+/// The queue flags record that the opposite of a pending request was requested while waiting for
+/// a reply, which is sent once the reply arrives.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OptionState {
+    /// Whether the client performs the option, as negotiated with `DO` and `DONT`.
+    pub us: OptionStatus,
+    pub us_queued: bool,
+    /// Whether the server performs the option, as negotiated with `WILL` and `WONT`.
+    pub him: OptionStatus,
+    pub him_queued: bool,
+}
+
+impl OptionState {
+    /// Returns true if the option is enabled on the specified side.
+    pub fn enabled(self, source: TelnetSource) -> bool {
+        match source {
+            TelnetSource::Client => self.us == OptionStatus::Yes,
+            TelnetSource::Server => self.him == OptionStatus::Yes,
+        }
+    }
+
+    /// Returns true if the option has been negotiated in either direction.
+    pub fn is_negotiated(self) -> bool {
+        self != Self::default()
+    }
+}
+
+/// Result of a negotiation with the server.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Transition {
+    /// Negotiation to send to the server.
+    pub send: Option<TelnetVerb>,
+    /// Whether the option was enabled (`true`) or disabled (`false`), if it changed.
+    pub enabled: Option<bool>,
+}
+
+/// Q method state of every telnet option.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Options {
+    states: Box<ByteTable<OptionState>>,
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, code: u8) -> OptionState {
+        self.states[code]
+    }
+
+    pub fn enabled(&self, source: TelnetSource, code: u8) -> bool {
+        self.states[code].enabled(source)
+    }
+
+    /// Codes of options that are enabled on the specified side.
+    pub fn enabled_codes(&self, source: TelnetSource) -> impl Iterator<Item = u8> + '_ {
+        self.iter()
+            .filter(move |(_, state)| state.enabled(source))
+            .map(|(code, _)| code)
+    }
+
+    /// Options that have been negotiated in either direction.
+    pub fn negotiated(&self) -> impl Iterator<Item = (u8, OptionState)> + '_ {
+        self.iter().filter(|(_, state)| state.is_negotiated())
+    }
+
+    /// Updates the state of an option after receiving a negotiation from the server. `supported`
+    /// determines whether a request to enable the option is accepted.
+    pub fn receive(&mut self, verb: TelnetVerb, code: u8, supported: bool) -> Transition {
+        let source = match verb {
+            TelnetVerb::Will | TelnetVerb::Wont => TelnetSource::Server,
+            TelnetVerb::Do | TelnetVerb::Dont => TelnetSource::Client,
+        };
+        let (status, queued) = self.side(source, code);
+        let (positive, negative) = replies(source);
+        let enable = matches!(verb, TelnetVerb::Will | TelnetVerb::Do);
+        let (send, enabled) = match (*status, enable) {
+            (OptionStatus::No, true) if supported => {
+                *status = OptionStatus::Yes;
+                (Some(positive), Some(true))
+            }
+            (OptionStatus::No, true) => (Some(negative), None),
+            (OptionStatus::Yes, true) | (OptionStatus::No, false) => (None, None),
+            (OptionStatus::WantNo, true) if *queued => {
+                warn!(target: "mud.telnet",
+                    "Server answered {negative} {} with {verb}", opt::display(code)
+                );
+                *status = OptionStatus::Yes;
+                *queued = false;
+                (None, Some(true))
+            }
+            (OptionStatus::WantNo, true) => {
+                warn!(target: "mud.telnet",
+                    "Server answered {negative} {} with {verb}", opt::display(code)
+                );
+                *status = OptionStatus::No;
+                (None, None)
+            }
+            (OptionStatus::WantYes, true) if *queued => {
+                *status = OptionStatus::WantNo;
+                *queued = false;
+                (Some(negative), None)
+            }
+            (OptionStatus::WantYes, true) => {
+                *status = OptionStatus::Yes;
+                (None, Some(true))
+            }
+            (OptionStatus::Yes, false) => {
+                *status = OptionStatus::No;
+                (Some(negative), Some(false))
+            }
+            (OptionStatus::WantNo, false) if *queued => {
+                *status = OptionStatus::WantYes;
+                *queued = false;
+                (Some(positive), None)
+            }
+            (OptionStatus::WantNo | OptionStatus::WantYes, false) => {
+                *status = OptionStatus::No;
+                *queued = false;
+                (None, None)
+            }
+        };
+        Transition { send, enabled }
+    }
+
+    /// Updates the state of an option after the client asks to enable or disable it on the
+    /// specified side. Requests that duplicate a pending or completed negotiation are not sent.
+    pub fn request(&mut self, source: TelnetSource, code: u8, enable: bool) -> Transition {
+        let (status, queued) = self.side(source, code);
+        let (positive, negative) = replies(source);
+        let (send, enabled) = match (*status, enable) {
+            (OptionStatus::No, true) => {
+                *status = OptionStatus::WantYes;
+                (Some(positive), None)
+            }
+            (OptionStatus::Yes, false) => {
+                *status = OptionStatus::WantNo;
+                (Some(negative), Some(false))
+            }
+            (OptionStatus::WantNo, true) | (OptionStatus::WantYes, false) => {
+                *queued = true;
+                (None, None)
+            }
+            (OptionStatus::WantNo, false) | (OptionStatus::WantYes, true) => {
+                *queued = false;
+                (None, None)
+            }
+            (OptionStatus::No, false) | (OptionStatus::Yes, true) => (None, None),
+        };
+        Transition { send, enabled }
+    }
+
+    fn side(&mut self, source: TelnetSource, code: u8) -> (&mut OptionStatus, &mut bool) {
+        let state = &mut self.states[code];
+        match source {
+            TelnetSource::Client => (&mut state.us, &mut state.us_queued),
+            TelnetSource::Server => (&mut state.him, &mut state.him_queued),
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (u8, OptionState)> + '_ {
+        (0..=u8::MAX).map(|code| (code, self.states[code]))
+    }
+}
+
+/// Negotiations that ask to enable and disable an option on the specified side.
+const fn replies(source: TelnetSource) -> (TelnetVerb, TelnetVerb) {
+    match source {
+        TelnetSource::Client => (TelnetVerb::Will, TelnetVerb::Wont),
+        TelnetSource::Server => (TelnetVerb::Do, TelnetVerb::Dont),
+    }
+}
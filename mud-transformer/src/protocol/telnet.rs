@@ -50,14 +50,12 @@ impl fmt::Display for TelnetVerb {
 }
 
 pub(crate) fn write_escaping_iac<W: Write>(mut writer: W, bytes: &[u8]) -> io::Result<()> {
-    let mut escaping = false;
-    for slice in bytes.split(|&c| c == telnet::IAC) {
-        if escaping {
-            writer.write_all(&[telnet::IAC, telnet::IAC])?;
-        } else {
-            writer.write_all(&[telnet::IAC])?;
-            escaping = true;
-        }
+    let mut slices = bytes.split(|&c| c == telnet::IAC);
+    if let Some(first) = slices.next() {
+        writer.write_all(first)?;
+    }
+    for slice in slices {
+        writer.write_all(&[telnet::IAC, telnet::IAC])?;
         writer.write_all(slice)?;
     }
     Ok(())
@@ -54,6 +54,10 @@ impl Interpreter {
         Self::default()
     }
 
+    pub fn set_console_controls(&mut self, console_controls: bool) {
+        self.csi.set_console_controls(console_controls);
+    }
+
     pub fn answerback(&self) -> &[u8] {
         &self.answerback
     }
@@ -22,6 +22,9 @@ pub use print_function::PrintFunction;
 mod rect;
 pub use rect::{Rect, RectEffect, ReverseVisualCharacterAttribute, VisualCharacterAttribute};
 
+mod screen;
+pub use screen::{Cell, Screen};
+
 mod selection;
 pub use selection::SelectionData;
 
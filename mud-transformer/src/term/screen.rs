@@ -0,0 +1,760 @@
+use std::fmt::{self, Write};
+use std::{mem, string};
+
+use flagset::FlagSet;
+use mxp::RgbColor;
+
+use super::{
+    AttributeRequest, CursorEffect, DeviceStatus, EraseRange, EraseTarget, Mode, Reset, TabEffect,
+    WindowOp,
+};
+use crate::output::{ControlFragment, Output, OutputFragment, TextFragment, TextStyle};
+use crate::responses::{
+    CursorPositionReport, ExtendedCursorPositionReport, OkReport, ScreenSizeReport, TabStopReport,
+    TextAreaSizeReport,
+};
+
+/// A character position on a [`Screen`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub c: char,
+    pub flags: FlagSet<TextStyle>,
+    pub foreground: Option<RgbColor>,
+    pub background: Option<RgbColor>,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            c: ' ',
+            flags: FlagSet::default(),
+            foreground: None,
+            background: None,
+        }
+    }
+}
+
+impl Cell {
+    fn styled(c: char, fragment: &TextFragment) -> Self {
+        Self {
+            c,
+            flags: fragment.flags,
+            foreground: fragment.foreground,
+            background: fragment.background,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+struct SavedCursor {
+    row: usize,
+    column: usize,
+    origin: bool,
+    pending_wrap: bool,
+}
+
+type Grid = Vec<Vec<Cell>>;
+
+/// Virtual terminal screen that applies [`Output`]s to a grid of [`Cell`]s.
+///
+/// Frontends that need to render full-screen programs, such as MUD editors and maps, can feed
+/// every output from the transformer into a `Screen` and draw its cells, rather than interpreting
+/// cursor movement themselves. The screen tracks the cursor, scroll region, tab stops, insert and
+/// autowrap modes, and the alternate screen buffer. [`TransformerConfig::console_controls`] should
+/// be enabled, so that requests to insert characters and lines reach the screen.
+///
+/// Queries about terminal state, such as cursor position reports, are answered from the screen's
+/// state. The answers are buffered until retrieved with [`Screen::drain_replies`], and should be
+/// sent to the server.
+///
+/// Every character occupies a single cell. Outputs that are gagged or directed to an MXP window
+/// are ignored, as are horizontal margins and paging.
+///
+/// [`TransformerConfig::console_controls`]: crate::TransformerConfig::console_controls
+///
+/// # Examples
+///
+/// ```
+/// use mud_transformer::term::Screen;
+/// use mud_transformer::{Transformer, TransformerConfig};
+///
+/// let mut transformer = Transformer::new(TransformerConfig {
+///     console_controls: true,
+///     ..Default::default()
+/// });
+/// transformer.receive(b"hello\x1B[1;4Hp!\x1B[6n", &mut [0; 1024]);
+/// let mut screen = Screen::new(3, 10);
+/// for output in transformer.flush_output() {
+///     screen.apply(&output);
+/// }
+/// assert_eq!(screen.text(), "help!");
+/// assert_eq!(screen.cursor(), (0, 5));
+/// assert_eq!(screen.drain_replies().as_str(), "\x1B[1;6R");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Screen {
+    rows: usize,
+    columns: usize,
+    grid: Grid,
+    /// The primary grid, stored while the alternate screen buffer is active.
+    primary: Option<Grid>,
+    row: usize,
+    column: usize,
+    /// The cursor is past the last column, and the next character will wrap to the next line.
+    pending_wrap: bool,
+    saved: SavedCursor,
+    top: usize,
+    bottom: usize,
+    tab_stops: Vec<bool>,
+    autowrap: bool,
+    cursor_visible: bool,
+    insert: bool,
+    origin: bool,
+    replies: String,
+}
+
+impl Default for Screen {
+    fn default() -> Self {
+        Self::new(24, 80)
+    }
+}
+
+impl Screen {
+    /// Maximum number of rows. The server can resize the screen, so dimensions are capped to keep
+    /// the grid's memory use bounded.
+    pub const MAX_ROWS: u16 = 1000;
+
+    /// Maximum number of columns.
+    pub const MAX_COLUMNS: u16 = 1000;
+
+    /// Creates a blank screen. Dimensions are clamped to a minimum of 1 and a maximum of
+    /// [`MAX_ROWS`](Self::MAX_ROWS) and [`MAX_COLUMNS`](Self::MAX_COLUMNS).
+    pub fn new(rows: u16, columns: u16) -> Self {
+        let (rows, columns) = clamp_size(rows, columns);
+        Self {
+            rows,
+            columns,
+            grid: blank_grid(rows, columns),
+            primary: None,
+            row: 0,
+            column: 0,
+            pending_wrap: false,
+            saved: SavedCursor::default(),
+            top: 0,
+            bottom: rows - 1,
+            tab_stops: (0..columns).map(is_default_stop).collect(),
+            autowrap: true,
+            cursor_visible: true,
+            insert: false,
+            origin: false,
+            replies: String::new(),
+        }
+    }
+
+    /// Number of rows on the screen.
+    pub fn rows(&self) -> u16 {
+        to_u16(self.rows)
+    }
+
+    /// Number of columns on the screen.
+    pub fn columns(&self) -> u16 {
+        to_u16(self.columns)
+    }
+
+    /// Zero-based row and column of the cursor.
+    pub fn cursor(&self) -> (u16, u16) {
+        (to_u16(self.row), to_u16(self.column))
+    }
+
+    /// Returns false if the server has hidden the cursor with DECTCEM.
+    pub fn cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    /// Returns true if the alternate screen buffer is active.
+    pub fn alternate_screen(&self) -> bool {
+        self.primary.is_some()
+    }
+
+    /// Zero-based top and bottom rows of the scroll region, inclusive.
+    pub fn scroll_region(&self) -> (u16, u16) {
+        (to_u16(self.top), to_u16(self.bottom))
+    }
+
+    /// Returns the cell at a zero-based row and column.
+    pub fn cell(&self, row: u16, column: u16) -> Option<&Cell> {
+        self.grid.get(usize::from(row))?.get(usize::from(column))
+    }
+
+    /// Returns the cells of a zero-based row.
+    pub fn line(&self, row: u16) -> Option<&[Cell]> {
+        self.grid.get(usize::from(row)).map(Vec::as_slice)
+    }
+
+    /// Iterates through the rows of the screen, from top to bottom.
+    pub fn lines(&self) -> impl Iterator<Item = &[Cell]> {
+        self.grid.iter().map(Vec::as_slice)
+    }
+
+    /// Returns the characters of a zero-based row, without trailing whitespace.
+    pub fn line_text(&self, row: u16) -> Option<String> {
+        self.line(row).map(line_text)
+    }
+
+    /// Returns the characters on the screen, one line per row. Trailing whitespace is removed from
+    /// each line, and trailing empty lines are omitted.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for line in self.lines() {
+            text.push_str(&line_text(line));
+            text.push('\n');
+        }
+        text.truncate(text.trim_end().len());
+        text
+    }
+
+    /// Removes and returns replies to queries from the server, which should be sent back to it.
+    pub fn drain_replies(&mut self) -> string::Drain<'_> {
+        self.replies.drain(..)
+    }
+
+    /// Changes the size of the screen. Rows and columns are added or removed at the bottom and
+    /// right, and the scroll region is reset. Dimensions are clamped as in [`Screen::new`].
+    pub fn resize(&mut self, rows: u16, columns: u16) {
+        let (rows, columns) = clamp_size(rows, columns);
+        resize_grid(&mut self.grid, rows, columns);
+        if let Some(primary) = &mut self.primary {
+            resize_grid(primary, rows, columns);
+        }
+        let old_columns = self.tab_stops.len();
+        self.tab_stops.truncate(columns);
+        self.tab_stops
+            .extend((old_columns..columns).map(is_default_stop));
+        self.rows = rows;
+        self.columns = columns;
+        self.top = 0;
+        self.bottom = rows - 1;
+        self.row = self.row.min(rows - 1);
+        self.column = self.column.min(columns - 1);
+        self.pending_wrap = false;
+    }
+
+    /// Applies an output to the screen. Gagged outputs and outputs directed to MXP windows are
+    /// ignored.
+    pub fn apply(&mut self, output: &Output) {
+        if output.gag || output.window.is_some() {
+            return;
+        }
+        self.apply_fragment(&output.fragment);
+    }
+
+    /// Applies an output fragment to the screen.
+    pub fn apply_fragment(&mut self, fragment: &OutputFragment) {
+        match fragment {
+            OutputFragment::Text(fragment) => self.print(fragment),
+            OutputFragment::LocalEcho(command) => {
+                for c in command.chars().filter(|c| !c.is_control()) {
+                    self.put(Cell {
+                        c,
+                        ..Default::default()
+                    });
+                }
+                self.new_line();
+            }
+            OutputFragment::Hr | OutputFragment::LineBreak => self.new_line(),
+            OutputFragment::PageBreak => self.line_feed(),
+            OutputFragment::Control(control) => self.control(control),
+            OutputFragment::Image(_) | OutputFragment::Mxp(_) | OutputFragment::Telnet(_) => (),
+        }
+    }
+
+    fn control(&mut self, fragment: &ControlFragment) {
+        match fragment {
+            ControlFragment::CarriageReturn => self.carriage_return(),
+            ControlFragment::Cursor(effect) => self.cursor_effect(*effect),
+            ControlFragment::DeleteCharacters(n) => {
+                let (row, column) = (self.row, self.column);
+                delete_cells(&mut self.grid[row], column, usize::from(*n));
+            }
+            ControlFragment::DeleteColumns(n) => {
+                let column = self.column;
+                for line in &mut self.grid[self.top..=self.bottom] {
+                    delete_cells(line, column, usize::from(*n));
+                }
+            }
+            ControlFragment::DeleteLines(n) if self.in_scroll_region() => {
+                self.delete_lines(self.row, usize::from(*n));
+                self.carriage_return();
+            }
+            ControlFragment::DeviceStatusReport(status) => self.report_status(*status),
+            ControlFragment::Erase { target, range, .. } => self.erase(*target, *range),
+            ControlFragment::EraseCharacters(n) => {
+                let end = self.columns.min(self.column + usize::from(*n));
+                self.grid[self.row][self.column..end].fill(Cell::default());
+            }
+            ControlFragment::InsertCharacters(n) => {
+                let (row, column) = (self.row, self.column);
+                insert_cells(&mut self.grid[row], column, usize::from(*n));
+            }
+            ControlFragment::InsertColumns(n) => {
+                let column = self.column;
+                for line in &mut self.grid[self.top..=self.bottom] {
+                    insert_cells(line, column, usize::from(*n));
+                }
+            }
+            ControlFragment::InsertLines(n) if self.in_scroll_region() => {
+                self.insert_lines(self.row, usize::from(*n));
+                self.carriage_return();
+            }
+            ControlFragment::ModeSet(mode, enable) => self.set_mode(*mode, *enable),
+            ControlFragment::NextLine => self.new_line(),
+            ControlFragment::Request(AttributeRequest::TabStop) => {
+                let stops: Vec<u16> = (0..self.columns)
+                    .filter(|&column| self.tab_stops[column])
+                    .map(|column| to_u16(column + 1))
+                    .collect();
+                self.reply(TabStopReport { stops });
+            }
+            ControlFragment::ResetTerminal(Reset::Soft) => self.soft_reset(),
+            ControlFragment::ResetTerminal(Reset::Hard | Reset::Secure) => {
+                let replies = mem::take(&mut self.replies);
+                *self = Self::new(self.rows(), self.columns());
+                self.replies = replies;
+            }
+            ControlFragment::ScreenAlignmentTest => {
+                for line in &mut self.grid {
+                    line.fill(Cell {
+                        c: 'E',
+                        ..Default::default()
+                    });
+                }
+                self.top = 0;
+                self.bottom = self.rows - 1;
+                self.origin = false;
+                self.move_to(0, 0);
+            }
+            ControlFragment::SetColumns(columns) => self.resize(self.rows(), *columns),
+            ControlFragment::SetRows(rows) => self.resize(*rows, self.columns()),
+            ControlFragment::Tab(effect) => self.tab_effect(effect),
+            ControlFragment::VerticalTab => self.line_feed(),
+            ControlFragment::VMargins { top, bottom } => {
+                let top = top.filter(|&top| top != 0).map_or(1, usize::from) - 1;
+                let bottom = bottom
+                    .filter(|&bottom| bottom != 0)
+                    .map_or(self.rows, usize::from)
+                    - 1;
+                if top < bottom && bottom < self.rows {
+                    self.top = top;
+                    self.bottom = bottom;
+                    self.set_position(1, 1);
+                }
+            }
+            ControlFragment::Window(op) => self.window_op(*op),
+            _ => (),
+        }
+    }
+
+    fn cursor_effect(&mut self, effect: CursorEffect) {
+        if !matches!(effect, CursorEffect::Save { .. }) {
+            self.pending_wrap = false;
+        }
+        match effect {
+            CursorEffect::Up(n) => self.cursor_up(n.into()),
+            CursorEffect::Down(n) => self.cursor_down(n.into()),
+            CursorEffect::Forward(n) | CursorEffect::ColumnRelative(n) => {
+                self.column = (self.column + usize::from(n)).min(self.columns - 1);
+            }
+            CursorEffect::Back(n) => self.column = self.column.saturating_sub(n.into()),
+            CursorEffect::NextLine(n) => {
+                self.cursor_down(n.into());
+                self.carriage_return();
+            }
+            CursorEffect::PreviousLine(n) => {
+                self.cursor_up(n.into());
+                self.carriage_return();
+            }
+            CursorEffect::TabForward(n) => self.tab_forward(n),
+            CursorEffect::TabBack(n) => {
+                for _ in 0..n {
+                    self.column = (0..self.column)
+                        .rfind(|&column| self.tab_stops[column])
+                        .unwrap_or(0);
+                }
+            }
+            CursorEffect::Position { row, column } => self.set_position(row, column),
+            CursorEffect::ColumnAbsolute(column) | CursorEffect::HorizontalAbsolute(column) => {
+                self.column = (usize::from(column.max(1)) - 1).min(self.columns - 1);
+            }
+            CursorEffect::RowAbsolute(row) => {
+                let column = to_u16(self.column + 1);
+                self.set_position(row, column);
+            }
+            CursorEffect::RowRelative(n) => {
+                self.row = (self.row + usize::from(n)).min(self.rows - 1);
+            }
+            CursorEffect::ScrollUp(n) => self.delete_lines(self.top, n.into()),
+            CursorEffect::ScrollDown(n) => self.insert_lines(self.top, n.into()),
+            CursorEffect::Index => self.line_feed(),
+            CursorEffect::ReverseIndex => {
+                if self.row == self.top {
+                    self.insert_lines(self.top, 1);
+                } else {
+                    self.row = self.row.saturating_sub(1);
+                }
+            }
+            CursorEffect::ForwardIndex => {
+                if self.column + 1 < self.columns {
+                    self.column += 1;
+                } else {
+                    for line in &mut self.grid[self.top..=self.bottom] {
+                        delete_cells(line, 0, 1);
+                    }
+                }
+            }
+            CursorEffect::BackIndex => {
+                if self.column > 0 {
+                    self.column -= 1;
+                } else {
+                    for line in &mut self.grid[self.top..=self.bottom] {
+                        insert_cells(line, 0, 1);
+                    }
+                }
+            }
+            CursorEffect::Save { .. } => self.save_cursor(),
+            CursorEffect::Restore { dec } => self.restore_cursor(dec),
+            CursorEffect::NextPage(_)
+            | CursorEffect::PrecedingPage(_)
+            | CursorEffect::PageBackward(_)
+            | CursorEffect::PageForward(_)
+            | CursorEffect::PageAbsolute(_) => (),
+        }
+    }
+
+    fn set_mode(&mut self, mode: Mode, enable: bool) {
+        match mode {
+            Mode::INSERT => self.insert = enable,
+            Mode::ORIGIN => {
+                self.origin = enable;
+                self.set_position(1, 1);
+            }
+            Mode::AUTOWRAP => {
+                self.autowrap = enable;
+                self.pending_wrap &= enable;
+            }
+            Mode::TEXT_CURSOR => self.cursor_visible = enable,
+            Mode::ALTERNATE_SCREEN_BUFFER | Mode::ALTERNATE_SCREEN_BUFFER_ => {
+                self.set_alternate_screen(enable);
+            }
+            Mode::DECSC if enable => self.save_cursor(),
+            Mode::DECSC => self.restore_cursor(true),
+            Mode::DECSC_AND_ALTERNATE_SCREEN_BUFFER if enable => {
+                self.save_cursor();
+                self.set_alternate_screen(true);
+            }
+            Mode::DECSC_AND_ALTERNATE_SCREEN_BUFFER => {
+                self.set_alternate_screen(false);
+                self.restore_cursor(true);
+            }
+            _ => (),
+        }
+    }
+
+    fn tab_effect(&mut self, effect: &TabEffect) {
+        match effect {
+            TabEffect::ClearAtCursor => self.tab_stops[self.column] = false,
+            TabEffect::ClearAll => self.tab_stops.fill(false),
+            TabEffect::RestoreStops(stops) => {
+                self.tab_stops.fill(false);
+                for &stop in stops {
+                    let column = usize::from(stop).wrapping_sub(1);
+                    if let Some(tab_stop) = self.tab_stops.get_mut(column) {
+                        *tab_stop = true;
+                    }
+                }
+            }
+            TabEffect::SetEvery8Columns => {
+                for (column, tab_stop) in self.tab_stops.iter_mut().enumerate() {
+                    *tab_stop = is_default_stop(column);
+                }
+            }
+            TabEffect::SetStop => self.tab_stops[self.column] = true,
+        }
+    }
+
+    fn window_op(&mut self, op: WindowOp) {
+        let (height, width) = (self.rows(), self.columns());
+        match op {
+            WindowOp::SetTextAreaSize {
+                height: rows,
+                width: columns,
+            } => {
+                let rows = if rows == 0 { height } else { rows };
+                let columns = if columns == 0 { width } else { columns };
+                self.resize(rows, columns);
+            }
+            WindowOp::SetLines(rows) if rows != 0 => self.resize(rows, width),
+            WindowOp::ReportTextAreaSize => self.reply(TextAreaSizeReport { height, width }),
+            WindowOp::ReportScreenSize => self.reply(ScreenSizeReport { height, width }),
+            _ => (),
+        }
+    }
+
+    fn report_status(&mut self, status: DeviceStatus) {
+        let row = to_u16(if self.origin {
+            self.row.saturating_sub(self.top) + 1
+        } else {
+            self.row + 1
+        });
+        let column = to_u16(self.column + 1);
+        match status {
+            DeviceStatus::OPERATING_STATUS => self.reply(OkReport),
+            DeviceStatus::CURSOR_POSITION => self.reply(CursorPositionReport { row, column }),
+            DeviceStatus::EXTENDED_CURSOR_POSITION => {
+                self.reply(ExtendedCursorPositionReport {
+                    row,
+                    column,
+                    page: 1,
+                });
+            }
+            _ => (),
+        }
+    }
+
+    fn reply<T: fmt::Display>(&mut self, reply: T) {
+        // Writing to a String is infallible.
+        let _ = write!(self.replies, "{reply}");
+    }
+
+    fn print(&mut self, fragment: &TextFragment) {
+        for c in fragment.text.chars() {
+            match c {
+                '\t' => self.tab_forward(1),
+                c if c.is_control() => (),
+                c => self.put(Cell::styled(c, fragment)),
+            }
+        }
+    }
+
+    fn put(&mut self, cell: Cell) {
+        if self.pending_wrap {
+            self.new_line();
+        }
+        let line = &mut self.grid[self.row];
+        if self.insert {
+            insert_cells(line, self.column, 1);
+        }
+        line[self.column] = cell;
+        if self.column + 1 < self.columns {
+            self.column += 1;
+        } else {
+            self.pending_wrap = self.autowrap;
+        }
+    }
+
+    fn carriage_return(&mut self) {
+        self.column = 0;
+        self.pending_wrap = false;
+    }
+
+    fn line_feed(&mut self) {
+        self.pending_wrap = false;
+        if self.row == self.bottom {
+            self.delete_lines(self.top, 1);
+        } else if self.row + 1 < self.rows {
+            self.row += 1;
+        }
+    }
+
+    fn new_line(&mut self) {
+        self.carriage_return();
+        self.line_feed();
+    }
+
+    fn tab_forward(&mut self, n: u16) {
+        self.pending_wrap = false;
+        for _ in 0..n {
+            self.column = (self.column + 1..self.columns)
+                .find(|&column| self.tab_stops[column])
+                .unwrap_or(self.columns - 1);
+        }
+    }
+
+    fn cursor_up(&mut self, n: usize) {
+        let min = if self.row >= self.top { self.top } else { 0 };
+        self.row = self.row.saturating_sub(n).max(min);
+    }
+
+    fn cursor_down(&mut self, n: usize) {
+        let max = if self.row <= self.bottom {
+            self.bottom
+        } else {
+            self.rows - 1
+        };
+        self.row = (self.row + n).min(max);
+    }
+
+    /// Moves the cursor to a one-based row and column, relative to the scroll region if origin
+    /// mode is enabled.
+    fn set_position(&mut self, row: u16, column: u16) {
+        let (min, max) = if self.origin {
+            (self.top, self.bottom)
+        } else {
+            (0, self.rows - 1)
+        };
+        let row = (min + usize::from(row.max(1)) - 1).min(max);
+        let column = (usize::from(column.max(1)) - 1).min(self.columns - 1);
+        self.move_to(row, column);
+    }
+
+    fn in_scroll_region(&self) -> bool {
+        (self.top..=self.bottom).contains(&self.row)
+    }
+
+    fn move_to(&mut self, row: usize, column: usize) {
+        self.row = row;
+        self.column = column;
+        self.pending_wrap = false;
+    }
+
+    /// Deletes lines starting at `row`, scrolling up the lines below it within the scroll region.
+    fn delete_lines(&mut self, row: usize, n: usize) {
+        let region = &mut self.grid[row..=self.bottom];
+        let n = n.min(region.len());
+        region.rotate_left(n);
+        let len = region.len();
+        for line in &mut region[len - n..] {
+            line.fill(Cell::default());
+        }
+    }
+
+    /// Inserts blank lines at `row`, scrolling down the lines below it within the scroll region.
+    fn insert_lines(&mut self, row: usize, n: usize) {
+        let region = &mut self.grid[row..=self.bottom];
+        let n = n.min(region.len());
+        region.rotate_right(n);
+        for line in &mut region[..n] {
+            line.fill(Cell::default());
+        }
+    }
+
+    fn erase(&mut self, target: EraseTarget, range: EraseRange) {
+        let (row, column) = (self.row, self.column);
+        let (above, below) = self.grid.split_at_mut(row);
+        let (line, below) = below.split_first_mut().expect("cursor out of bounds");
+        match range {
+            EraseRange::AfterCursor => line[column..].fill(Cell::default()),
+            EraseRange::BeforeCursor => line[..=column].fill(Cell::default()),
+            EraseRange::Full => line.fill(Cell::default()),
+        }
+        if target == EraseTarget::Line {
+            return;
+        }
+        let others = match range {
+            EraseRange::AfterCursor => below,
+            EraseRange::BeforeCursor => above,
+            EraseRange::Full => {
+                for line in above {
+                    line.fill(Cell::default());
+                }
+                below
+            }
+        };
+        for line in others {
+            line.fill(Cell::default());
+        }
+    }
+
+    fn save_cursor(&mut self) {
+        self.saved = SavedCursor {
+            row: self.row,
+            column: self.column,
+            origin: self.origin,
+            pending_wrap: self.pending_wrap,
+        };
+    }
+
+    fn restore_cursor(&mut self, dec: bool) {
+        let saved = self.saved;
+        self.move_to(
+            saved.row.min(self.rows - 1),
+            saved.column.min(self.columns - 1),
+        );
+        if dec {
+            self.origin = saved.origin;
+            self.pending_wrap = saved.pending_wrap && self.autowrap;
+        }
+    }
+
+    fn set_alternate_screen(&mut self, enable: bool) {
+        if !enable {
+            if let Some(primary) = self.primary.take() {
+                self.grid = primary;
+            }
+        } else if self.primary.is_none() {
+            let alternate = blank_grid(self.rows, self.columns);
+            self.primary = Some(mem::replace(&mut self.grid, alternate));
+        }
+    }
+
+    fn soft_reset(&mut self) {
+        self.insert = false;
+        self.origin = false;
+        self.autowrap = true;
+        self.cursor_visible = true;
+        self.top = 0;
+        self.bottom = self.rows - 1;
+        self.saved = SavedCursor::default();
+    }
+}
+
+const fn is_default_stop(column: usize) -> bool {
+    column != 0 && column.is_multiple_of(8)
+}
+
+fn to_u16(n: usize) -> u16 {
+    u16::try_from(n).unwrap_or(u16::MAX)
+}
+
+fn clamp_size(rows: u16, columns: u16) -> (usize, usize) {
+    (
+        usize::from(rows.clamp(1, Screen::MAX_ROWS)),
+        usize::from(columns.clamp(1, Screen::MAX_COLUMNS)),
+    )
+}
+
+fn blank_grid(rows: usize, columns: usize) -> Grid {
+    vec![vec![Cell::default(); columns]; rows]
+}
+
+fn resize_grid(grid: &mut Grid, rows: usize, columns: usize) {
+    grid.resize_with(rows, Vec::new);
+    for line in grid {
+        line.resize(columns, Cell::default());
+    }
+}
+
+fn line_text(line: &[Cell]) -> String {
+    let text: String = line.iter().map(|cell| cell.c).collect();
+    text.trim_end().to_owned()
+}
+
+/// Deletes cells at `column`, shifting the cells after them to the left.
+fn delete_cells(line: &mut [Cell], column: usize, n: usize) {
+    let Some(cells) = line.get_mut(column..) else {
+        return;
+    };
+    let n = n.min(cells.len());
+    cells.rotate_left(n);
+    let len = cells.len();
+    cells[len - n..].fill(Cell::default());
+}
+
+/// Inserts blank cells at `column`, shifting the cells after them to the right.
+fn insert_cells(line: &mut [Cell], column: usize, n: usize) {
+    let Some(cells) = line.get_mut(column..) else {
+        return;
+    };
+    let n = n.min(cells.len());
+    cells.rotate_right(n);
+    cells[..n].fill(Cell::default());
+}
@@ -8,6 +8,8 @@ use mxp::responses::{SupportResponse, VersionResponse};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use super::encoding::Encoding;
+use super::link_policy::LinkPolicy;
 use crate::opt;
 
 const ALWAYS_WILL: ByteSet = {
@@ -20,6 +22,7 @@ const ALWAYS_WILL: ByteSet = {
     will.insert(opt::MNES);
     will.insert(opt::CHARSET);
     will.insert(opt::MCCP2);
+    will.insert(opt::MCCP3);
     will.insert(opt::MXP);
     will
 };
@@ -152,7 +155,7 @@ impl From<Tag> for mxp::ActionKind {
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TransformerConfig {
-    /// Transmitted in response to an MXP `<VERSION>` request.
+    /// Transmitted in response to an MXP `<VERSION>` request and in GMCP `Core.Hello`.
     /// Default: empty.
     pub app_name: String,
     /// Transmitted as IPADDRESS in an MNES response.
@@ -161,7 +164,12 @@ pub struct TransformerConfig {
     /// Default: empty.
     pub colors: Vec<RgbColor>,
     /// Client supports some VT100 features, such as moving the cursor and erasing characters.
+    /// If false, requests to insert characters and lines are output as spaces and line breaks
+    /// instead of [`ControlFragment::InsertCharacters`] and [`ControlFragment::InsertLines`].
     /// Default: false.
+    ///
+    /// [`ControlFragment::InsertCharacters`]: crate::output::ControlFragment::InsertCharacters
+    /// [`ControlFragment::InsertLines`]: crate::output::ControlFragment::InsertLines
     pub console_controls: bool,
     /// Insert a newline into the output when server sends GA (Go-Ahead).
     /// Default: false.
@@ -172,15 +180,47 @@ pub struct TransformerConfig {
     /// Use the US-ASCII charset instead of UTF-8.
     /// Default: false.
     pub disable_utf8: bool,
+    /// Character encoding of text received from the server, unless the server negotiates a
+    /// different one through CHARSET.
+    /// Default: [`Encoding::Utf8`].
+    pub encoding: Encoding,
+    /// GMCP packages supported by the client, such as `"Char 1"` or `"Room 1"`. If not empty,
+    /// the client agrees to GMCP and sends these packages in `Core.Supports.Set`.
+    /// Default: empty.
+    pub gmcp_packages: Vec<String>,
     /// Ignore colors set by MXP tags.
     /// Default: false.
     pub ignore_mxp_colors: bool,
     /// Convert underlined text into clickable links, as per the Mud Server Link Protocol.
     /// Default: false.
     pub linkify_underlined: bool,
+    /// Append commands sent with [`Transformer::send_command`] to output, unless the server is
+    /// echoing input itself.
+    /// Default: false.
+    ///
+    /// [`Transformer::send_command`]: crate::Transformer::send_command
+    pub local_echo: bool,
+    /// Restrictions on links, images, and sounds sent by the server.
+    /// Default: [`LinkPolicy::new`].
+    pub link_policy: LinkPolicy,
     /// Client supports XTerm mouse tracking.
     /// Default: false.
     pub mouse_tracking: bool,
+    /// Quotas on MXP definitions and open tags from the server.
+    /// Default: [`mxp::Limits::DEFAULT`].
+    pub mxp_limits: mxp::Limits,
+    /// Telnet options for the server to perform, requested with `DO` when the transformer is
+    /// created, for servers that wait for the client to ask. Requested options are added to
+    /// [`will`](Self::will). Default: empty.
+    pub offer_do: ByteSet,
+    /// Telnet options for the client to perform, offered with `WILL` when the transformer is
+    /// created, for servers that wait for the client to offer. Offered options are added to
+    /// [`will`](Self::will). Default: empty.
+    pub offer_will: ByteSet,
+    /// Errors in malformed MXP from the server to recover from, instead of discarding the tag or
+    /// entity containing them.
+    /// Default: [`mxp::ParseOptions::STRICT`].
+    pub parse_options: mxp::ParseOptions,
     /// Transmitted in response to an MXP `<PASSWORD>` request.
     /// Default: empty.
     pub password: String,
@@ -212,7 +252,7 @@ pub struct TransformerConfig {
     /// Condition for the transformer to activate MXP mode.
     /// Default: [`UseMxp::Command`].
     pub use_mxp: UseMxp,
-    /// Transmitted in response to an MXP `<VERSION>` response.
+    /// Transmitted in response to an MXP `<VERSION>` response and in GMCP `Core.Hello`.
     /// Default: empty.
     pub version: String,
     /// Custom Telnet protocols to support, sent during a telnet `WILL` negotiation.
@@ -239,9 +279,17 @@ impl TransformerConfig {
             convert_ga_to_newline: false,
             disable_compression: false,
             disable_utf8: false,
+            encoding: Encoding::Utf8,
+            gmcp_packages: Vec::new(),
             ignore_mxp_colors: false,
             linkify_underlined: false,
+            link_policy: LinkPolicy::new(),
+            local_echo: false,
             mouse_tracking: false,
+            mxp_limits: mxp::Limits::DEFAULT,
+            offer_do: ByteSet::new(),
+            offer_will: ByteSet::new(),
+            parse_options: mxp::ParseOptions::STRICT,
             password: String::new(),
             player: String::new(),
             proxy: false,
@@ -268,8 +316,14 @@ impl TransformerConfig {
 
     pub(crate) fn postprocess_will(&mut self) {
         self.will |= ALWAYS_WILL;
+        self.will |= self.offer_do;
+        self.will |= self.offer_will;
         if self.disable_compression {
             self.will.remove(opt::MCCP2);
+            self.will.remove(opt::MCCP3);
+        }
+        if !self.gmcp_packages.is_empty() {
+            self.will.insert(opt::GMCP);
         }
         if self.use_mxp == UseMxp::Never {
             self.will.remove(opt::MXP);
@@ -290,6 +344,7 @@ impl TransformerConfig {
             version: &self.version,
             style: self.style_version.as_deref(),
             registered: None,
+            ..Default::default()
         }
     }
 }
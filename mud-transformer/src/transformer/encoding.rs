@@ -0,0 +1,261 @@
+use std::fmt;
+use std::str;
+
+use encoding_rs::EncoderResult;
+use log::warn;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Character encoding of text received from the server.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// UTF-8.
+    #[default]
+    Utf8,
+    /// ISO-8859-1, also known as Latin-1.
+    Latin1,
+    /// Code page 437, the character set of the IBM PC, known for its box-drawing characters.
+    Cp437,
+    /// Windows-1252, the Windows superset of Latin-1.
+    Cp1252,
+    /// KOI8-R, for Russian.
+    Koi8R,
+    /// Big5, for Traditional Chinese.
+    Big5,
+    /// GBK, for Simplified Chinese.
+    Gbk,
+}
+
+const NAMES: &[(&str, Encoding)] = &[
+    ("UTF-8", Encoding::Utf8),
+    ("UTF8", Encoding::Utf8),
+    ("ISO-8859-1", Encoding::Latin1),
+    ("ISO_8859-1", Encoding::Latin1),
+    ("ISO8859-1", Encoding::Latin1),
+    ("LATIN1", Encoding::Latin1),
+    ("LATIN-1", Encoding::Latin1),
+    ("L1", Encoding::Latin1),
+    ("CP437", Encoding::Cp437),
+    ("IBM437", Encoding::Cp437),
+    ("CP1252", Encoding::Cp1252),
+    ("WINDOWS-1252", Encoding::Cp1252),
+    ("KOI8-R", Encoding::Koi8R),
+    ("KOI8R", Encoding::Koi8R),
+    ("BIG5", Encoding::Big5),
+    ("BIG-5", Encoding::Big5),
+    ("CP950", Encoding::Big5),
+    ("GBK", Encoding::Gbk),
+    ("CP936", Encoding::Gbk),
+    ("GB2312", Encoding::Gbk),
+];
+
+impl Encoding {
+    /// Finds an encoding by name, such as `"ISO-8859-1"` or `"CP437"`.
+    ///
+    /// Case-insensitive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mud_transformer::Encoding;
+    ///
+    /// assert_eq!(Encoding::from_name("latin1"), Some(Encoding::Latin1));
+    /// assert_eq!(Encoding::from_name("IBM437"), Some(Encoding::Cp437));
+    /// assert_eq!(Encoding::from_name("EBCDIC"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        NAMES
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+            .map(|&(_, encoding)| encoding)
+    }
+
+    /// Returns the name used to report the encoding to the server.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Latin1 => "ISO-8859-1",
+            Self::Cp437 => "CP437",
+            Self::Cp1252 => "CP1252",
+            Self::Koi8R => "KOI8-R",
+            Self::Big5 => "BIG5",
+            Self::Gbk => "GBK",
+        }
+    }
+
+    /// Encodes text into the encoding. Characters that cannot be encoded are replaced with `'?'`.
+    pub(crate) fn encode_into(self, text: &str, buf: &mut Vec<u8>) {
+        match self.implementation() {
+            Some(encoding) => encode_with(encoding, text, buf),
+            None if self == Self::Utf8 => buf.extend_from_slice(text.as_bytes()),
+            None if self == Self::Latin1 => {
+                buf.extend(text.chars().map(|c| u8::try_from(c).unwrap_or(b'?')));
+            }
+            None => buf.extend(text.chars().map(encode_cp437)),
+        }
+    }
+
+    const fn implementation(self) -> Option<&'static encoding_rs::Encoding> {
+        match self {
+            Self::Utf8 | Self::Latin1 | Self::Cp437 => None,
+            Self::Cp1252 => Some(encoding_rs::WINDOWS_1252),
+            Self::Koi8R => Some(encoding_rs::KOI8_R),
+            Self::Big5 => Some(encoding_rs::BIG5),
+            Self::Gbk => Some(encoding_rs::GBK),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.name().fmt(f)
+    }
+}
+
+fn encode_with(encoding: &'static encoding_rs::Encoding, mut text: &str, buf: &mut Vec<u8>) {
+    let mut encoder = encoding.new_encoder();
+    loop {
+        if let Some(len) = encoder.max_buffer_length_from_utf8_without_replacement(text.len()) {
+            buf.reserve(len);
+        }
+        let (result, read) = encoder.encode_from_utf8_to_vec_without_replacement(text, buf, true);
+        text = &text[read..];
+        match result {
+            EncoderResult::InputEmpty => return,
+            EncoderResult::OutputFull => (),
+            EncoderResult::Unmappable(_) => buf.push(b'?'),
+        }
+    }
+}
+
+fn encode_cp437(c: char) -> u8 {
+    if c.is_ascii() {
+        return c as u8;
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    CP437
+        .iter()
+        .position(|&mapped| mapped == c)
+        .map_or(b'?', |i| 0x80 + i as u8)
+}
+
+/// Decodes non-ASCII text from the server one byte at a time.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Decoder {
+    encoding: Encoding,
+    sequence: Vec<u8>,
+    decoded: String,
+}
+
+impl Decoder {
+    pub fn new(encoding: Encoding) -> Self {
+        Self {
+            encoding,
+            sequence: Vec::with_capacity(4),
+            decoded: String::new(),
+        }
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+        self.sequence.clear();
+    }
+
+    /// Returns `true` if `c` belongs to the partially received character.
+    pub fn continues(&self, c: u8) -> bool {
+        match (self.encoding, self.sequence.as_slice()) {
+            (Encoding::Utf8, _) => matches!(c, 0x80..=0xBF),
+            (Encoding::Big5, [_]) => matches!(c, 0x40..=0x7E | 0xA1..=0xFE),
+            (Encoding::Gbk, [_]) => matches!(c, 0x30..=0x39 | 0x40..=0x7E | 0x80..=0xFE),
+            (Encoding::Gbk, [_, _]) => matches!(c, 0x81..=0xFE),
+            (Encoding::Gbk, [_, _, _]) => matches!(c, 0x30..=0x39),
+            _ => false,
+        }
+    }
+
+    /// Receives a non-ASCII byte. Returns the decoded character once it is complete.
+    pub fn push(&mut self, c: u8) -> Option<&str> {
+        self.sequence.push(c);
+        if self.is_complete() {
+            Some(self.flush())
+        } else {
+            None
+        }
+    }
+
+    /// Decodes the partially received character, if any.
+    pub fn flush(&mut self) -> &str {
+        self.decoded.clear();
+        let decoded = match (self.encoding, self.sequence.as_slice()) {
+            (_, []) => None,
+            (Encoding::Latin1, &[c]) => Some(char::from(c)),
+            (Encoding::Cp437, &[c]) if c >= 0x80 => Some(CP437[usize::from(c - 0x80)]),
+            (Encoding::Utf8, sequence) => {
+                if let Ok(s) = str::from_utf8(sequence) {
+                    self.decoded.push_str(s);
+                }
+                None
+            }
+            (encoding, sequence) => {
+                if let Some(s) = encoding
+                    .implementation()
+                    .and_then(|e| e.decode_without_bom_handling_and_without_replacement(sequence))
+                {
+                    self.decoded.push_str(&s);
+                }
+                None
+            }
+        };
+        if let Some(decoded) = decoded {
+            self.decoded.push(decoded);
+        }
+        if self.decoded.is_empty() && !self.sequence.is_empty() {
+            warn!(target: "mud.telnet", "Malformed {}: {:?}", self.encoding, self.sequence);
+            self.decoded.push(char::REPLACEMENT_CHARACTER);
+        }
+        self.sequence.clear();
+        &self.decoded
+    }
+
+    fn is_complete(&self) -> bool {
+        match (self.encoding, self.sequence.as_slice()) {
+            (_, []) => false,
+            (Encoding::Utf8, [lead, rest @ ..]) => match lead {
+                0xC0..=0xDF => rest.len() == 1,
+                0xE0..=0xEF => rest.len() == 2,
+                0xF0..=0xF7 => rest.len() == 3,
+                _ => true,
+            },
+            (Encoding::Big5, [lead, rest @ ..]) => !matches!(lead, 0x81..=0xFE) || rest.len() == 1,
+            (Encoding::Gbk, [lead]) => !matches!(lead, 0x81..=0xFE),
+            (Encoding::Gbk, [_, second, rest @ ..]) => {
+                !matches!(second, 0x30..=0x39) || rest.len() == 2
+            }
+            _ => true,
+        }
+    }
+}
+
+const CP437: [char; 128] = [
+    '\u{00C7}', '\u{00FC}', '\u{00E9}', '\u{00E2}', '\u{00E4}', '\u{00E0}', '\u{00E5}', '\u{00E7}',
+    '\u{00EA}', '\u{00EB}', '\u{00E8}', '\u{00EF}', '\u{00EE}', '\u{00EC}', '\u{00C4}', '\u{00C5}',
+    '\u{00C9}', '\u{00E6}', '\u{00C6}', '\u{00F4}', '\u{00F6}', '\u{00F2}', '\u{00FB}', '\u{00F9}',
+    '\u{00FF}', '\u{00D6}', '\u{00DC}', '\u{00A2}', '\u{00A3}', '\u{00A5}', '\u{20A7}', '\u{0192}',
+    '\u{00E1}', '\u{00ED}', '\u{00F3}', '\u{00FA}', '\u{00F1}', '\u{00D1}', '\u{00AA}', '\u{00BA}',
+    '\u{00BF}', '\u{2310}', '\u{00AC}', '\u{00BD}', '\u{00BC}', '\u{00A1}', '\u{00AB}', '\u{00BB}',
+    '\u{2591}', '\u{2592}', '\u{2593}', '\u{2502}', '\u{2524}', '\u{2561}', '\u{2562}', '\u{2556}',
+    '\u{2555}', '\u{2563}', '\u{2551}', '\u{2557}', '\u{255D}', '\u{255C}', '\u{255B}', '\u{2510}',
+    '\u{2514}', '\u{2534}', '\u{252C}', '\u{251C}', '\u{2500}', '\u{253C}', '\u{255E}', '\u{255F}',
+    '\u{255A}', '\u{2554}', '\u{2569}', '\u{2566}', '\u{2560}', '\u{2550}', '\u{256C}', '\u{2567}',
+    '\u{2568}', '\u{2564}', '\u{2565}', '\u{2559}', '\u{2558}', '\u{2552}', '\u{2553}', '\u{256B}',
+    '\u{256A}', '\u{2518}', '\u{250C}', '\u{2588}', '\u{2584}', '\u{258C}', '\u{2590}', '\u{2580}',
+    '\u{03B1}', '\u{00DF}', '\u{0393}', '\u{03C0}', '\u{03A3}', '\u{03C3}', '\u{00B5}', '\u{03C4}',
+    '\u{03A6}', '\u{0398}', '\u{03A9}', '\u{03B4}', '\u{221E}', '\u{03C6}', '\u{03B5}', '\u{2229}',
+    '\u{2261}', '\u{00B1}', '\u{2265}', '\u{2264}', '\u{2320}', '\u{2321}', '\u{00F7}', '\u{2248}',
+    '\u{00B0}', '\u{2219}', '\u{00B7}', '\u{221A}', '\u{207F}', '\u{00B2}', '\u{25A0}', '\u{00A0}',
+];
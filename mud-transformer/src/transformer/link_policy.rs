@@ -0,0 +1,150 @@
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Reason a URL, file name, or command was rejected by a [`LinkPolicy`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LinkViolation {
+    /// Longer than [`LinkPolicy::max_len`].
+    TooLong,
+    /// Contains control characters, or begins or ends with whitespace. Browsers strip these
+    /// characters before interpreting a URL, which could be used to disguise its scheme.
+    InvalidCharacter,
+    /// URL scheme is not in [`LinkPolicy::schemes`].
+    ForbiddenScheme,
+    /// URL has no scheme, and [`LinkPolicy::allow_relative`] is false.
+    RelativeUrl,
+    /// File name is an absolute path, or includes a URL scheme.
+    AbsolutePath,
+    /// File name or relative URL contains a `..` segment.
+    PathTraversal,
+}
+
+impl fmt::Display for LinkViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::TooLong => "link too long",
+            Self::InvalidCharacter => "invalid character in link",
+            Self::ForbiddenScheme => "URL scheme not allowed",
+            Self::RelativeUrl => "relative URL not allowed",
+            Self::AbsolutePath => "file name must be a relative path",
+            Self::PathTraversal => "path traversal not allowed",
+        })
+    }
+}
+
+impl std::error::Error for LinkViolation {}
+
+/// Determines which destinations the server is allowed to send to the client.
+///
+/// The policy is applied to the `href` of `<A>` links, the file names and URLs of `<IMAGE>`,
+/// `<SOUND>` and `<MUSIC>` tags, and the commands of `<SEND>` tags and MSLP links. When a destination is rejected,
+/// the transformer logs a warning and neutralizes it: links are displayed as plain text, URLs are
+/// removed from images and sounds, and images and sounds with rejected file names are dropped.
+///
+/// # Examples
+///
+/// ```
+/// use mud_transformer::{LinkPolicy, LinkViolation};
+///
+/// let policy = LinkPolicy::default();
+/// assert_eq!(policy.check_url("https://example.com"), Ok(()));
+/// assert_eq!(policy.check_url("javascript:alert(1)"), Err(LinkViolation::ForbiddenScheme));
+/// assert_eq!(policy.check_path("sounds/ding.wav"), Ok(()));
+/// assert_eq!(policy.check_path("../../.bashrc"), Err(LinkViolation::PathTraversal));
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkPolicy {
+    /// URL schemes that may be opened. Case-insensitive.
+    /// Default: `http` and `https`.
+    pub schemes: Vec<String>,
+    /// Accept URLs without a scheme, such as `www.example.com`.
+    /// Default: false.
+    pub allow_relative: bool,
+    /// Maximum length of a URL, file name, or command, in bytes.
+    /// Default: 2048.
+    pub max_len: usize,
+}
+
+impl Default for LinkPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinkPolicy {
+    /// Constructs the default policy, which allows `http` and `https` URLs of up to 2048 bytes.
+    pub fn new() -> Self {
+        Self {
+            schemes: vec!["http".to_owned(), "https".to_owned()],
+            allow_relative: false,
+            max_len: 2048,
+        }
+    }
+
+    /// Checks a URL to be opened in a browser or downloaded from.
+    pub fn check_url(&self, url: &str) -> Result<(), LinkViolation> {
+        self.check_chars(url)?;
+        match scheme(url) {
+            Some(scheme) if self.schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme)) => Ok(()),
+            Some(_) => Err(LinkViolation::ForbiddenScheme),
+            None if !self.allow_relative => Err(LinkViolation::RelativeUrl),
+            None => check_traversal(url),
+        }
+    }
+
+    /// Checks the name of a file to be loaded from the client's local directories.
+    pub fn check_path(&self, path: &str) -> Result<(), LinkViolation> {
+        self.check_chars(path)?;
+        if path.starts_with(['/', '\\']) || scheme(path).is_some() {
+            return Err(LinkViolation::AbsolutePath);
+        }
+        check_traversal(path)
+    }
+
+    /// Checks a command to be sent to the server.
+    pub fn check_command(&self, command: &str) -> Result<(), LinkViolation> {
+        if command.len() > self.max_len {
+            return Err(LinkViolation::TooLong);
+        }
+        Ok(())
+    }
+
+    fn check_chars(&self, s: &str) -> Result<(), LinkViolation> {
+        self.check_command(s)?;
+        if s.trim() != s || s.chars().any(char::is_control) {
+            return Err(LinkViolation::InvalidCharacter);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the scheme of a URL as defined by RFC 3986, if it has one. Windows drive letters, such
+/// as the `C` in `C:\`, are treated as schemes.
+fn scheme(url: &str) -> Option<&str> {
+    let (scheme, _) = url.split_once(':')?;
+    let mut chars = scheme.chars();
+    if !chars.next()?.is_ascii_alphabetic() {
+        return None;
+    }
+    if chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')) {
+        Some(scheme)
+    } else {
+        None
+    }
+}
+
+fn check_traversal(path: &str) -> Result<(), LinkViolation> {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    if path.split(['/', '\\']).any(is_parent_dir) {
+        return Err(LinkViolation::PathTraversal);
+    }
+    Ok(())
+}
+
+fn is_parent_dir(segment: &str) -> bool {
+    let segment = segment.to_ascii_lowercase().replace("%2e", ".");
+    segment == ".."
+}
@@ -3,6 +3,15 @@ pub use bytetable::ByteSet;
 mod config;
 pub use config::{TabBehavior, Tag, TransformerConfig, UseMxp};
 
+mod encoding;
+pub use encoding::Encoding;
+
+mod link_policy;
+pub use link_policy::{LinkPolicy, LinkViolation};
+
+mod option_handler;
+pub use option_handler::{OptionContext, TelnetOptionHandler};
+
 mod phase;
 
 mod state;
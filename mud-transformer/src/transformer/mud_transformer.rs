@@ -5,27 +5,34 @@ use std::{mem, slice};
 use bytes::BytesMut;
 use bytestring::ByteString;
 use bytestringmut::ByteStringMut;
-use bytetable::ByteSet;
 use log::{debug, error, info, warn};
 use mxp::element::ElementFlag;
 use mxp::entity::PublishedIter;
 use mxp::node::{Definition, Tag, TagOpen};
 
 use super::config::{TabBehavior, TransformerConfig, UseMxp};
+use super::encoding::{Decoder, Encoding};
+use super::link_policy::{LinkPolicy, LinkViolation};
+use super::option_handler::{OptionHandlers, TelnetOptionHandler};
 use super::phase::Phase;
 use super::state::StateLock;
 use super::tag_list::TagList;
 use crate::bytestring_ext::ByteStringMutExt;
 use crate::escape::{ansi, telnet};
 use crate::input::{BufferedInput, InputDrain};
-use crate::opt::{self, charset, mccp2, mnes, mtts, status};
+use crate::opt::{self, charset, gmcp, mccp2, mnes, msp, mtts, naws, status};
 use crate::output::{
     BufferedOutput, ControlFragment, EntityFragment, MapperFragment, MxpFragment, OutputDrain,
     OutputFragment, TelnetFragment, TextStyle, VariableFragment,
 };
-use crate::protocol::{Negotiate, TelnetSource, TelnetVerb, xterm};
+use crate::protocol::{
+    Negotiate, OptionState, Options, TelnetSource, TelnetVerb, Transition, write_escaping_iac,
+    xterm,
+};
 use crate::term::{CursorEffect, EraseRange, EraseTarget};
 
+type CheckLink = fn(&LinkPolicy, &str) -> Result<(), LinkViolation>;
+
 fn input_mxp_auth(input: &mut BufferedInput, auth: &str) {
     if auth.is_empty() {
         return;
@@ -38,7 +45,6 @@ pub struct Transformer {
     config: TransformerConfig,
 
     phase: Phase,
-    doing: Box<ByteSet>,
     in_paragraph: bool,
     ignore_next_newline: bool,
 
@@ -49,19 +55,27 @@ pub struct Transformer {
     mxp_quote_terminator: Option<NonZero<u8>>,
     mxp_state: StateLock,
     mxp_tags: TagList,
+    mxp_warnings: Vec<mxp::Error>,
 
-    charsets: charset::Charsets,
+    charset: Option<Encoding>,
     decompress: mccp2::Decompress,
     decompressing: bool,
     mnes_variables: mnes::Variables,
+    msp_trigger: Vec<u8>,
+    msp_sound_url: Option<String>,
+    msp_music_url: Option<String>,
     ttype_negotiator: mtts::Negotiator,
 
+    options: Options,
+    option_handlers: OptionHandlers,
+    window_size: Option<naws::WindowSize>,
+
     ansi: xterm::Interpreter,
     after_ansi: bool,
     subnegotiation_data: BytesMut,
     subnegotiation_type: u8,
     last_char: u8,
-    utf8_sequence: Vec<u8>,
+    decoder: Decoder,
 
     input: BufferedInput,
     output: BufferedOutput,
@@ -81,45 +95,130 @@ impl Transformer {
             output.disable_mxp_colors();
         }
         config.postprocess_will();
-        Self {
+        let mut mxp_state = mxp::State::with_globals();
+        mxp_state.set_limits(config.mxp_limits);
+        let mut ansi = xterm::Interpreter::new();
+        ansi.set_console_controls(config.console_controls);
+        let offer_do = config.offer_do;
+        let offer_will = config.offer_will;
+        let mut transformer = Self {
             phase: Phase::Normal,
-            doing: Box::default(),
 
             mxp_active: config.use_mxp == UseMxp::Always,
 
             in_paragraph: false,
             ignore_next_newline: false,
             mxp_buf: ByteStringMut::new(),
-            mxp_mode: mxp::ModeState::new(),
+            mxp_mode: mxp::ModeState::with_options(config.parse_options),
             mxp_quote_terminator: None,
             mxp_entity_string: Vec::new(),
             mxp_tags: TagList::new(),
-            mxp_state: mxp::State::with_globals().into(),
+            mxp_state: mxp_state.into(),
+            mxp_warnings: Vec::new(),
 
-            charsets: charset::Charsets::new(),
+            charset: None,
             decompress: mccp2::Decompress::new(),
             decompressing: false,
             mnes_variables: mnes::Variables::new(),
+            msp_trigger: Vec::new(),
+            msp_sound_url: None,
+            msp_music_url: None,
             ttype_negotiator: mtts::Negotiator::new(),
 
-            ansi: xterm::Interpreter::new(),
+            options: Options::new(),
+            option_handlers: OptionHandlers::new(),
+            window_size: None,
+
+            ansi,
             after_ansi: false,
             subnegotiation_type: 0,
             subnegotiation_data: BytesMut::new(),
 
             last_char: b'\n',
-            utf8_sequence: Vec::with_capacity(4),
+            decoder: Decoder::new(config.encoding),
             output,
             input: BufferedInput::new(),
 
             config,
+        };
+        for code in &offer_will {
+            transformer.request_option(TelnetSource::Client, code, true);
+        }
+        for code in &offer_do {
+            transformer.request_option(TelnetSource::Server, code, true);
         }
+        transformer
     }
 
     pub fn decompressing(&self) -> bool {
         self.decompressing
     }
 
+    /// Returns the negotiation state of a telnet option.
+    pub fn option_state(&self, code: u8) -> OptionState {
+        self.options.get(code)
+    }
+
+    /// Options that have been negotiated with the server in either direction, including options
+    /// that are awaiting a reply.
+    pub fn negotiated_options(&self) -> impl Iterator<Item = (u8, OptionState)> + '_ {
+        self.options.negotiated()
+    }
+
+    /// Asks the server to enable or disable a telnet option. If `source` is
+    /// [`TelnetSource::Client`], the client offers to perform the option with `WILL` or `WONT`.
+    /// If it is [`TelnetSource::Server`], the server is asked to perform it with `DO` or `DONT`.
+    ///
+    /// Options that are not in [`TransformerConfig::will`] cannot be enabled. Requests are not
+    /// sent if the option is already in the requested state or a negotiation is in progress.
+    pub fn request_option(&mut self, source: TelnetSource, code: u8, enable: bool) {
+        if enable && !self.supports(source, code) {
+            warn!(target: "mud.telnet", "Not requesting unsupported option {}", opt::display(code));
+            return;
+        }
+        let transition = self.options.request(source, code, enable);
+        self.apply_transition(source, code, transition);
+    }
+
+    /// Returns true if the server has agreed to send GMCP messages.
+    pub fn gmcp(&self) -> bool {
+        self.options.enabled(TelnetSource::Server, opt::GMCP)
+    }
+
+    /// Returns true if input to the server is being compressed with MCCP3.
+    pub fn compressing(&self) -> bool {
+        self.input.compressing()
+    }
+
+    /// Ends the MCCP3 stream, if input is being compressed. This should be called before
+    /// disconnecting, after which the remaining input should be drained and sent to the server
+    /// so that it receives a complete stream.
+    pub fn end_compression(&mut self) {
+        if self.input.compressing() {
+            info!(target: "mud.compress", "Ending gracefully");
+            self.input.end_compression();
+        }
+    }
+
+    /// Sets the size of the client's window, in characters. If the server has enabled NAWS, it is
+    /// sent the new size, unless the size has not changed. Otherwise, the size is sent once the
+    /// server enables NAWS.
+    pub fn set_window_size(&mut self, width: u16, height: u16) {
+        let size = naws::WindowSize { width, height };
+        if self.window_size == Some(size) {
+            return;
+        }
+        self.window_size = Some(size);
+        if self.options.enabled(TelnetSource::Client, opt::NAWS) {
+            self.send_window_size(size);
+        }
+    }
+
+    /// Returns the size most recently passed to [`set_window_size`](Self::set_window_size).
+    pub fn window_size(&self) -> Option<naws::WindowSize> {
+        self.window_size
+    }
+
     pub fn mxp_active(&self) -> bool {
         self.mxp_active
     }
@@ -139,6 +238,37 @@ impl Transformer {
             .is_ok_and(|entity| entity.is_some())
     }
 
+    pub fn register_mxp_tag(
+        &mut self,
+        tag: mxp::element::CustomTag,
+    ) -> mxp::Result<Option<mxp::element::CustomTag>> {
+        self.mxp_state.register_tag(tag)
+    }
+
+    pub fn unregister_mxp_tag(&mut self, name: &str) -> Option<mxp::element::CustomTag> {
+        self.mxp_state.unregister_tag(name)
+    }
+
+    /// Registers a handler for a telnet option, replacing and returning the handler previously
+    /// registered for the option, if any. Registering a handler allows the option to be
+    /// negotiated, as if it were in [`TransformerConfig::will`].
+    pub fn register_option_handler<H>(
+        &mut self,
+        code: u8,
+        handler: H,
+    ) -> Option<Box<dyn TelnetOptionHandler>>
+    where
+        H: TelnetOptionHandler + 'static,
+    {
+        self.option_handlers.insert(code, Box::new(handler))
+    }
+
+    /// Removes and returns the handler registered for a telnet option, if any. The option is no
+    /// longer negotiated unless it is in [`TransformerConfig::will`].
+    pub fn unregister_option_handler(&mut self, code: u8) -> Option<Box<dyn TelnetOptionHandler>> {
+        self.option_handlers.remove(code)
+    }
+
     pub fn config(&self) -> &TransformerConfig {
         &self.config
     }
@@ -154,11 +284,18 @@ impl Transformer {
         if config.colors != self.config.colors {
             self.output.set_colors(&self.config.colors);
         }
+        self.mxp_mode.set_options(self.config.parse_options);
+        self.mxp_state.set_limits(self.config.mxp_limits);
+        self.ansi.set_console_controls(self.config.console_controls);
+        self.update_encoding();
         match self.config.use_mxp {
             UseMxp::Always => self.mxp_on(),
             UseMxp::Never => self.mxp_off(),
             UseMxp::Command | UseMxp::Query => (),
         }
+        if self.gmcp() && config.gmcp_packages != self.config.gmcp_packages {
+            self.update_gmcp_packages(&config.gmcp_packages);
+        }
         let mnes_updates = self.mnes_variables.changes(&config, &self.config);
         if mnes_updates.is_empty() {
             return;
@@ -199,6 +336,37 @@ impl Transformer {
         self.input.drain()
     }
 
+    /// Queues a line of user input to be sent to the server.
+    ///
+    /// Line breaks are converted to CR LF, and one is appended to the end. The text is encoded
+    /// into the negotiated character set, and IAC bytes are escaped. If
+    /// [`TransformerConfig::local_echo`] is enabled and the server has not taken over echoing
+    /// (as it does while the user types a password), the command is also appended to output as an
+    /// [`OutputFragment::LocalEcho`].
+    pub fn send_command(&mut self, command: &str) {
+        let command = command.strip_suffix('\n').unwrap_or(command);
+        let encoding = self.decoder.encoding();
+        let ascii_only = encoding == Encoding::Utf8 && self.config.disable_utf8;
+        let mut encoded = Vec::with_capacity(command.len() + 2);
+        for line in command.split('\n') {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if ascii_only {
+                encoded.extend(
+                    line.chars()
+                        .map(|c| if c.is_ascii() { c as u8 } else { b'?' }),
+                );
+            } else {
+                encoding.encode_into(line, &mut encoded);
+            }
+            encoded.extend_from_slice(b"\r\n");
+        }
+        write_escaping_iac(&mut self.input, &encoded).unwrap();
+        if self.config.local_echo && !self.options.enabled(TelnetSource::Server, opt::ECHO) {
+            self.output
+                .append(OutputFragment::LocalEcho(command.to_owned()));
+        }
+    }
+
     pub fn published_entities(&self) -> PublishedIter<'_> {
         self.mxp_state.published_entities()
     }
@@ -281,12 +449,174 @@ impl Transformer {
         });
     }
 
+    fn receive_negotiation(&mut self, verb: TelnetVerb, code: u8) {
+        self.output.append(TelnetFragment::Negotiation {
+            source: TelnetSource::Server,
+            verb,
+            code,
+        });
+        let source = match verb {
+            TelnetVerb::Will | TelnetVerb::Wont => TelnetSource::Server,
+            TelnetVerb::Do | TelnetVerb::Dont => TelnetSource::Client,
+        };
+        let supported = self.supports(source, code);
+        let transition = self.options.receive(verb, code, supported);
+        self.apply_transition(source, code, transition);
+    }
+
+    fn supports(&self, source: TelnetSource, code: u8) -> bool {
+        self.config.will.contains(code) || self.option_handlers.supports(source, code)
+    }
+
+    fn apply_transition(&mut self, source: TelnetSource, code: u8, transition: Transition) {
+        if transition.enabled == Some(false) {
+            self.disable_option(source, code);
+        }
+        if let Some(verb) = transition.send {
+            self.send_negotiation(verb, code);
+        }
+        if transition.enabled == Some(true) {
+            self.enable_option(source, code);
+        }
+        if let Some(enabled) = transition.enabled {
+            self.option_handlers
+                .handle(code, &mut self.input, &mut self.output, |handler, cx| {
+                    if enabled {
+                        handler.enabled(source, cx);
+                    } else {
+                        handler.disabled(source, cx);
+                    }
+                });
+        }
+    }
+
+    fn enable_option(&mut self, source: TelnetSource, code: u8) {
+        match (source, code) {
+            (TelnetSource::Server, opt::ECHO) => {
+                self.output
+                    .append(TelnetFragment::SetEcho { should_echo: false });
+            }
+            (TelnetSource::Server, opt::GMCP) => {
+                self.send_subnegotiation(gmcp::Handshake::Hello);
+                if !self.config.gmcp_packages.is_empty() {
+                    self.send_subnegotiation(gmcp::Handshake::Set);
+                }
+            }
+            (TelnetSource::Client, opt::MTTS) => self.ttype_negotiator.reset(),
+            (TelnetSource::Client, opt::NAWS) => {
+                self.output.append(TelnetFragment::Naws);
+                if let Some(size) = self.window_size {
+                    self.send_window_size(size);
+                }
+            }
+            (_, opt::MXP) if self.config.use_mxp == UseMxp::Query => self.mxp_on(),
+            (_, opt::MCCP3) => self.start_compression(),
+            (_, opt::MSDP) => self.output.append(TelnetFragment::Msdp { enabled: true }),
+            _ => (),
+        }
+    }
+
+    fn disable_option(&mut self, source: TelnetSource, code: u8) {
+        match (source, code) {
+            (TelnetSource::Server, opt::ECHO) => {
+                self.output
+                    .append(TelnetFragment::SetEcho { should_echo: true });
+            }
+            (TelnetSource::Server, opt::MCCP2) => {
+                info!(target: "mud.decompress", "Decompression disabled");
+                self.decompressing = false;
+            }
+            (TelnetSource::Client, opt::MXP) => self.mxp_off(),
+            (TelnetSource::Client, opt::MTTS) => self.ttype_negotiator.reset(),
+            (TelnetSource::Client, opt::MNES) => self.mnes_variables.clear(),
+            (_, opt::MSP) => {
+                self.msp_sound_url = None;
+                self.msp_music_url = None;
+            }
+            (TelnetSource::Client, opt::CHARSET) => {
+                self.charset = None;
+                self.update_encoding();
+            }
+            (_, opt::MCCP3) => {
+                let state = self.options.get(opt::MCCP3);
+                if !state.enabled(TelnetSource::Client) && !state.enabled(TelnetSource::Server) {
+                    self.end_compression();
+                }
+            }
+            (_, opt::MSDP) => {
+                let state = self.options.get(opt::MSDP);
+                if !state.enabled(TelnetSource::Client) && !state.enabled(TelnetSource::Server) {
+                    self.output.append(TelnetFragment::Msdp { enabled: false });
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn start_compression(&mut self) {
+        if self.input.compressing() {
+            return;
+        }
+        info!(target: "mud.compress", "Beginning compression");
+        self.input
+            .write(&[telnet::IAC, telnet::SB, opt::MCCP3, telnet::IAC, telnet::SE]);
+        self.input.start_compression();
+    }
+
+    fn send_window_size(&mut self, size: naws::WindowSize) {
+        info!(target: "mud.telnet", "Sending window size {}x{}", size.width, size.height);
+        size.encode(&mut self.input).unwrap();
+    }
+
+    fn charset_name(&self) -> &'static str {
+        match self.decoder.encoding() {
+            Encoding::Utf8 if self.config.disable_utf8 => "ASCII",
+            encoding => encoding.name(),
+        }
+    }
+
+    fn update_encoding(&mut self) {
+        let encoding = self.charset.unwrap_or(self.config.encoding);
+        if encoding != self.decoder.encoding() {
+            info!(target: "mud.telnet", "Decoding text as {encoding}");
+            self.decoder.set_encoding(encoding);
+            if self.phase == Phase::MultibyteCharacter {
+                self.phase = Phase::Normal;
+            }
+        }
+        // The charset name also depends on `disable_utf8`, so it may change with the same encoding.
+        if let Some(update) = self.mnes_variables.update_charset(self.charset_name()) {
+            self.send_subnegotiation(update);
+        }
+    }
+
     fn send_subnegotiation<T: Negotiate>(&mut self, negotiator: T) {
         self.input.write(&[telnet::IAC, telnet::SB, T::OPT]);
         negotiator.negotiate(&mut self.input, &self.config).unwrap();
         self.input.write(&[telnet::IAC, telnet::SE]);
     }
 
+    fn update_gmcp_packages(&mut self, old: &[String]) {
+        let packages = &self.config.gmcp_packages;
+        let removed: Vec<String> = old
+            .iter()
+            .filter(|package| !packages.contains(package))
+            .cloned()
+            .collect();
+        let added: Vec<String> = packages
+            .iter()
+            .filter(|package| !old.contains(package))
+            .cloned()
+            .collect();
+        if !removed.is_empty() {
+            self.send_subnegotiation(gmcp::Handshake::Remove(&removed));
+        }
+        if !added.is_empty() {
+            self.send_subnegotiation(gmcp::Handshake::Add(&added));
+        }
+    }
+
+    #[allow(clippy::collapsible_match)]
     fn receive_subnegotiation(&mut self, subnegotiation_type: u8, data: &[u8]) {
         match subnegotiation_type {
             opt::STATUS => {
@@ -296,7 +626,12 @@ impl Transformer {
                 self.input
                     .write(&[telnet::IAC, telnet::SB, opt::STATUS, status::IS]);
                 status::encode(&mut self.input, telnet::WILL, self.config.will).unwrap();
-                status::encode(&mut self.input, telnet::DO, *self.doing).unwrap();
+                status::encode(
+                    &mut self.input,
+                    telnet::DO,
+                    self.options.enabled_codes(TelnetSource::Client),
+                )
+                .unwrap();
                 self.input.write(&[telnet::IAC, telnet::SE]);
             }
             opt::MTTS => {
@@ -310,21 +645,26 @@ impl Transformer {
                 let [mnes::SEND, request @ ..] = data else {
                     return;
                 };
-                self.mnes_variables = mnes::Variables::from(request);
+                self.mnes_variables =
+                    mnes::Variables::from(request).with_charset(self.charset_name());
                 self.send_subnegotiation(self.mnes_variables);
             }
             opt::CHARSET => {
                 let [charset::REQUEST, request @ ..] = data else {
                     return;
                 };
-                match charset::Charsets::decode(request) {
-                    Ok(charsets) => self.charsets = charsets,
+                let charsets = match charset::Charsets::decode(request, &self.config) {
+                    Ok(charsets) => charsets,
                     Err(e) => {
                         error!(target: "mud.telnet", "Error decoding charsets: {e}");
                         return;
                     }
+                };
+                self.send_subnegotiation(charsets);
+                if charsets.is_accepted() {
+                    self.charset = charsets.encoding();
+                    self.update_encoding();
                 }
-                self.send_subnegotiation(self.charsets);
             }
             opt::MCCP2 => {
                 if !self.config.disable_compression {
@@ -419,18 +759,29 @@ impl Transformer {
 
     fn mxp_collect_entity(&mut self) -> mxp::Result<()> {
         let name = mxp::validate_utf8(&self.mxp_entity_string)?;
-        let entity = self.mxp_state.decode_entity(name)?;
-        write!(self.output, "{entity}");
+        match self.mxp_state.decode_entity(name) {
+            Ok(entity) => write!(self.output, "{entity}"),
+            Err(e) if self.mxp_mode.options().recovers(e.kind()) => {
+                write!(self.output, "&{name};");
+                self.mxp_warnings.push(e);
+            }
+            Err(e) => return Err(e),
+        }
         Ok(())
     }
 
     fn mxp_collect_element(&mut self, entity_string: &[u8]) -> mxp::Result<()> {
         let secure = self.mxp_mode.use_secure();
         let source = mxp::validate_utf8(entity_string)?;
-        match Tag::parse(source, secure)? {
+        let options = self.mxp_mode.options();
+        match Tag::parse_with(source, secure, options, &mut self.mxp_warnings)? {
             Tag::Close(tag) => {
-                let closed = self.mxp_tags.find_last(secure, tag.name)?;
-                self.mxp_close_tags_from(closed);
+                let closed =
+                    self.mxp_tags
+                        .find_last(secure, tag.name, options, &mut self.mxp_warnings)?;
+                if let Some(closed) = closed {
+                    self.mxp_close_tags_from(closed);
+                }
                 Ok(())
             }
             Tag::Definition(definition) => {
@@ -471,14 +822,24 @@ impl Transformer {
         let component = mxp_state.get_component(tag.name, secure)?;
 
         if !component.is_command() {
+            let max_depth = mxp_state.limits().max_depth;
             self.mxp_tags
-                .open(component, secure, self.output.span_len());
+                .open(component, secure, self.output.span_len(), max_depth)?;
         }
 
         match component {
             mxp::Component::AtomicTag(atom) => {
-                let action = atom.decode(&tag.arguments, mxp_state)?;
-                self.mxp_apply_action(action);
+                let decoder = self.mxp_decoder(mxp_state);
+                let action = atom.decode(&tag.arguments, &decoder);
+                self.mxp_warnings.extend(decoder.take_warnings());
+                self.mxp_apply_action(action?, mxp_state);
+                Ok(())
+            }
+            mxp::Component::Custom(custom) => {
+                let decoder = self.mxp_decoder(mxp_state);
+                let action = custom.decode(&tag.arguments, &decoder);
+                self.mxp_warnings.extend(decoder.take_warnings());
+                self.mxp_apply_action(action?, mxp_state);
                 Ok(())
             }
             mxp::Component::Element(el) => {
@@ -524,19 +885,27 @@ impl Transformer {
         if let Some(flag) = &el.flag {
             self.mxp_set_flag(flag, args, empty || el.empty);
         }
-        for action in el.decode(args, mxp_state) {
-            self.mxp_apply_action(action?);
-        }
-        Ok(())
+        let decoder = self.mxp_decoder(mxp_state);
+        let result = el.decode(args, &decoder).try_for_each(|action| {
+            self.mxp_apply_action(action?, mxp_state);
+            Ok(())
+        });
+        self.mxp_warnings.extend(decoder.take_warnings());
+        result
     }
 
-    fn mxp_apply_action(&mut self, action: mxp::Action<Cow<str>>) {
+    fn mxp_decoder<'a>(&self, mxp_state: &'a mxp::State) -> mxp::LenientDecoder<&'a mxp::State> {
+        mxp::LenientDecoder::new(mxp_state, self.mxp_mode.options())
+    }
+
+    fn mxp_apply_action(&mut self, action: mxp::Action<Cow<str>>, mxp_state: &mxp::State) {
         use mxp::Action;
 
         match action {
             Action::Bold => self.output.set_mxp_style(TextStyle::Bold),
             Action::Br => self.output.start_line(),
             Action::Color(m) => self.output.set_mxp_color(m),
+            Action::Custom(m) => self.output.append(m.into_owned()),
             Action::Dest(m) => self.output.set_mxp_window(m),
             Action::Expire(m) => self.output.append(m.into_owned()),
             Action::Filter(m) => self.output.append(m.into_owned()),
@@ -546,10 +915,22 @@ impl Transformer {
             Action::Heading(m) => self.output.set_mxp_heading(m),
             Action::Highlight => self.output.set_mxp_style(TextStyle::Highlight),
             Action::Hr => self.output.append(OutputFragment::Hr),
-            Action::Hyperlink(m) => self.output.set_mxp_link(m.into_owned()),
-            Action::Image(m) => self.output.append(m.into_owned()),
+            Action::Hyperlink(m) => {
+                if self.permit_link(&m.href, LinkPolicy::check_url) {
+                    self.output.set_mxp_link(m.into_owned());
+                }
+            }
+            Action::Image(mut m) => {
+                if self.permit_media(&m.fname, &mut m.url) {
+                    self.output.append(m.into_owned());
+                }
+            }
             Action::Italic => self.output.set_mxp_style(TextStyle::Italic),
-            Action::Music(m) => self.output.append(m.into_owned()),
+            Action::Music(mut m) => {
+                if self.permit_media(&m.fname, &mut m.url) {
+                    self.output.append(m.into_owned());
+                }
+            }
             Action::MusicOff => self.output.append(MxpFragment::MusicOff),
             Action::MxpOff | Action::Reset => (),
             Action::NoBr => self.ignore_next_newline = true,
@@ -557,9 +938,17 @@ impl Transformer {
             Action::Password => input_mxp_auth(&mut self.input, &self.config.password),
             Action::Relocate(m) => self.output.append(m.into_owned()),
             Action::SBr => self.output.write_str(" "),
-            Action::Send(m) => self.output.set_mxp_link(m.into_owned()),
+            Action::Send(m) => {
+                if self.permit_link(&m.href, LinkPolicy::check_command) {
+                    self.output.set_mxp_link(m.into_owned());
+                }
+            }
             Action::Small => self.output.set_mxp_style(TextStyle::Small),
-            Action::Sound(m) => self.output.append(m.into_owned()),
+            Action::Sound(mut m) => {
+                if self.permit_media(&m.fname, &mut m.url) {
+                    self.output.append(m.into_owned());
+                }
+            }
             Action::SoundOff => self.output.append(MxpFragment::SoundOff),
             Action::Stat(m) => self.output.append(m.into_owned()),
             Action::Strikeout => self.output.set_mxp_style(TextStyle::Strikeout),
@@ -568,7 +957,13 @@ impl Transformer {
                 self.config.style_version = Some(m.styleversion.clone());
                 self.output.append(m);
             }
-            Action::Support(m) => write!(self.input, "{}", self.config.support_response(m)),
+            Action::Support(m) => {
+                let response = self
+                    .config
+                    .support_response(m)
+                    .with_custom_tags(mxp_state.custom_tags());
+                write!(self.input, "{response}");
+            }
             Action::Tt => self.output.set_mxp_style(TextStyle::NonProportional),
             Action::Underline => self.output.set_mxp_style(TextStyle::Underline),
             Action::User => input_mxp_auth(&mut self.input, &self.config.player),
@@ -577,10 +972,107 @@ impl Transformer {
         }
     }
 
+    fn permit_link(&self, link: &str, check: CheckLink) -> bool {
+        match check(&self.config.link_policy, link) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!(target: "mud.mxp", "Rejected link {link:?}: {e}");
+                false
+            }
+        }
+    }
+
+    /// Returns `false` if the file name is rejected. If only the URL is rejected, it is removed.
+    fn permit_media(&self, fname: &str, url: &mut Option<Cow<str>>) -> bool {
+        if !self.permit_link(fname, LinkPolicy::check_path) {
+            return false;
+        }
+        if let Some(link) = url
+            && !self.permit_link(link, LinkPolicy::check_url)
+        {
+            *url = None;
+        }
+        true
+    }
+
+    fn msp_active(&self) -> bool {
+        self.options.enabled(TelnetSource::Server, opt::MSP)
+            || self.options.enabled(TelnetSource::Client, opt::MSP)
+    }
+
+    fn msp_collect_trigger(&mut self, c: u8) {
+        self.msp_trigger.push(c);
+        if msp::is_trigger(&self.msp_trigger) {
+            self.phase = Phase::Normal;
+            let trigger = mem::take(&mut self.msp_trigger);
+            match msp::parse(&String::from_utf8_lossy(&trigger)) {
+                Ok(element) => self.msp_apply(element),
+                Err(e) => warn!(target: "mud.msp", "{e}"),
+            }
+        } else if !msp::is_trigger_prefix(&self.msp_trigger) {
+            self.msp_trigger.pop();
+            self.phase = Phase::Normal;
+            self.msp_unterminated();
+            self.receive_byte(c);
+        } else if self.msp_trigger.len() > msp::MAX_TRIGGER_LEN {
+            self.phase = Phase::Normal;
+            self.msp_unterminated();
+        }
+    }
+
+    fn msp_apply(&mut self, element: msp::Element<&str>) {
+        match element {
+            msp::Element::Sound(sound) if sound.fname.eq_ignore_ascii_case("off") => {
+                match sound.url {
+                    Some(url) => self.msp_sound_url = Some(url.to_owned()),
+                    None => self.output.append(MxpFragment::SoundOff),
+                }
+            }
+            msp::Element::Music(music) if music.fname.eq_ignore_ascii_case("off") => {
+                match music.url {
+                    Some(url) => self.msp_music_url = Some(url.to_owned()),
+                    None => self.output.append(MxpFragment::MusicOff),
+                }
+            }
+            msp::Element::Sound(sound) => {
+                let mut sound = sound.map_text(Cow::Borrowed);
+                if sound.url.is_none() {
+                    sound.url = self.msp_sound_url.as_deref().map(Cow::Borrowed);
+                }
+                if self.permit_media(&sound.fname, &mut sound.url) {
+                    let sound = sound.into_owned();
+                    self.output.append(sound);
+                }
+            }
+            msp::Element::Music(music) => {
+                let mut music = music.map_text(Cow::Borrowed);
+                if music.url.is_none() {
+                    music.url = self.msp_music_url.as_deref().map(Cow::Borrowed);
+                }
+                if self.permit_media(&music.fname, &mut music.url) {
+                    let music = music.into_owned();
+                    self.output.append(music);
+                }
+            }
+        }
+    }
+
+    /// Writes an incomplete trigger as text.
+    fn msp_unterminated(&mut self) {
+        let trigger = mem::take(&mut self.msp_trigger);
+        self.output.write_str(&String::from_utf8_lossy(&trigger));
+    }
+
     fn mxp_unterminated(&mut self, error: mxp::ErrorKind) {
         let entity_string = String::from_utf8_lossy(&self.mxp_entity_string);
-        let e = mxp::Error::new(entity_string, error);
-        warn!(target: "mud.mxp", "{e}");
+        if error == mxp::ErrorKind::UnterminatedEntity && self.mxp_mode.options().recovers(error) {
+            write!(self.output, "&{entity_string}");
+            let e = mxp::Error::new(entity_string, error);
+            warn!(target: "mud.mxp", "{e} (recovered)");
+        } else {
+            let e = mxp::Error::new(entity_string, error);
+            warn!(target: "mud.mxp", "{e}");
+        }
         self.mxp_entity_string.clear();
         self.mxp_mode.use_secure(); // clear SECURE_ONCE
     }
@@ -596,26 +1088,19 @@ impl Transformer {
             self.output.append(ControlFragment::CarriageReturn);
         }
 
-        if self.phase == Phase::Utf8Character && !is_utf8_continuation(c) {
-            let sequence = match str::from_utf8(&self.utf8_sequence) {
-                Ok(sequence) => sequence,
-                Err(e) => {
-                    warn!(target: "mud.telnet", "Malformed UTF-8: {:?} ({e})", self.utf8_sequence);
-                    "\u{FFFD}"
-                }
-            };
-            self.output.write_str(sequence);
+        if self.phase == Phase::MultibyteCharacter && !self.decoder.continues(c) {
+            self.output.write_str(self.decoder.flush());
             self.phase = Phase::Normal;
         }
 
         if self.phase.is_phase_reset(c) {
-            self.phase = Phase::Normal;
-            match self.phase {
+            match mem::replace(&mut self.phase, Phase::Normal) {
                 Phase::Ansi | Phase::AnsiString | Phase::Esc => self.ansi.terminate(),
                 Phase::MxpComment => self.mxp_unterminated(mxp::ErrorKind::UnterminatedComment),
                 Phase::MxpElement => self.mxp_unterminated(mxp::ErrorKind::UnterminatedElement),
                 Phase::MxpEntity => self.mxp_unterminated(mxp::ErrorKind::UnterminatedEntity),
                 Phase::MxpQuote => self.mxp_unterminated(mxp::ErrorKind::UnterminatedQuote),
+                Phase::MspTrigger => self.msp_unterminated(),
                 _ => (),
             }
         }
@@ -631,6 +1116,11 @@ impl Transformer {
                 }
                 match c {
                     b' ' if self.in_paragraph && last_char == b' ' => (),
+                    b'!' if self.msp_active() && self.output.at_line_start() => {
+                        self.msp_trigger.clear();
+                        self.msp_trigger.push(c);
+                        self.phase = Phase::MspTrigger;
+                    }
                     b'<' if self.mxp_active && !self.mxp_mode.is_locked() => {
                         self.mxp_entity_string.clear();
                         self.phase = Phase::MxpElement;
@@ -682,11 +1172,11 @@ impl Transformer {
                     ansi::BS => self.output.append(CursorEffect::Back(1)),
                     ansi::VT => self.output.append(ControlFragment::VerticalTab),
                     ansi::FF => self.output.append(OutputFragment::PageBreak),
-                    128..248 => {
-                        self.utf8_sequence.push(c);
-                        self.phase = Phase::Utf8Character;
-                    }
-                    ..32 | ansi::DEL | 248.. => {
+                    128.. => match self.decoder.push(c) {
+                        Some(decoded) => self.output.write_str(decoded),
+                        None => self.phase = Phase::MultibyteCharacter,
+                    },
+                    ..32 | ansi::DEL => {
                         info!(target: "mud.telnet", "Unhandled character: {c:#x}");
                     }
                 }
@@ -705,7 +1195,14 @@ impl Transformer {
                 }
             }
 
-            Phase::Utf8Character => self.utf8_sequence.push(c),
+            Phase::MspTrigger => self.msp_collect_trigger(c),
+
+            Phase::MultibyteCharacter => {
+                if let Some(decoded) = self.decoder.push(c) {
+                    self.output.write_str(decoded);
+                    self.phase = Phase::Normal;
+                }
+            }
 
             Phase::Ansi | Phase::AnsiString if c == ansi::ESC => {
                 self.phase = Phase::Esc;
@@ -722,7 +1219,12 @@ impl Transformer {
                     xterm::Outcome::Done => (),
                     xterm::Outcome::Link => {
                         if let Some(link) = self.ansi.take_mslp_link() {
-                            self.output.set_mxp_link(link);
+                            match self.config.link_policy.check_command(&link.href) {
+                                Ok(()) => self.output.set_mxp_link(link),
+                                Err(e) => {
+                                    warn!(target: "mud.telnet", "Rejected MSLP link {:?}: {e}", link.href);
+                                }
+                            }
                         } else if self.config.linkify_underlined {
                             self.output.set_mxp_link(mxp::Send::default());
                         }
@@ -773,93 +1275,15 @@ impl Transformer {
                 }
             }
 
-            Phase::Will => {
-                self.phase = Phase::Normal;
-                self.output.append(TelnetFragment::Negotiation {
-                    source: TelnetSource::Server,
-                    verb: TelnetVerb::Will,
-                    code: c,
-                });
-                let supported = self.config.will.contains(c);
-                if supported {
-                    match c {
-                        opt::ECHO => self
-                            .output
-                            .append(TelnetFragment::SetEcho { should_echo: false }),
-                        opt::MXP if self.config.use_mxp == UseMxp::Query => self.mxp_on(),
-                        _ => (),
-                    }
-                }
-                let verb = if supported {
-                    TelnetVerb::Do
-                } else {
-                    TelnetVerb::Dont
-                };
-                self.send_negotiation(verb, c);
-            }
-
-            Phase::Wont => {
-                self.phase = Phase::Normal;
-                self.output.append(TelnetFragment::Negotiation {
-                    source: TelnetSource::Server,
-                    verb: TelnetVerb::Wont,
-                    code: c,
-                });
-                if self.config.will.contains(c) {
-                    match c {
-                        opt::ECHO => self
-                            .output
-                            .append(TelnetFragment::SetEcho { should_echo: true }),
-                        opt::MCCP2 => {
-                            info!(target: "mud.decompress", "Decompression disabled");
-                            self.decompressing = false;
-                        }
-                        _ => (),
-                    }
-                }
-                self.send_negotiation(TelnetVerb::Dont, c);
-            }
-
-            Phase::Do => {
-                self.phase = Phase::Normal;
-                self.output.append(TelnetFragment::Negotiation {
-                    source: TelnetSource::Server,
-                    verb: TelnetVerb::Do,
-                    code: c,
-                });
-                let supported = self.config.will.contains(c);
-                if supported {
-                    self.doing.insert(c);
-                    match c {
-                        opt::MTTS => self.ttype_negotiator.reset(),
-                        opt::NAWS => self.output.append(TelnetFragment::Naws),
-                        opt::MXP if self.config.use_mxp == UseMxp::Query => self.mxp_on(),
-                        _ => (),
-                    }
-                }
-                let verb = if supported {
-                    TelnetVerb::Will
-                } else {
-                    TelnetVerb::Wont
+            Phase::Will | Phase::Wont | Phase::Do | Phase::Dont => {
+                let verb = match self.phase {
+                    Phase::Will => TelnetVerb::Will,
+                    Phase::Wont => TelnetVerb::Wont,
+                    Phase::Do => TelnetVerb::Do,
+                    _ => TelnetVerb::Dont,
                 };
-                self.send_negotiation(verb, c);
-            }
-
-            Phase::Dont => {
-                self.doing.remove(c);
-                self.output.append(TelnetFragment::Negotiation {
-                    source: TelnetSource::Server,
-                    verb: TelnetVerb::Dont,
-                    code: c,
-                });
                 self.phase = Phase::Normal;
-                match c {
-                    opt::MXP if self.mxp_active => self.mxp_off(),
-                    opt::MTTS => self.ttype_negotiator.reset(),
-                    opt::MNES => self.mnes_variables.clear(),
-                    _ => (),
-                }
-                self.send_negotiation(TelnetVerb::Wont, c);
+                self.receive_negotiation(verb, c);
             }
 
             Phase::Sb => {
@@ -883,11 +1307,24 @@ impl Transformer {
                         "Subnegotiation terminated with {c} instead of SE: {data:?}"
                     );
                 }
-                self.receive_subnegotiation(self.subnegotiation_type, &data);
+                let code = self.subnegotiation_type;
+                self.receive_subnegotiation(code, &data);
                 self.output.append(TelnetFragment::Subnegotiation {
-                    code: self.subnegotiation_type,
-                    data,
+                    code,
+                    data: data.clone(),
                 });
+                if self.options.enabled(TelnetSource::Client, code)
+                    || self.options.enabled(TelnetSource::Server, code)
+                {
+                    self.option_handlers.handle(
+                        code,
+                        &mut self.input,
+                        &mut self.output,
+                        |handler, cx| {
+                            handler.subnegotiation(&data, cx);
+                        },
+                    );
+                }
             }
 
             Phase::MxpElement => match c {
@@ -895,8 +1332,18 @@ impl Transformer {
                     self.phase = Phase::Normal;
                     let mut entity_string = Vec::new(); // never allocates
                     mem::swap(&mut entity_string, &mut self.mxp_entity_string);
-                    if let Err(mut e) = self.mxp_collect_element(&entity_string) {
-                        if let Ok(source) = str::from_utf8(&entity_string) {
+                    let result = self.mxp_collect_element(&entity_string);
+                    let source = str::from_utf8(&entity_string).ok();
+                    for warning in self.mxp_warnings.drain(..) {
+                        match source {
+                            Some(source) => {
+                                warn!(target: "mud.mxp", "{warning} (recovered in <{source}>)");
+                            }
+                            None => warn!(target: "mud.mxp", "{warning} (recovered)"),
+                        }
+                    }
+                    if let Err(mut e) = result {
+                        if let Some(source) = source {
                             e = e.with_context(format_args!(" (in <{source}>)"));
                         }
                         warn!(target: "mud.mxp", "{e}");
@@ -942,6 +1389,9 @@ impl Transformer {
                     if let Err(e) = self.mxp_collect_entity() {
                         warn!(target: "mud.mxp", "{e}");
                     }
+                    for warning in self.mxp_warnings.drain(..) {
+                        warn!(target: "mud.mxp", "{warning} (recovered)");
+                    }
                 }
                 b'&' => self.mxp_unterminated(mxp::ErrorKind::UnterminatedEntity),
                 b'<' => {
@@ -953,8 +1403,3 @@ impl Transformer {
         }
     }
 }
-
-#[inline]
-const fn is_utf8_continuation(c: u8) -> bool {
-    (c & 0xC0) == 0x80
-}
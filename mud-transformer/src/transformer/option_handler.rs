@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::escape::telnet;
+use crate::input::BufferedInput;
+use crate::output::{BufferedOutput, OutputFragment};
+use crate::protocol::{TelnetSource, write_escaping_iac};
+
+/// Handler for a telnet option that is not built into the transformer, such as a protocol
+/// specific to one MUD.
+///
+/// Handlers are registered for an option code with [`Transformer::register_option_handler`].
+/// Negotiation of the option is performed by the transformer, which accepts the option on any side
+/// for which [`supports`](Self::supports) returns true. Handlers are notified once the option is
+/// enabled or disabled, and receive the data of every subnegotiation for the option. They can
+/// respond through the provided [`OptionContext`].
+///
+/// Subnegotiations are still emitted as [`TelnetFragment::Subnegotiation`] after the handler has
+/// received them.
+///
+/// [`Transformer::register_option_handler`]: crate::Transformer::register_option_handler
+/// [`TelnetFragment::Subnegotiation`]: crate::output::TelnetFragment::Subnegotiation
+pub trait TelnetOptionHandler: Send + Sync {
+    /// Returns true if the option may be enabled on the specified side.
+    /// [`TelnetSource::Client`] means the client performs the option, as negotiated with `DO`,
+    /// and [`TelnetSource::Server`] means the server performs it, as negotiated with `WILL`.
+    fn supports(&self, source: TelnetSource) -> bool {
+        let _ = source;
+        true
+    }
+
+    /// Called when the option is enabled on the specified side.
+    fn enabled(&mut self, source: TelnetSource, cx: &mut OptionContext) {
+        let _ = (source, cx);
+    }
+
+    /// Called when the option is disabled on the specified side.
+    fn disabled(&mut self, source: TelnetSource, cx: &mut OptionContext) {
+        let _ = (source, cx);
+    }
+
+    /// Called with the data of a subnegotiation for the option, without the surrounding `IAC SB`
+    /// and `IAC SE` and with escaped `IAC` bytes unescaped. Subnegotiations are only passed to the
+    /// handler while the option is enabled on either side.
+    fn subnegotiation(&mut self, data: &[u8], cx: &mut OptionContext) {
+        let _ = (data, cx);
+    }
+}
+
+/// Access to the transformer's input and output for a [`TelnetOptionHandler`].
+pub struct OptionContext<'a> {
+    code: u8,
+    input: &'a mut BufferedInput,
+    output: &'a mut BufferedOutput,
+}
+
+impl OptionContext<'_> {
+    /// Code of the option being handled.
+    pub fn code(&self) -> u8 {
+        self.code
+    }
+
+    /// Sends a subnegotiation for the option to the server. `IAC` bytes in `data` are escaped.
+    pub fn subnegotiate(&mut self, data: &[u8]) {
+        self.input.write(&[telnet::IAC, telnet::SB, self.code]);
+        write_escaping_iac(&mut *self.input, data).unwrap();
+        self.input.write(&[telnet::IAC, telnet::SE]);
+    }
+
+    /// Sends bytes to the server as-is.
+    pub fn write(&mut self, bytes: &[u8]) {
+        self.input.write(bytes);
+    }
+
+    /// Adds a fragment to the transformer's output.
+    pub fn append<T: Into<OutputFragment>>(&mut self, fragment: T) {
+        self.output.append(fragment);
+    }
+}
+
+/// Registered handlers, keyed by option code.
+#[derive(Default)]
+pub(crate) struct OptionHandlers {
+    handlers: HashMap<u8, Box<dyn TelnetOptionHandler>>,
+}
+
+impl fmt::Debug for OptionHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
+
+impl OptionHandlers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(
+        &mut self,
+        code: u8,
+        handler: Box<dyn TelnetOptionHandler>,
+    ) -> Option<Box<dyn TelnetOptionHandler>> {
+        self.handlers.insert(code, handler)
+    }
+
+    pub fn remove(&mut self, code: u8) -> Option<Box<dyn TelnetOptionHandler>> {
+        self.handlers.remove(&code)
+    }
+
+    pub fn supports(&self, source: TelnetSource, code: u8) -> bool {
+        self.handlers
+            .get(&code)
+            .is_some_and(|handler| handler.supports(source))
+    }
+
+    /// Calls the handler for an option, if one is registered.
+    pub fn handle<F>(
+        &mut self,
+        code: u8,
+        input: &mut BufferedInput,
+        output: &mut BufferedOutput,
+        f: F,
+    ) where
+        F: FnOnce(&mut dyn TelnetOptionHandler, &mut OptionContext),
+    {
+        let Some(handler) = self.handlers.get_mut(&code) else {
+            return;
+        };
+        let mut cx = OptionContext {
+            code,
+            input,
+            output,
+        };
+        f(handler.as_mut(), &mut cx);
+    }
+}
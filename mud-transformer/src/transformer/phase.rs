@@ -28,8 +28,11 @@ pub(crate) enum Phase {
     /// Received TELNET IAC SB c <data> IAC (awaiting IAC or SE)
     SubnegotiationIac,
 
-    /// Received 110 xxxxx, 1110 xxxx, or 11110 xxx
-    Utf8Character,
+    /// Received the first byte of a multibyte character
+    MultibyteCharacter,
+
+    /// Collecting MSP trigger, eg. !!SOUND(xxxxx). Starts on ! at line start, stops on )
+    MspTrigger,
 
     // MXP modes
     /// Collecting element, eg. < xxxxx >. Starts on <, stops on >
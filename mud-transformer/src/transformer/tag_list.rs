@@ -35,12 +35,25 @@ impl TagList {
         self.base.clear();
     }
 
-    pub fn open(&mut self, component: mxp::Component, secure: bool, span_index: usize) {
+    pub fn open(
+        &mut self,
+        component: mxp::Component,
+        secure: bool,
+        span_index: usize,
+        max_depth: usize,
+    ) -> mxp::Result<()> {
+        if self.base.len() >= max_depth {
+            return Err(mxp::Error::new(
+                component.name(),
+                mxp::ErrorKind::TagsNestedTooDeeply,
+            ));
+        }
         self.base.push(Tag {
             name: component.name().to_owned(),
             secure,
             span_index,
         });
+        Ok(())
     }
 
     pub fn last_open_index(&self) -> usize {
@@ -50,13 +63,28 @@ impl TagList {
         }
     }
 
-    pub fn find_last(&self, secure: bool, name: &str) -> mxp::Result<usize> {
+    /// Finds the most recent tag with the specified name. Returns `Ok(None)` if the closing tag
+    /// should be ignored.
+    pub fn find_last(
+        &self,
+        secure: bool,
+        name: &str,
+        options: mxp::ParseOptions,
+        warnings: &mut Vec<mxp::Error>,
+    ) -> mxp::Result<Option<usize>> {
+        let mut recover = |error: mxp::Error| {
+            if !options.recovers(error.kind()) {
+                return Err(error);
+            }
+            warnings.push(error);
+            Ok(())
+        };
         for (i, tag) in self.base.iter().enumerate().rev() {
             if tag.name.eq_ignore_ascii_case(name) {
                 if !secure && tag.secure {
-                    return Err(mxp::Error::new(name, mxp::ErrorKind::TagOpenedInSecureMode));
+                    recover(mxp::Error::new(name, mxp::ErrorKind::TagOpenedInSecureMode))?;
                 }
-                return Ok(i);
+                return Ok(Some(i));
             }
             if !secure && tag.secure {
                 return Err(mxp::Error::new(
@@ -65,6 +93,7 @@ impl TagList {
                 ));
             }
         }
-        Err(mxp::Error::new(name, mxp::ErrorKind::UnmatchedCloseTag))
+        recover(mxp::Error::new(name, mxp::ErrorKind::UnmatchedCloseTag))?;
+        Ok(None)
     }
 }
@@ -1,8 +1,9 @@
 mod common;
 use std::fmt::Write as _;
 
-use common::transform;
-use mud_transformer::output::ControlFragment;
+use common::{transform, transform_with};
+use mud_transformer::TransformerConfig;
+use mud_transformer::output::{ControlFragment, OutputFragment, TextFragment};
 
 #[test]
 fn set_title() {
@@ -30,3 +31,28 @@ fn load_answerback() {
     assert_eq!(transformer.input(), "Test answerback");
     assert_eq!(transformer.output(), &[]);
 }
+
+#[test]
+fn insert_as_text() {
+    let output = transform("\x1B[2@\x1B[2L").output();
+    let expected = &[
+        TextFragment::from("  ").into(),
+        OutputFragment::LineBreak,
+        OutputFragment::LineBreak,
+    ];
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn insert_controls() {
+    let config = TransformerConfig {
+        console_controls: true,
+        ..Default::default()
+    };
+    let output = transform_with(config, "\x1B[2@\x1B[2L").output();
+    let expected = &[
+        ControlFragment::InsertCharacters(2).into(),
+        ControlFragment::InsertLines(2).into(),
+    ];
+    assert_eq!(output, expected);
+}
@@ -0,0 +1,149 @@
+mod common;
+use common::transform_with;
+use mud_transformer::TransformerConfig;
+use mud_transformer::escape::telnet;
+use mud_transformer::opt::msp;
+use mud_transformer::output::{AudioChannel, AudioCue, AudioEvent, AudioState, MxpFragment};
+
+fn sound(fname: &str, priority: u8) -> mxp::Sound {
+    mxp::Sound {
+        fname: fname.to_owned(),
+        priority,
+        ..Default::default()
+    }
+}
+
+fn music(fname: &str, continual: bool) -> mxp::Music {
+    mxp::Music {
+        fname: fname.to_owned(),
+        continual,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn sound_priority() {
+    let mut audio = AudioState::new();
+    let low = sound("low.wav", 10);
+    let high = sound("high.wav", 90);
+    assert_eq!(
+        audio.apply_fragment(&MxpFragment::Sound(low.clone())),
+        [AudioEvent::Start(AudioCue::Sound(low.clone()))]
+    );
+    assert_eq!(
+        audio.apply_fragment(&MxpFragment::Sound(high.clone())),
+        [
+            AudioEvent::Stop(AudioChannel::Sound),
+            AudioEvent::Start(AudioCue::Sound(high.clone()))
+        ]
+    );
+    assert!(audio.apply_fragment(&MxpFragment::Sound(low)).is_empty());
+    assert!(
+        audio
+            .apply_fragment(&MxpFragment::Sound(sound("tie.wav", 90)))
+            .is_empty()
+    );
+    assert_eq!(
+        audio.playing(AudioChannel::Sound),
+        Some(&AudioCue::Sound(high))
+    );
+    assert_eq!(
+        audio.apply_fragment(&MxpFragment::SoundOff),
+        [AudioEvent::Stop(AudioChannel::Sound)]
+    );
+    assert_eq!(audio.playing(AudioChannel::Sound), None);
+}
+
+#[test]
+fn continual_music() {
+    let mut audio = AudioState::new();
+    audio.apply_fragment(&MxpFragment::Music(music("town.mid", false)));
+    let again = mxp::Music {
+        volume: 50,
+        ..music("town.mid", true)
+    };
+    assert_eq!(
+        audio.apply_fragment(&MxpFragment::Music(again.clone())),
+        [AudioEvent::Keep(AudioCue::Music(again))]
+    );
+    let restart = music("town.mid", false);
+    assert_eq!(
+        audio.apply_fragment(&MxpFragment::Music(restart.clone())),
+        [
+            AudioEvent::Stop(AudioChannel::Music),
+            AudioEvent::Start(AudioCue::Music(restart))
+        ]
+    );
+    assert_eq!(
+        audio.apply_fragment(&MxpFragment::Music(music("forest.mid", true)))[0],
+        AudioEvent::Stop(AudioChannel::Music)
+    );
+}
+
+#[test]
+fn repetition() {
+    let mut audio = AudioState::new();
+    let twice = mxp::Sound {
+        repeat: 2.try_into().unwrap(),
+        ..sound("bell.wav", 50)
+    };
+    audio.apply_fragment(&MxpFragment::Sound(twice.clone()));
+    assert_eq!(
+        audio.finished(AudioChannel::Sound),
+        [AudioEvent::Start(AudioCue::Sound(twice))]
+    );
+    assert!(audio.finished(AudioChannel::Sound).is_empty());
+    assert_eq!(audio.playing(AudioChannel::Sound), None);
+
+    let forever = mxp::Music {
+        repeat: mxp::AudioRepetition::Forever,
+        ..music("theme.mid", false)
+    };
+    audio.apply_fragment(&MxpFragment::Music(forever.clone()));
+    for _ in 0..3 {
+        assert_eq!(
+            audio.finished(AudioChannel::Music),
+            [AudioEvent::Start(AudioCue::Music(forever.clone()))]
+        );
+    }
+}
+
+#[test]
+fn default_url() {
+    let mut audio = AudioState::new();
+    let off = mxp::Sound {
+        url: Some("http://example.org/sounds/".to_owned()),
+        ..sound("Off", 50)
+    };
+    assert!(audio.apply_fragment(&MxpFragment::Sound(off)).is_empty());
+    assert_eq!(
+        audio.default_url(AudioChannel::Sound),
+        Some("http://example.org/sounds/")
+    );
+    audio.apply_fragment(&MxpFragment::Sound(sound("bell.wav", 50)));
+    let cue = audio.playing(AudioChannel::Sound).unwrap();
+    assert_eq!(cue.url(), Some("http://example.org/sounds/"));
+    assert_eq!(audio.default_url(AudioChannel::Music), None);
+    assert_eq!(audio.reset(), [AudioEvent::Stop(AudioChannel::Sound)]);
+    assert_eq!(audio.default_url(AudioChannel::Sound), None);
+}
+
+#[test]
+fn applies_msp_output() {
+    let mut config = TransformerConfig::new();
+    config.will.insert(msp::OPT);
+    let mut transformer = transform_with(config, [telnet::IAC, telnet::WILL, msp::OPT]);
+    transformer.receive(
+        b"!!MUSIC(town.mid L=-1)\r\n!!SOUND(bell.wav)\r\n!!MUSIC(Off)\r\n",
+        &mut [0; 1024],
+    );
+    let mut audio = AudioState::new();
+    let events: Vec<AudioEvent> = transformer
+        .flush_output()
+        .flat_map(|output| audio.apply(&output))
+        .collect();
+    assert_eq!(events.len(), 3);
+    assert!(matches!(&events[0], AudioEvent::Start(cue) if cue.fname() == "town.mid"));
+    assert!(matches!(&events[1], AudioEvent::Start(cue) if cue.fname() == "bell.wav"));
+    assert_eq!(events[2], AudioEvent::Stop(AudioChannel::Music));
+}
@@ -1,4 +1,4 @@
 #![allow(unused)]
 
 mod transformer;
-pub use transformer::{transform, transform_with};
+pub use transformer::{TestTransformer, receive, text, transform, transform_with};
@@ -40,11 +40,15 @@ impl DerefMut for TestTransformer {
 impl TestTransformer {
     #[track_caller]
     pub fn input(&mut self) -> String {
+        String::from_utf8(self.input_bytes()).expect("invalid UTF-8")
+    }
+
+    pub fn input_bytes(&mut self) -> Vec<u8> {
         let mut input = Vec::new();
         if let Some(mut drain) = self.transformer.drain_input() {
             drain.read_to_end(&mut input).unwrap();
         }
-        String::from_utf8(input).expect("invalid UTF-8")
+        input
     }
 
     pub fn output(&mut self) -> Vec<OutputFragment> {
@@ -54,3 +58,18 @@ impl TestTransformer {
             .collect()
     }
 }
+
+pub fn receive(transformer: &mut Transformer, bytes: &[u8]) {
+    transformer.receive(bytes, &mut [0; 1024]);
+}
+
+pub fn text(output: &[OutputFragment]) -> String {
+    output
+        .iter()
+        .filter_map(|fragment| match fragment {
+            OutputFragment::Text(fragment) => Some(fragment.text.to_string()),
+            OutputFragment::LineBreak => Some("\n".to_owned()),
+            _ => None,
+        })
+        .collect()
+}
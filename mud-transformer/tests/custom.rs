@@ -0,0 +1,60 @@
+use std::borrow::Cow;
+
+use mud_transformer::output::OutputFragment;
+use mud_transformer::{Transformer, TransformerConfig, UseMxp};
+use mxp::element::CustomTag;
+
+const MAPPER: CustomTag = CustomTag {
+    name: "MAPPER",
+    args: &["room", "exits"],
+    open: false,
+    command: true,
+    decode: CustomTag::decode_args,
+};
+
+fn transformer() -> Transformer {
+    let mut transformer = Transformer::new(TransformerConfig {
+        use_mxp: UseMxp::Always,
+        ..Default::default()
+    });
+    transformer.register_mxp_tag(MAPPER).unwrap();
+    transformer
+}
+
+#[test]
+fn custom_tag() {
+    let mut transformer = transformer();
+    let mut buf = [0; 1024];
+    transformer.receive(b"\x1B[1z<MAPPER 1024 exits='n s'>", &mut buf);
+    let output: Vec<OutputFragment> = transformer
+        .flush_output()
+        .map(|output| output.fragment)
+        .collect();
+    let expected: Vec<OutputFragment> = vec![
+        mxp::Custom {
+            name: "MAPPER",
+            values: vec![
+                ("room", Cow::Borrowed("1024")),
+                ("exits", Cow::Borrowed("n s")),
+            ],
+        }
+        .into_owned()
+        .into(),
+    ];
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn custom_tag_support() {
+    let mut transformer = transformer();
+    let mut buf = [0; 1024];
+    transformer.receive(b"\x1B[1z<SUPPORT mapper.* minimap>", &mut buf);
+    let mut input = String::new();
+    if let Some(mut drain) = transformer.drain_input() {
+        std::io::Read::read_to_string(&mut drain, &mut input).unwrap();
+    }
+    assert_eq!(
+        input,
+        "\x1B[1z<SUPPORTS +mapper.room +mapper.exits -minimap>\r\n"
+    );
+}
@@ -0,0 +1,141 @@
+mod common;
+use common::{text, transform_with};
+use mud_transformer::opt::charset;
+use mud_transformer::{Encoding, TransformerConfig};
+
+fn decode(encoding: Encoding, input: &[u8]) -> String {
+    let config = TransformerConfig {
+        encoding,
+        ..Default::default()
+    };
+    text(&transform_with(config, input).output())
+}
+
+#[test]
+fn utf8_sequences() {
+    assert_eq!(decode(Encoding::Utf8, "aé b ü 中".as_bytes()), "aé b ü 中");
+}
+
+#[test]
+fn utf8_malformed() {
+    assert_eq!(
+        decode(Encoding::Utf8, b"a\xC3b\xBFc"),
+        "a\u{FFFD}b\u{FFFD}c"
+    );
+}
+
+#[test]
+fn latin1() {
+    assert_eq!(decode(Encoding::Latin1, b"caf\xE9"), "café");
+}
+
+#[test]
+fn cp437() {
+    assert_eq!(
+        decode(Encoding::Cp437, b"\xC9\xCD\xBB\r\n\xBA \xBA"),
+        "╔═╗\n║ ║"
+    );
+}
+
+#[test]
+fn cp1252() {
+    assert_eq!(
+        decode(Encoding::Cp1252, b"\x80 \x93quoted\x94"),
+        "€ “quoted”"
+    );
+}
+
+#[test]
+fn koi8_r() {
+    assert_eq!(
+        decode(Encoding::Koi8R, b"\xD0\xD2\xC9\xD7\xC5\xD4"),
+        "привет"
+    );
+}
+
+#[test]
+fn big5() {
+    assert_eq!(decode(Encoding::Big5, b"\xA4\xA4\xA4\xE5!"), "中文!");
+}
+
+#[test]
+fn gbk() {
+    assert_eq!(decode(Encoding::Gbk, b"\xD6\xD0\xCE\xC4!"), "中文!");
+}
+
+#[test]
+fn gbk_interrupted() {
+    assert_eq!(decode(Encoding::Gbk, b"\xD6\r\n\xCE\xC4"), "\u{FFFD}\n文");
+}
+
+#[test]
+fn charset_request() {
+    let mut transformer = transform_with(
+        TransformerConfig::default(),
+        b"\xFF\xFA\x2A\x01;KOI8-R;US-ASCII\xFF\xF0\xD0\xD2\xC9\xD7\xC5\xD4",
+    );
+    assert_eq!(transformer.input_bytes(), b"\xFF\xFA\x2A\x02KOI8-R\xFF\xF0");
+    assert_eq!(text(&transformer.output()), "привет");
+}
+
+#[test]
+fn charset_request_prefers_configured() {
+    let config = TransformerConfig {
+        encoding: Encoding::Cp437,
+        ..Default::default()
+    };
+    let mut transformer = transform_with(config, b"\xFF\xFA\x2A\x01;BIG5;ibm437;Latin1\xFF\xF0");
+    assert_eq!(transformer.input_bytes(), b"\xFF\xFA\x2A\x02ibm437\xFF\xF0");
+}
+
+#[test]
+fn charset_request_rejected() {
+    let mut transformer = transform_with(
+        TransformerConfig::default(),
+        b"\xFF\xFA\x2A\x01;EBCDIC\xFF\xF0",
+    );
+    assert_eq!(transformer.input_bytes(), b"\xFF\xFA\x2A\x03\xFF\xF0");
+}
+
+#[test]
+fn charset_response_encode() {
+    let mut encoded = Vec::new();
+    charset::Response::Accepted("KOI8-R")
+        .encode(&mut encoded)
+        .unwrap();
+    assert_eq!(encoded, b"\xFF\xFA\x2A\x02KOI8-R\xFF\xF0");
+}
+
+#[test]
+fn mnes_charset() {
+    let mut transformer = transform_with(
+        TransformerConfig::default(),
+        b"\xFF\xFA\x27\x01\x00CHARSET\xFF\xF0\xFF\xFA\x2A\x01;GBK\xFF\xF0",
+    );
+    let expected: &[u8] = b"\xFF\xFA\x27\x00\x00CHARSET\x01UTF-8\xFF\xF0\
+        \xFF\xFA\x2A\x02GBK\xFF\xF0\
+        \xFF\xFA\x27\x02\x00CHARSET\x01GBK\xFF\xF0";
+    assert_eq!(transformer.input_bytes(), expected);
+}
+
+#[test]
+fn mnes_charset_disable_utf8() {
+    let mut transformer = transform_with(
+        TransformerConfig::default(),
+        b"\xFF\xFA\x27\x01\x00CHARSET\xFF\xF0",
+    );
+    transformer.input_bytes();
+    transformer.set_config(TransformerConfig {
+        disable_utf8: true,
+        ..Default::default()
+    });
+    assert_eq!(
+        transformer.input_bytes(),
+        b"\xFF\xFA\x27\x02\x00CHARSET\x01ASCII\xFF\xF0"
+    );
+    transformer.set_config(TransformerConfig::default());
+    assert_eq!(
+        transformer.input_bytes(),
+        b"\xFF\xFA\x27\x02\x00CHARSET\x01UTF-8\xFF\xF0"
+    );
+}
@@ -0,0 +1,80 @@
+mod common;
+use common::transform_with;
+use mud_transformer::TransformerConfig;
+use mud_transformer::escape::telnet;
+use mud_transformer::opt::gmcp;
+
+fn config(packages: &[&str]) -> TransformerConfig {
+    TransformerConfig {
+        app_name: "Client \"X\"".to_owned(),
+        version: "1.0".to_owned(),
+        gmcp_packages: packages.iter().map(|&package| package.to_owned()).collect(),
+        ..Default::default()
+    }
+}
+
+fn subnegotiation(message: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    gmcp::encode_command(&mut bytes, message).unwrap();
+    bytes
+}
+
+const WILL: [u8; 3] = [telnet::IAC, telnet::WILL, gmcp::OPT];
+
+#[test]
+fn handshake() {
+    let mut transformer = transform_with(config(&["Char 1", "Room 1"]), WILL);
+    assert!(transformer.gmcp());
+    let expected = [
+        &[telnet::IAC, telnet::DO, gmcp::OPT][..],
+        &subnegotiation(r#"Core.Hello {"client":"Client \"X\"","version":"1.0"}"#),
+        &subnegotiation(r#"Core.Supports.Set ["Char 1","Room 1"]"#),
+    ]
+    .concat();
+    assert_eq!(transformer.input_bytes(), expected);
+}
+
+#[test]
+fn unsupported_without_packages() {
+    let mut transformer = transform_with(config(&[]), WILL);
+    assert!(!transformer.gmcp());
+    assert_eq!(
+        transformer.input_bytes(),
+        [telnet::IAC, telnet::DONT, gmcp::OPT]
+    );
+}
+
+#[test]
+fn handshake_once() {
+    let mut transformer = transform_with(config(&["Char 1"]), WILL);
+    transformer.input_bytes();
+    transformer.receive(&WILL, &mut [0; 1024]);
+    assert_eq!(transformer.input_bytes(), b"");
+}
+
+#[test]
+fn change_packages() {
+    let mut transformer = transform_with(config(&["Char 1", "Room 1"]), WILL);
+    transformer.input_bytes();
+    transformer.set_config(config(&["Char 1", "Comm.Channel 1"]));
+    let expected = [
+        subnegotiation(r#"Core.Supports.Remove ["Room 1"]"#),
+        subnegotiation(r#"Core.Supports.Add ["Comm.Channel 1"]"#),
+    ]
+    .concat();
+    assert_eq!(transformer.input_bytes(), expected);
+}
+
+#[test]
+fn change_packages_before_negotiation() {
+    let mut transformer = transform_with(config(&["Char 1"]), "");
+    transformer.set_config(config(&["Room 1"]));
+    assert_eq!(transformer.input_bytes(), b"");
+}
+
+#[test]
+fn wont() {
+    let mut transformer = transform_with(config(&["Char 1"]), WILL);
+    transformer.receive(&[telnet::IAC, telnet::WONT, gmcp::OPT], &mut [0; 1024]);
+    assert!(!transformer.gmcp());
+}
@@ -0,0 +1,150 @@
+#![cfg(feature = "json")]
+
+use mud_transformer::opt::gmcp::package::{
+    ChannelText, Item, ItemChange, MediaPlay, MediaStop, MediaType, RoomInfo, Vitals,
+};
+use mud_transformer::opt::gmcp::{Message, Package};
+use serde_json::json;
+
+fn parse(message: &str) -> Package {
+    Package::from_message(&Message::decode(message.as_bytes()).unwrap()).unwrap()
+}
+
+#[test]
+fn vitals_as_strings() {
+    let package = parse(r#"Char.Vitals {"hp":"4500","maxhp":"5000","nl":"10","bleed":"0"}"#);
+    let expected = Vitals {
+        hp: Some(4500),
+        max_hp: Some(5000),
+        next_level: Some(10),
+        other: [("bleed".to_owned(), json!("0"))].into_iter().collect(),
+        ..Default::default()
+    };
+    assert_eq!(package, Package::CharVitals(expected));
+}
+
+#[test]
+fn items() {
+    let package = parse(
+        r#"Char.Items.Add {"location":"room","item":{"id":59330,"name":"a rat","attrib":"m"}}"#,
+    );
+    let expected = ItemChange {
+        location: "room".to_owned(),
+        item: Item {
+            id: "59330".to_owned(),
+            name: "a rat".to_owned(),
+            attrib: Some("m".to_owned()),
+            ..Default::default()
+        },
+    };
+    assert!(expected.item.has_attribute('m'));
+    assert_eq!(package, Package::CharItemsAdd(expected));
+    let Package::CharItemsList(list) =
+        parse(r#"char.items.list {"location":"inv","items":[{"id":"1","name":"a sword"}]}"#)
+    else {
+        panic!("expected Char.Items.List");
+    };
+    assert_eq!(list.items[0].name, "a sword");
+}
+
+#[test]
+fn room_info() {
+    let package = parse(
+        r#"Room.Info {"num":12345,"name":"A hallway","area":"Castle","details":["shop"],"exits":{"n":12344,"se":"12336"}}"#,
+    );
+    let expected = RoomInfo {
+        num: Some(12345),
+        name: Some("A hallway".to_owned()),
+        area: Some("Castle".to_owned()),
+        details: vec!["shop".to_owned()],
+        exits: [("n".to_owned(), 12344), ("se".to_owned(), 12336)]
+            .into_iter()
+            .collect(),
+        ..Default::default()
+    };
+    assert_eq!(package, Package::RoomInfo(expected));
+}
+
+#[test]
+fn channel_text() {
+    let package = parse(
+        r#"Comm.Channel.Text {"channel":"say","talker":"Tecton","text":"Tecton says, \"Hi.\""}"#,
+    );
+    let expected = ChannelText {
+        channel: "say".to_owned(),
+        talker: "Tecton".to_owned(),
+        text: "Tecton says, \"Hi.\"".to_owned(),
+    };
+    assert_eq!(package, Package::CommChannelText(expected));
+}
+
+#[test]
+fn media() {
+    let package = parse(
+        r#"Client.Media.Play {"name":"rain.mp3","type":"music","volume":50,"loops":-1,"continue":true}"#,
+    );
+    let expected = MediaPlay {
+        name: "rain.mp3".to_owned(),
+        kind: Some(MediaType::Music),
+        volume: Some(50),
+        loops: Some(-1),
+        continue_playing: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(package, Package::ClientMediaPlay(expected));
+    assert_eq!(
+        parse("Client.Media.Stop"),
+        Package::ClientMediaStop(MediaStop::default())
+    );
+}
+
+#[test]
+fn discord() {
+    let Package::ExternalDiscordStatus(status) = parse(
+        r#"External.Discord.Status {"smallimage":["server-icon"],"largeimage":"logo","partysize":2}"#,
+    ) else {
+        panic!("expected External.Discord.Status");
+    };
+    assert_eq!(status.small_image, ["server-icon"]);
+    assert_eq!(status.large_image, ["logo"]);
+    assert_eq!(status.party_size, Some(2));
+}
+
+#[test]
+fn goodbye() {
+    assert_eq!(
+        parse(r#"Core.Goodbye "Farewell.""#),
+        Package::CoreGoodbye("Farewell.".to_owned())
+    );
+    assert_eq!(parse("Core.Goodbye"), Package::CoreGoodbye(String::new()));
+}
+
+#[test]
+fn unknown_package() {
+    assert_eq!(
+        parse(r#"IRE.Rift.List [{"name":"gold","amount":5}]"#),
+        Package::Other {
+            command: "IRE.Rift.List".to_owned(),
+            data: json!([{"name":"gold","amount":5}]),
+        }
+    );
+}
+
+#[test]
+fn invalid_data() {
+    let message = Message::decode(br#"Room.Info {"exits":{"n":"north"}}"#).unwrap();
+    assert!(Package::from_message(&message).is_err());
+}
+
+#[test]
+fn to_message() {
+    let package = parse(r#"Char.Vitals {"hp":"10","maxhp":"20"}"#);
+    let message = package.to_message().unwrap();
+    assert_eq!(message.command, "Char.Vitals");
+    assert_eq!(message.data.as_deref(), Some(r#"{"hp":10,"maxhp":20}"#));
+    let ping = Package::Other {
+        command: "Core.Ping".to_owned(),
+        data: json!(null),
+    };
+    assert_eq!(ping.to_message().unwrap().data, None);
+}
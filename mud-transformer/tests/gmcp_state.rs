@@ -0,0 +1,85 @@
+#![cfg(feature = "json")]
+
+mod common;
+use common::transform;
+use mud_transformer::escape::telnet;
+use mud_transformer::opt::gmcp::{self, Message, State};
+use serde_json::json;
+
+fn update(state: &mut State, message: &str) -> Vec<String> {
+    state
+        .update(&Message::decode(message.as_bytes()).unwrap())
+        .unwrap()
+}
+
+#[test]
+fn merges_objects() {
+    let mut state = State::new();
+    let changed = update(&mut state, r#"Char.Vitals {"hp":100,"maxhp":100}"#);
+    assert_eq!(changed, ["Char.Vitals.hp", "Char.Vitals.maxhp"]);
+    let changed = update(&mut state, r#"Char.Vitals {"hp":80,"maxhp":100}"#);
+    assert_eq!(changed, ["Char.Vitals.hp"]);
+    assert_eq!(
+        state.get("Char.Vitals"),
+        Some(&json!({"hp":80,"maxhp":100}))
+    );
+}
+
+#[test]
+fn separate_packages() {
+    let mut state = State::new();
+    update(&mut state, r#"Char.Vitals {"hp":100}"#);
+    update(&mut state, r#"Char.Status {"level":5}"#);
+    update(&mut state, r#"Room.Info {"num":1,"exits":{"n":2}}"#);
+    assert_eq!(
+        serde_json::to_value(state.packages()).unwrap(),
+        json!({
+            "Char": {"Vitals": {"hp": 100}, "Status": {"level": 5}},
+            "Room": {"Info": {"num": 1, "exits": {"n": 2}}},
+        })
+    );
+    assert_eq!(state.get("Room.Info.exits.n"), Some(&json!(2)));
+    assert_eq!(state.get("Room.Info.exits.s"), None);
+}
+
+#[test]
+fn replaces_arrays_and_scalars() {
+    let mut state = State::new();
+    update(&mut state, r#"Char.Items.List {"items":[1,2,3]}"#);
+    let changed = update(&mut state, r#"Char.Items.List {"items":[4]}"#);
+    assert_eq!(changed, ["Char.Items.List.items"]);
+    assert_eq!(state.get("Char.Items.List.items"), Some(&json!([4])));
+    let changed = update(&mut state, r#"Char.Items.List "none""#);
+    assert_eq!(changed, ["Char.Items.List"]);
+    assert_eq!(state.get("Char.Items.List"), Some(&json!("none")));
+}
+
+#[test]
+fn ignores_messages_without_data() {
+    let mut state = State::new();
+    assert!(update(&mut state, "Core.Ping").is_empty());
+    assert!(state.is_empty());
+}
+
+#[test]
+fn invalid_json() {
+    let mut state = State::new();
+    let message = Message::decode(b"Char.Vitals {hp").unwrap();
+    assert!(state.update(&message).is_err());
+}
+
+#[test]
+fn apply_output() {
+    let mut input = Vec::new();
+    gmcp::encode_command(&mut input, r#"Char.Vitals {"hp":100}"#).unwrap();
+    input.extend_from_slice(&[telnet::IAC, telnet::SB, gmcp::OPT]);
+    input.extend_from_slice(br#"Char.Vitals {"hp":50}"#);
+    input.extend_from_slice(&[telnet::IAC, telnet::SE]);
+    let mut state = State::new();
+    let changed: Vec<_> = transform(input)
+        .flush_output()
+        .flat_map(|output| state.apply(&output))
+        .collect();
+    assert_eq!(changed, ["Char.Vitals.hp", "Char.Vitals.hp"]);
+    assert_eq!(state.get("Char.Vitals.hp"), Some(&json!(50)));
+}
@@ -0,0 +1,55 @@
+mod common;
+use common::{transform, transform_with};
+use mud_transformer::output::TextFragment;
+use mud_transformer::{TransformerConfig, UseMxp};
+
+fn lenient() -> TransformerConfig {
+    TransformerConfig {
+        use_mxp: UseMxp::Always,
+        parse_options: mxp::ParseOptions::LENIENT,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn strict_entities() {
+    let output = transform("fish &foo; chips").output();
+    let expected = &[TextFragment::from("fish  chips").into()];
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn lenient_entities() {
+    let output = transform_with(lenient(), "fish & chips &foo; &lt;3").output();
+    let expected = &[TextFragment::from("fish & chips &foo; <3").into()];
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn lenient_stray_close_tag() {
+    let output = transform_with(lenient(), "a</p>b</send x>c").output();
+    let expected = &[TextFragment::from("abc").into()];
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn lenient_link() {
+    let output = transform_with(
+        lenient(),
+        "\x1B[4z<send href=\"buy fish & chips\">menu\x1B[4z</send x>",
+    )
+    .output();
+    let expected = &[TextFragment {
+        text: "menu".into(),
+        link: Some("buy fish & chips".into()),
+        ..Default::default()
+    }
+    .into()];
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn lenient_unterminated_entity() {
+    let output = transform_with(lenient(), "fish & chips\r\n").output();
+    assert_eq!(output[0], TextFragment::from("fish & chips").into());
+}
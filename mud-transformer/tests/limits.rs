@@ -0,0 +1,39 @@
+mod common;
+use common::transform_with;
+use mud_transformer::output::{TextFragment, TextStyle};
+use mud_transformer::{TransformerConfig, UseMxp};
+
+#[test]
+fn max_depth() {
+    let config = TransformerConfig {
+        use_mxp: UseMxp::Always,
+        mxp_limits: mxp::Limits {
+            max_depth: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let output = transform_with(config, "<b><i>text</i></b>").output();
+    let expected = &[TextFragment {
+        text: "text".into(),
+        flags: TextStyle::Bold.into(),
+        ..Default::default()
+    }
+    .into()];
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn max_entity_len() {
+    let config = TransformerConfig {
+        use_mxp: UseMxp::Always,
+        mxp_limits: mxp::Limits {
+            max_entity_len: 4,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let transformer = transform_with(config, "\x1B[1z<!EN a 1234><!EN b 12345>");
+    assert_eq!(transformer.get_mxp_entity("a"), Some("1234"));
+    assert_eq!(transformer.get_mxp_entity("b"), None);
+}
@@ -0,0 +1,113 @@
+mod common;
+use common::{transform, transform_with};
+use mud_transformer::output::{Link, MxpFragment, OutputFragment, SendTo, TextFragment};
+use mud_transformer::{LinkPolicy, TransformerConfig, UseMxp};
+
+#[test]
+fn allowed_hyperlink() {
+    let output = transform("\x1B[4z<a href='https://example.com'>site</a>").output();
+    let expected = &[TextFragment {
+        text: "site".into(),
+        link: Some(Link {
+            href: "https://example.com".into(),
+            hint: "https://example.com".into(),
+            send_to: SendTo::Internet,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+    .into()];
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn rejected_hyperlink() {
+    let output = transform("\x1B[4z<a href='javascript:alert(1)'>site</a>").output();
+    let expected = &[TextFragment::from("site").into()];
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn relative_hyperlink() {
+    let config = TransformerConfig {
+        use_mxp: UseMxp::Always,
+        link_policy: LinkPolicy {
+            allow_relative: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let output = transform_with(config, "\x1B[4z<a href='www.example.com'>site</a>").output();
+    let link = match output.as_slice() {
+        [OutputFragment::Text(fragment)] => fragment.link.as_ref().map(|link| link.href.as_str()),
+        _ => panic!("unexpected output: {output:?}"),
+    };
+    assert_eq!(link, Some("www.example.com"));
+}
+
+#[test]
+fn rejected_image_url() {
+    let output = transform("\x1B[4z<image map.png url='file:///etc/'>").output();
+    let [OutputFragment::Image(image)] = output.as_slice() else {
+        panic!("unexpected output: {output:?}");
+    };
+    assert_eq!(image.fname, "map.png");
+    assert_eq!(image.url, None);
+}
+
+#[test]
+fn rejected_image_fname() {
+    let output = transform("\x1B[4z<image ../../secrets.png>").output();
+    assert_eq!(output, &[]);
+}
+
+#[test]
+fn rejected_sound_url() {
+    let output = transform("\x1B[4z<sound ding.wav U='ftp://example.com/sounds/'>").output();
+    let [OutputFragment::Mxp(MxpFragment::Sound(sound))] = output.as_slice() else {
+        panic!("unexpected output: {output:?}");
+    };
+    assert_eq!(sound.fname, "ding.wav");
+    assert_eq!(sound.url, None);
+}
+
+#[test]
+fn rejected_sound_fname() {
+    let output = transform("\x1B[4z<sound 'C:\\Windows\\Media\\ding.wav'>").output();
+    assert_eq!(output, &[]);
+}
+
+#[test]
+fn rejected_mslp_link() {
+    let config = TransformerConfig {
+        link_policy: LinkPolicy {
+            max_len: 8,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let output = transform_with(
+        config,
+        "\x1B]68;1;SEND;say Hello World!\x07\x1B[4m(click me)\x1B[24m",
+    )
+    .output();
+    let [OutputFragment::Text(fragment)] = output.as_slice() else {
+        panic!("unexpected output: {output:?}");
+    };
+    assert_eq!(fragment.link, None);
+}
+
+#[test]
+fn long_send_command() {
+    let config = TransformerConfig {
+        use_mxp: UseMxp::Always,
+        link_policy: LinkPolicy {
+            max_len: 8,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let output = transform_with(config, "<send 'say hello there'>hi</send>").output();
+    let expected = &[TextFragment::from("hi").into()];
+    assert_eq!(output, expected);
+}
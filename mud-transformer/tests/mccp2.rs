@@ -0,0 +1,72 @@
+mod common;
+use common::{text, transform};
+use mud_transformer::escape::telnet;
+use mud_transformer::opt::mccp2::{self, Compressor};
+
+fn server_output() -> Vec<u8> {
+    let mut compressor = Compressor::new();
+    let mut output = vec![telnet::IAC, telnet::WILL, mccp2::OPT];
+    output.extend_from_slice(b"plain\r\n");
+    compressor.start(&mut output);
+    compressor.compress(b"Hello, ", &mut output).unwrap();
+    compressor.write(b"world", &mut output).unwrap();
+    compressor.compress(b"!\r\n", &mut output).unwrap();
+    compressor.finish(&mut output).unwrap();
+    output.extend_from_slice(b"done\r\n");
+    output
+}
+
+#[test]
+fn start_marker() {
+    let mut output = Vec::new();
+    Compressor::new().start(&mut output);
+    assert_eq!(
+        output,
+        [telnet::IAC, telnet::SB, mccp2::OPT, telnet::IAC, telnet::SE]
+    );
+}
+
+#[test]
+fn round_trip() {
+    let mut transformer = transform(server_output());
+    assert_eq!(text(&transformer.output()), "plain\nHello, world!\ndone\n");
+    assert!(!transformer.decompressing());
+}
+
+#[test]
+fn round_trip_in_chunks() {
+    let mut transformer = transform("");
+    let mut buf = [0; 1024];
+    let mut output = Vec::new();
+    for chunk in server_output().chunks(3) {
+        transformer.receive(chunk, &mut buf);
+        output.extend(transformer.flush_output().map(|output| output.fragment));
+    }
+    assert_eq!(text(&output), "plain\nHello, world!\ndone\n");
+}
+
+#[test]
+fn flush_points() {
+    let mut compressor = Compressor::new();
+    let mut output = Vec::new();
+    compressor.start(&mut output);
+    compressor.compress(b"prompt> ", &mut output).unwrap();
+    let mut transformer = transform(output);
+    assert!(transformer.decompressing());
+    assert_eq!(text(&transformer.output()), "prompt> ");
+}
+
+#[test]
+fn compression_levels() {
+    let input = "The quick brown fox jumps over the lazy dog.\r\n".repeat(50);
+    let mut none = Vec::new();
+    Compressor::with_level(0)
+        .compress(input.as_bytes(), &mut none)
+        .unwrap();
+    let mut best = Vec::new();
+    Compressor::with_level(9)
+        .compress(input.as_bytes(), &mut best)
+        .unwrap();
+    assert!(none.len() > input.len());
+    assert!(best.len() < input.len() / 10);
+}
@@ -0,0 +1,94 @@
+mod common;
+use common::{transform, transform_with};
+use mud_transformer::TransformerConfig;
+use mud_transformer::escape::telnet;
+use mud_transformer::opt::mccp2::{Inflate, InflateFlush, Status};
+use mud_transformer::opt::mccp3;
+
+const START: [u8; 5] = [telnet::IAC, telnet::SB, mccp3::OPT, telnet::IAC, telnet::SE];
+
+fn negotiate(verb: u8) -> [u8; 3] {
+    [telnet::IAC, verb, mccp3::OPT]
+}
+
+fn negotiated(verb: u8) -> Vec<u8> {
+    [&negotiate(verb)[..], &START].concat()
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn inflate(inflate: &mut Inflate, input: &[u8]) -> (Vec<u8>, Status) {
+    let mut buf = [0; 1024];
+    let status = inflate
+        .decompress(input, &mut buf, InflateFlush::SyncFlush)
+        .unwrap();
+    assert_eq!(inflate.total_in() as usize, input.len());
+    (buf[..inflate.total_out() as usize].to_vec(), status)
+}
+
+#[test]
+fn compress_after_do() {
+    let mut transformer = transform(negotiate(telnet::DO));
+    assert!(transformer.compressing());
+    assert_eq!(transformer.input_bytes(), negotiated(telnet::WILL));
+    transformer.send_command("look");
+    let compressed = transformer.input_bytes();
+    assert_ne!(compressed, b"look\r\n");
+    let (inflated, status) = inflate(&mut Inflate::new(true, 15), &compressed);
+    assert_eq!(inflated, b"look\r\n");
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn compress_after_will() {
+    let mut transformer = transform(negotiate(telnet::WILL));
+    assert!(transformer.compressing());
+    assert_eq!(transformer.input_bytes(), negotiated(telnet::DO));
+}
+
+#[test]
+fn end_after_dont() {
+    let mut transformer = transform(negotiate(telnet::DO));
+    transformer.input_bytes();
+    transformer.send_command("quit");
+    transformer.receive(&negotiate(telnet::DONT), &mut [0; 1024]);
+    assert!(!transformer.compressing());
+    let input = transformer.input_bytes();
+    let (compressed, reply) = input.split_at(input.len() - 3);
+    assert_eq!(reply, negotiate(telnet::WONT));
+    let (inflated, status) = inflate(&mut Inflate::new(true, 15), compressed);
+    assert_eq!(inflated, b"quit\r\n");
+    assert_eq!(status, Status::StreamEnd);
+}
+
+#[test]
+fn end_compression() {
+    let mut transformer = transform(negotiate(telnet::DO));
+    transformer.input_bytes();
+    transformer.end_compression();
+    assert!(!transformer.compressing());
+    let (inflated, status) = inflate(&mut Inflate::new(true, 15), &transformer.input_bytes());
+    assert_eq!(inflated, b"");
+    assert_eq!(status, Status::StreamEnd);
+    transformer.send_command("look");
+    assert_eq!(transformer.input_bytes(), b"look\r\n");
+}
+
+#[test]
+fn disable_compression() {
+    let config = TransformerConfig {
+        disable_compression: true,
+        ..Default::default()
+    };
+    let mut transformer = transform_with(config, negotiate(telnet::DO));
+    assert!(!transformer.compressing());
+    assert_eq!(transformer.input_bytes(), negotiate(telnet::WONT));
+}
+
+#[test]
+fn continue_after_dont_if_will() {
+    let mut transformer = transform([negotiate(telnet::WILL), negotiate(telnet::DO)].concat());
+    transformer.receive(&negotiate(telnet::DONT), &mut [0; 1024]);
+    assert!(transformer.compressing());
+    transformer.receive(&negotiate(telnet::WONT), &mut [0; 1024]);
+    assert!(!transformer.compressing());
+}
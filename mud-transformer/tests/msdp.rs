@@ -4,8 +4,9 @@ use std::collections::HashMap;
 use bytes::Bytes;
 use common::transform;
 use mud_transformer::escape::telnet;
-use mud_transformer::opt::msdp;
+use mud_transformer::opt::msdp::{self, Change};
 use mud_transformer::output::{OutputFragment, TelnetFragment};
+use mud_transformer::{Transformer, TransformerConfig};
 
 fn subnegotiate(bytes: &[u8]) -> Vec<u8> {
     let mut subnegotiation = Vec::with_capacity(bytes.len() + 5);
@@ -85,3 +86,191 @@ fn msdp_table() {
 
     assert_eq!(iter.as_slice(), &[]);
 }
+
+fn msdp_config() -> TransformerConfig {
+    let mut config = TransformerConfig::new();
+    config.will.insert(msdp::OPT);
+    config
+}
+
+fn apply(session: &mut msdp::Session, transformer: &mut Transformer, input: &[u8]) -> Vec<Change> {
+    transformer.receive(input, &mut [0; 1024]);
+    transformer
+        .flush_output()
+        .flat_map(|output| session.apply(&output))
+        .collect()
+}
+
+fn commands(session: &mut msdp::Session) -> Vec<u8> {
+    session.drain_commands().collect()
+}
+
+fn report(variables: &[&str]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    msdp::Command::report(variables).write(&mut bytes).unwrap();
+    bytes
+}
+
+fn change(variable: &str, path: &[&str]) -> Change {
+    Change {
+        variable: Bytes::copy_from_slice(variable.as_bytes()),
+        path: path
+            .iter()
+            .map(|key| Bytes::copy_from_slice(key.as_bytes()))
+            .collect(),
+    }
+}
+
+const WILL_MSDP: [u8; 3] = [telnet::IAC, telnet::WILL, msdp::OPT];
+
+#[test]
+fn session_reports_after_negotiation() {
+    let mut transformer = Transformer::new(msdp_config());
+    let mut session = msdp::Session::new();
+    session.report(["HEALTH", "MANA"]);
+    assert_eq!(commands(&mut session), b"");
+    apply(&mut session, &mut transformer, &WILL_MSDP);
+    assert!(session.active());
+    assert_eq!(commands(&mut session), report(&["HEALTH", "MANA"]));
+    session.report(["MANA", "MOVEMENT"]);
+    assert_eq!(commands(&mut session), report(&["MOVEMENT"]));
+    session.unreport(["HEALTH", "EXPERIENCE"]);
+    let mut expected = Vec::new();
+    msdp::Command::unreport(["HEALTH"])
+        .write(&mut expected)
+        .unwrap();
+    assert_eq!(commands(&mut session), expected);
+    assert_eq!(session.reported(), ["MANA", "MOVEMENT"]);
+}
+
+#[test]
+fn session_inactive_if_refused() {
+    let mut transformer = Transformer::new(TransformerConfig::new());
+    let mut session = msdp::Session::new();
+    session.report(["HEALTH"]);
+    apply(&mut session, &mut transformer, &WILL_MSDP);
+    assert!(!session.active());
+    apply(
+        &mut session,
+        &mut transformer,
+        &[telnet::IAC, telnet::DO, msdp::OPT],
+    );
+    assert!(!session.active());
+    assert_eq!(commands(&mut session), b"");
+}
+
+#[test]
+fn session_resubscribes() {
+    let mut transformer = Transformer::new(msdp_config());
+    let mut session = msdp::Session::new();
+    session.report(["HEALTH"]);
+    apply(&mut session, &mut transformer, &WILL_MSDP);
+    commands(&mut session);
+    apply(&mut session, &mut transformer, &WILL_MSDP);
+    assert_eq!(commands(&mut session), b"");
+    apply(
+        &mut session,
+        &mut transformer,
+        &[telnet::IAC, telnet::DO, msdp::OPT],
+    );
+    apply(
+        &mut session,
+        &mut transformer,
+        &[telnet::IAC, telnet::DONT, msdp::OPT],
+    );
+    assert!(session.active());
+    session.report(["MANA"]);
+    assert_eq!(commands(&mut session), report(&["MANA"]));
+    apply(
+        &mut session,
+        &mut transformer,
+        &[telnet::IAC, telnet::WONT, msdp::OPT],
+    );
+    assert!(!session.active());
+    apply(
+        &mut session,
+        &mut transformer,
+        &[telnet::IAC, telnet::DO, msdp::OPT],
+    );
+    assert_eq!(commands(&mut session), report(&["HEALTH", "MANA"]));
+
+    session.reset();
+    let mut transformer = Transformer::new(msdp_config());
+    apply(&mut session, &mut transformer, &WILL_MSDP);
+    assert_eq!(commands(&mut session), report(&["HEALTH", "MANA"]));
+}
+
+#[test]
+fn session_resubscribes_after_wont() {
+    let mut transformer = Transformer::new(msdp_config());
+    let mut session = msdp::Session::new();
+    session.report(["HEALTH"]);
+    apply(&mut session, &mut transformer, &WILL_MSDP);
+    commands(&mut session);
+    apply(
+        &mut session,
+        &mut transformer,
+        &[telnet::IAC, telnet::WONT, msdp::OPT],
+    );
+    assert!(!session.active());
+    apply(&mut session, &mut transformer, &WILL_MSDP);
+    assert_eq!(commands(&mut session), report(&["HEALTH"]));
+}
+
+#[test]
+fn session_values() {
+    let mut transformer = Transformer::new(msdp_config());
+    let mut session = msdp::Session::new();
+    let changes = apply(
+        &mut session,
+        &mut transformer,
+        &subnegotiate(b"\x01HEALTH\x02100\x01MANA\x0250"),
+    );
+    assert_eq!(changes, [change("HEALTH", &[]), change("MANA", &[])]);
+    let changes = apply(
+        &mut session,
+        &mut transformer,
+        &subnegotiate(b"\x01HEALTH\x0290\x01MANA\x0250"),
+    );
+    assert_eq!(changes, [change("HEALTH", &[])]);
+    assert_eq!(session.get("HEALTH"), Some(&"90".into()));
+}
+
+#[test]
+fn session_table_changes() {
+    let mut transformer = Transformer::new(msdp_config());
+    let mut session = msdp::Session::new();
+    apply(
+        &mut session,
+        &mut transformer,
+        &subnegotiate(b"\x01ROOM\x02\x03\x01VNUM\x021\x01EXITS\x02\x03\x01n\x022\x04\x04"),
+    );
+    let changes = apply(
+        &mut session,
+        &mut transformer,
+        &subnegotiate(b"\x01ROOM\x02\x03\x01VNUM\x022\x01EXITS\x02\x03\x01s\x021\x04\x04"),
+    );
+    assert_eq!(
+        changes,
+        [
+            change("ROOM", &["EXITS", "n"]),
+            change("ROOM", &["EXITS", "s"]),
+            change("ROOM", &["VNUM"]),
+        ]
+    );
+}
+
+#[test]
+fn session_reportable_variables() {
+    let mut session = msdp::Session::new();
+    session.list_reportable();
+    let mut expected = Vec::new();
+    msdp::Command::list("REPORTABLE_VARIABLES")
+        .write(&mut expected)
+        .unwrap();
+    assert_eq!(commands(&mut session), expected);
+    session.receive(Bytes::from_static(
+        b"\x01REPORTABLE_VARIABLES\x02\x05\x02HEALTH\x02MANA\x06",
+    ));
+    assert_eq!(session.reportable(), ["HEALTH", "MANA"]);
+}
@@ -0,0 +1,96 @@
+mod common;
+use common::{TestTransformer, receive, text, transform_with};
+use mud_transformer::TransformerConfig;
+use mud_transformer::escape::telnet;
+use mud_transformer::opt::msp;
+use mud_transformer::output::{MxpFragment, OutputFragment};
+
+fn msp_transformer() -> TestTransformer {
+    let mut config = TransformerConfig::new();
+    config.will.insert(msp::OPT);
+    transform_with(config, [telnet::IAC, telnet::WILL, msp::OPT])
+}
+
+fn sounds(fragments: &[OutputFragment]) -> Vec<&MxpFragment> {
+    fragments
+        .iter()
+        .filter_map(|fragment| match fragment {
+            OutputFragment::Mxp(fragment) => Some(fragment),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn sound_trigger() {
+    let mut transformer = msp_transformer();
+    receive(
+        &mut transformer,
+        b"A storm.\r\n!!SOUND(weather/rain.wav V=80 L=2)Rain falls.\r\n",
+    );
+    let output = transformer.output();
+    assert_eq!(text(&output), "A storm.\nRain falls.\n");
+    let expected = mxp::Sound {
+        fname: "weather/rain.wav".to_owned(),
+        volume: 80,
+        repeat: 2.try_into().unwrap(),
+        ..Default::default()
+    };
+    assert_eq!(sounds(&output), [&MxpFragment::Sound(expected)]);
+}
+
+#[test]
+fn music_trigger_split() {
+    let mut transformer = msp_transformer();
+    receive(&mut transformer, b"!!MUS");
+    receive(&mut transformer, b"IC(town.mid L=-1)\r\n");
+    let output = transformer.output();
+    let [MxpFragment::Music(music)] = &sounds(&output)[..] else {
+        panic!("expected music: {output:?}");
+    };
+    assert_eq!(music.fname, "town.mid");
+    assert_eq!(music.repeat, mxp::AudioRepetition::Forever);
+}
+
+#[test]
+fn off_and_default_url() {
+    let mut transformer = msp_transformer();
+    receive(
+        &mut transformer,
+        b"!!SOUND(Off U=http://example.org/sounds/)\r\n!!SOUND(bell.wav)\r\n!!SOUND(Off)\r\n!!MUSIC(Off)\r\n",
+    );
+    let output = transformer.output();
+    let expected = mxp::Sound {
+        fname: "bell.wav".to_owned(),
+        url: Some("http://example.org/sounds/".to_owned()),
+        ..Default::default()
+    };
+    assert_eq!(
+        sounds(&output),
+        [
+            &MxpFragment::Sound(expected),
+            &MxpFragment::SoundOff,
+            &MxpFragment::MusicOff
+        ]
+    );
+}
+
+#[test]
+fn trigger_only_at_line_start() {
+    let mut transformer = msp_transformer();
+    receive(
+        &mut transformer,
+        b"Say !!SOUND(x.wav)\r\n!!Hey!\r\n!!SOUND(y.wav\r\n",
+    );
+    let output = transformer.output();
+    assert_eq!(text(&output), "Say !!SOUND(x.wav)\n!!Hey!\n!!SOUND(y.wav\n");
+    assert!(sounds(&output).is_empty());
+}
+
+#[test]
+fn text_without_msp() {
+    let mut transformer = transform_with(TransformerConfig::new(), "");
+    receive(&mut transformer, b"!!SOUND(x.wav)\r\n");
+    let output = transformer.output();
+    assert_eq!(text(&output), "!!SOUND(x.wav)\n");
+}
@@ -0,0 +1,108 @@
+mod common;
+use std::io;
+
+use common::transform;
+use mud_transformer::escape::telnet;
+use mud_transformer::opt::mssp::{self, MsspRecord};
+use mud_transformer::output::{OutputFragment, TelnetFragment};
+
+fn sample() -> MsspRecord {
+    let mut record = MsspRecord::new();
+    record.insert("NAME", &["Elsewhere"]);
+    record.insert("PLAYERS", &["12"]);
+    record.insert("UPTIME", &["1700000000"]);
+    record.insert("PORT", &["4000", "5000"]);
+    record.insert("ANSI", &["1"]);
+    record.insert("PAY TO PLAY", &["0"]);
+    record.insert("GENRE", &["Fantasy"]);
+    record.insert("MASCOT", &["Otter"]);
+    record
+}
+
+#[test]
+fn typed_fields() {
+    let record = sample();
+    assert_eq!(record.name.as_deref(), Some("Elsewhere"));
+    assert_eq!(record.players, Some(12));
+    assert_eq!(record.uptime, Some(1_700_000_000));
+    assert_eq!(record.port, [4000, 5000]);
+    assert_eq!(record.ansi, Some(true));
+    assert_eq!(record.pay_to_play, Some(false));
+    assert_eq!(record.genre, ["Fantasy"]);
+    assert_eq!(record.other["MASCOT"], ["Otter"]);
+}
+
+#[test]
+fn repeated_variables() {
+    let record =
+        MsspRecord::decode(b"\x01PORT\x024000\x01port\x025000\x02\x01CODEBASE\x02Merc\x02ROM");
+    assert_eq!(record.port, [4000, 5000]);
+    assert_eq!(record.codebase, ["Merc", "ROM"]);
+    assert!(record.other.is_empty());
+}
+
+#[test]
+fn invalid_values() {
+    let record = MsspRecord::decode(b"\x01PLAYERS\x02many\x01MXP\x02yes");
+    assert_eq!(record.players, None);
+    assert_eq!(record.mxp, None);
+    assert_eq!(record.other["PLAYERS"], ["many"]);
+    assert_eq!(record.other["MXP"], ["yes"]);
+}
+
+#[test]
+fn round_trip() {
+    let mut encoded = Vec::new();
+    sample().encode(&mut encoded).unwrap();
+    let output = transform(encoded).output();
+    let [
+        OutputFragment::Telnet(TelnetFragment::Subnegotiation {
+            code: mssp::OPT,
+            data,
+        }),
+    ] = output.as_slice()
+    else {
+        panic!("expected MSSP subnegotiation, got {output:?}");
+    };
+    assert_eq!(MsspRecord::decode(data), sample());
+}
+
+#[test]
+fn encode() {
+    let mut record = MsspRecord::new();
+    record.insert("NAME", &["Elsewhere"]);
+    record.insert("PORT", &["4000", "5000"]);
+    let mut encoded = Vec::new();
+    record.encode(&mut encoded).unwrap();
+    let expected = [
+        &[telnet::IAC, telnet::SB, mssp::OPT][..],
+        b"\x01NAME\x02Elsewhere\x01PORT\x024000\x025000",
+        &[telnet::IAC, telnet::SE],
+    ]
+    .concat();
+    assert_eq!(encoded, expected);
+}
+
+#[test]
+fn encode_rejects_delimiters() {
+    let mut record = MsspRecord::new();
+    record.insert("NAME", &["Else\x01where"]);
+    let mut encoded = Vec::new();
+    let error = record.encode(&mut encoded).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert!(encoded.is_empty());
+}
+
+#[test]
+fn plaintext() {
+    let mut encoded = Vec::new();
+    sample().encode_plaintext(&mut encoded).unwrap();
+    let text = String::from_utf8(encoded).unwrap();
+    assert!(text.contains("\r\nPORT\t4000\t5000\r\n"));
+    let text = format!("Welcome!\r\n{text}By what name are you known?");
+    assert_eq!(MsspRecord::parse_plaintext(&text), Some(sample()));
+    assert_eq!(
+        MsspRecord::parse_plaintext("MSSP-REPLY-START\r\nNAME\tX\r\n"),
+        None
+    );
+}
@@ -0,0 +1,107 @@
+mod common;
+use common::{TestTransformer, receive, transform_with};
+use mud_transformer::TransformerConfig;
+use mud_transformer::escape::telnet;
+use mud_transformer::opt::naws;
+
+fn naws_transformer() -> TestTransformer {
+    let mut config = TransformerConfig::new();
+    config.will.insert(naws::OPT);
+    transform_with(config, [])
+}
+
+#[test]
+fn send_size_on_do() {
+    let mut transformer = naws_transformer();
+    transformer.set_window_size(80, 24);
+    assert!(transformer.input_bytes().is_empty());
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, naws::OPT]);
+    let expected = [
+        &[telnet::IAC, telnet::WILL, naws::OPT][..],
+        &naws::subnegotiate(80, 24),
+    ]
+    .concat();
+    assert_eq!(transformer.input_bytes(), expected);
+}
+
+#[test]
+fn send_size_on_change() {
+    let mut transformer = naws_transformer();
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, naws::OPT]);
+    assert_eq!(
+        transformer.input_bytes(),
+        [telnet::IAC, telnet::WILL, naws::OPT]
+    );
+    transformer.set_window_size(100, 40);
+    assert_eq!(transformer.input_bytes(), naws::subnegotiate(100, 40));
+    transformer.set_window_size(100, 40);
+    assert!(transformer.input_bytes().is_empty());
+    transformer.set_window_size(120, 40);
+    assert_eq!(transformer.input_bytes(), naws::subnegotiate(120, 40));
+}
+
+#[test]
+fn no_size_after_dont() {
+    let mut transformer = naws_transformer();
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, naws::OPT]);
+    receive(&mut transformer, &[telnet::IAC, telnet::DONT, naws::OPT]);
+    transformer.input_bytes();
+    transformer.set_window_size(80, 24);
+    assert!(transformer.input_bytes().is_empty());
+    assert_eq!(
+        transformer.window_size(),
+        Some(naws::WindowSize {
+            width: 80,
+            height: 24
+        })
+    );
+}
+
+#[test]
+fn no_size_if_unsupported() {
+    let mut transformer = transform_with(TransformerConfig::new(), []);
+    transformer.set_window_size(80, 24);
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, naws::OPT]);
+    assert_eq!(
+        transformer.input_bytes(),
+        [telnet::IAC, telnet::WONT, naws::OPT]
+    );
+}
+
+#[test]
+fn escape_iac_in_size() {
+    let mut transformer = naws_transformer();
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, naws::OPT]);
+    transformer.input_bytes();
+    transformer.set_window_size(255, 24);
+    assert_eq!(
+        transformer.input_bytes(),
+        [
+            telnet::IAC,
+            telnet::SB,
+            naws::OPT,
+            0,
+            telnet::IAC,
+            telnet::IAC,
+            0,
+            24,
+            telnet::IAC,
+            telnet::SE
+        ]
+    );
+    assert_eq!(
+        naws::subnegotiate(255, 24),
+        [
+            telnet::IAC,
+            telnet::SB,
+            naws::OPT,
+            0,
+            telnet::IAC,
+            telnet::IAC,
+            0,
+            24,
+            telnet::IAC,
+            telnet::SE
+        ]
+    );
+}
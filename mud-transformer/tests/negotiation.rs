@@ -0,0 +1,198 @@
+mod common;
+use common::{TestTransformer, receive, transform_with};
+use mud_transformer::escape::telnet;
+use mud_transformer::opt::{self, gmcp};
+use mud_transformer::output::{OutputFragment, TelnetFragment};
+use mud_transformer::{OptionState, OptionStatus, TelnetSource, TransformerConfig};
+
+fn transformer() -> TestTransformer {
+    transform_with(TransformerConfig::new(), [])
+}
+
+#[test]
+fn reannounced_option_is_not_answered() {
+    let mut transformer = transformer();
+    receive(&mut transformer, &[telnet::IAC, telnet::WILL, opt::ECHO]);
+    assert_eq!(
+        transformer.input_bytes(),
+        [telnet::IAC, telnet::DO, opt::ECHO]
+    );
+    receive(&mut transformer, &[telnet::IAC, telnet::WILL, opt::ECHO]);
+    assert_eq!(transformer.input_bytes(), b"");
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, opt::MTTS]);
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, opt::MTTS]);
+    assert_eq!(
+        transformer.input_bytes(),
+        [telnet::IAC, telnet::WILL, opt::MTTS]
+    );
+}
+
+#[test]
+fn set_echo_once() {
+    let mut transformer = transformer();
+    receive(&mut transformer, &[telnet::IAC, telnet::WILL, opt::ECHO]);
+    receive(&mut transformer, &[telnet::IAC, telnet::WILL, opt::ECHO]);
+    receive(&mut transformer, &[telnet::IAC, telnet::WONT, opt::ECHO]);
+    receive(&mut transformer, &[telnet::IAC, telnet::WONT, opt::ECHO]);
+    let echoes: Vec<_> = transformer
+        .output()
+        .into_iter()
+        .filter_map(|fragment| match fragment {
+            OutputFragment::Telnet(TelnetFragment::SetEcho { should_echo }) => Some(should_echo),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(echoes, [false, true]);
+}
+
+#[test]
+fn disable_option() {
+    let mut transformer = transformer();
+    receive(&mut transformer, &[telnet::IAC, telnet::WILL, opt::ECHO]);
+    transformer.input_bytes();
+    receive(&mut transformer, &[telnet::IAC, telnet::WONT, opt::ECHO]);
+    assert_eq!(
+        transformer.input_bytes(),
+        [telnet::IAC, telnet::DONT, opt::ECHO]
+    );
+    assert_eq!(transformer.option_state(opt::ECHO), OptionState::default());
+}
+
+#[test]
+fn refuse_unsupported_option() {
+    let mut transformer = transformer();
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, opt::NAWS]);
+    assert_eq!(
+        transformer.input_bytes(),
+        [telnet::IAC, telnet::WONT, opt::NAWS]
+    );
+    receive(&mut transformer, &[telnet::IAC, telnet::DONT, opt::NAWS]);
+    assert_eq!(transformer.input_bytes(), b"");
+    assert!(!transformer.option_state(opt::NAWS).is_negotiated());
+}
+
+#[test]
+fn option_state() {
+    let mut transformer = transformer();
+    receive(&mut transformer, &[telnet::IAC, telnet::WILL, opt::ECHO]);
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, opt::MTTS]);
+    let echo = transformer.option_state(opt::ECHO);
+    assert_eq!(echo.him, OptionStatus::Yes);
+    assert_eq!(echo.us, OptionStatus::No);
+    assert!(echo.enabled(TelnetSource::Server));
+    assert!(!echo.enabled(TelnetSource::Client));
+    let codes: Vec<u8> = transformer
+        .negotiated_options()
+        .map(|(code, _)| code)
+        .collect();
+    assert_eq!(codes, [opt::ECHO, opt::MTTS]);
+}
+
+#[test]
+fn offer_at_connect() {
+    let mut config = TransformerConfig::new();
+    config.offer_will.insert(opt::NAWS);
+    config.offer_do.insert(opt::ECHO);
+    let mut transformer = transform_with(config, []);
+    assert_eq!(
+        transformer.input_bytes(),
+        [
+            telnet::IAC,
+            telnet::WILL,
+            opt::NAWS,
+            telnet::IAC,
+            telnet::DO,
+            opt::ECHO
+        ]
+    );
+    assert_eq!(
+        transformer.option_state(opt::NAWS).us,
+        OptionStatus::WantYes
+    );
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, opt::NAWS]);
+    receive(&mut transformer, &[telnet::IAC, telnet::WILL, opt::ECHO]);
+    assert_eq!(transformer.input_bytes(), b"");
+    assert!(
+        transformer
+            .option_state(opt::NAWS)
+            .enabled(TelnetSource::Client)
+    );
+    assert!(
+        transformer
+            .option_state(opt::ECHO)
+            .enabled(TelnetSource::Server)
+    );
+}
+
+#[test]
+fn gmcp_handshake_after_request() {
+    let mut config = TransformerConfig::new();
+    config.gmcp_packages.push("Char 1".to_owned());
+    let mut transformer = transform_with(config, []);
+    transformer.request_option(TelnetSource::Server, gmcp::OPT, true);
+    assert_eq!(
+        transformer.input_bytes(),
+        [telnet::IAC, telnet::DO, gmcp::OPT]
+    );
+    receive(&mut transformer, &[telnet::IAC, telnet::WILL, gmcp::OPT]);
+    assert!(transformer.gmcp());
+    assert!(
+        transformer
+            .input_bytes()
+            .starts_with(b"\xFF\xFA\xC9Core.Hello")
+    );
+}
+
+#[test]
+fn suppress_duplicate_requests() {
+    let mut transformer = transformer();
+    transformer.request_option(TelnetSource::Server, opt::ECHO, true);
+    transformer.request_option(TelnetSource::Server, opt::ECHO, true);
+    assert_eq!(
+        transformer.input_bytes(),
+        [telnet::IAC, telnet::DO, opt::ECHO]
+    );
+    receive(&mut transformer, &[telnet::IAC, telnet::WILL, opt::ECHO]);
+    transformer.request_option(TelnetSource::Server, opt::ECHO, true);
+    assert_eq!(transformer.input_bytes(), b"");
+}
+
+#[test]
+fn queue_opposite_request() {
+    let mut transformer = transformer();
+    transformer.request_option(TelnetSource::Server, opt::ECHO, true);
+    transformer.request_option(TelnetSource::Server, opt::ECHO, false);
+    assert!(transformer.option_state(opt::ECHO).him_queued);
+    transformer.input_bytes();
+    receive(&mut transformer, &[telnet::IAC, telnet::WILL, opt::ECHO]);
+    assert_eq!(
+        transformer.input_bytes(),
+        [telnet::IAC, telnet::DONT, opt::ECHO]
+    );
+    receive(&mut transformer, &[telnet::IAC, telnet::WONT, opt::ECHO]);
+    assert_eq!(transformer.input_bytes(), b"");
+    assert!(!transformer.option_state(opt::ECHO).is_negotiated());
+}
+
+#[test]
+fn request_disable() {
+    let mut transformer = transformer();
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, opt::MTTS]);
+    transformer.input_bytes();
+    transformer.request_option(TelnetSource::Client, opt::MTTS, false);
+    assert_eq!(
+        transformer.input_bytes(),
+        [telnet::IAC, telnet::WONT, opt::MTTS]
+    );
+    receive(&mut transformer, &[telnet::IAC, telnet::DONT, opt::MTTS]);
+    assert_eq!(transformer.input_bytes(), b"");
+    assert_eq!(transformer.option_state(opt::MTTS).us, OptionStatus::No);
+}
+
+#[test]
+fn unsupported_request() {
+    let mut transformer = transformer();
+    transformer.request_option(TelnetSource::Client, opt::NAWS, true);
+    assert_eq!(transformer.input_bytes(), b"");
+    assert!(!transformer.option_state(opt::NAWS).is_negotiated());
+}
@@ -0,0 +1,120 @@
+mod common;
+use common::{TestTransformer, receive, transform_with};
+use mud_transformer::escape::telnet;
+use mud_transformer::output::{OutputFragment, TelnetFragment};
+use mud_transformer::{OptionContext, TelnetOptionHandler, TelnetSource, TransformerConfig};
+
+const OPT: u8 = 100;
+
+#[derive(Default)]
+struct Echo {
+    server_only: bool,
+}
+
+impl TelnetOptionHandler for Echo {
+    fn supports(&self, source: TelnetSource) -> bool {
+        !self.server_only || source == TelnetSource::Server
+    }
+
+    fn enabled(&mut self, source: TelnetSource, cx: &mut OptionContext) {
+        if source == TelnetSource::Server {
+            cx.subnegotiate(b"hello");
+        }
+    }
+
+    fn disabled(&mut self, _source: TelnetSource, cx: &mut OptionContext) {
+        cx.append(OutputFragment::LocalEcho("bye".to_owned()));
+    }
+
+    fn subnegotiation(&mut self, data: &[u8], cx: &mut OptionContext) {
+        let mut reply = b"echo ".to_vec();
+        reply.extend_from_slice(data);
+        cx.subnegotiate(&reply);
+    }
+}
+
+fn with_handler(handler: Echo) -> TestTransformer {
+    let mut transformer = transform_with(TransformerConfig::new(), []);
+    transformer.register_option_handler(OPT, handler);
+    transformer
+}
+
+fn subnegotiation(data: &[u8]) -> Vec<u8> {
+    [
+        &[telnet::IAC, telnet::SB, OPT],
+        data,
+        &[telnet::IAC, telnet::SE],
+    ]
+    .concat()
+}
+
+#[test]
+fn handler_enabled() {
+    let mut transformer = with_handler(Echo::default());
+    receive(&mut transformer, &[telnet::IAC, telnet::WILL, OPT]);
+    let expected = [
+        &[telnet::IAC, telnet::DO, OPT][..],
+        &subnegotiation(b"hello"),
+    ]
+    .concat();
+    assert_eq!(transformer.input_bytes(), expected);
+}
+
+#[test]
+fn handler_supports() {
+    let mut transformer = with_handler(Echo { server_only: true });
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, OPT]);
+    assert_eq!(transformer.input_bytes(), [telnet::IAC, telnet::WONT, OPT]);
+}
+
+#[test]
+fn handler_subnegotiation() {
+    let mut transformer = with_handler(Echo::default());
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, OPT]);
+    transformer.input_bytes();
+    transformer.output();
+    receive(&mut transformer, &subnegotiation(b"a\xFF\xFFb"));
+    assert_eq!(
+        transformer.input_bytes(),
+        subnegotiation(b"echo a\xFF\xFFb")
+    );
+    assert_eq!(
+        transformer.output(),
+        [OutputFragment::Telnet(TelnetFragment::Subnegotiation {
+            code: OPT,
+            data: b"a\xFFb"[..].into(),
+        })]
+    );
+}
+
+#[test]
+fn no_subnegotiation_if_not_enabled() {
+    let mut transformer = with_handler(Echo::default());
+    receive(&mut transformer, &subnegotiation(b"hi"));
+    assert!(transformer.input_bytes().is_empty());
+    receive(&mut transformer, &[telnet::IAC, telnet::WILL, OPT]);
+    receive(&mut transformer, &[telnet::IAC, telnet::WONT, OPT]);
+    transformer.input_bytes();
+    receive(&mut transformer, &subnegotiation(b"hi"));
+    assert!(transformer.input_bytes().is_empty());
+}
+
+#[test]
+fn handler_disabled() {
+    let mut transformer = with_handler(Echo::default());
+    receive(&mut transformer, &[telnet::IAC, telnet::DO, OPT]);
+    receive(&mut transformer, &[telnet::IAC, telnet::DONT, OPT]);
+    assert!(
+        transformer
+            .output()
+            .contains(&OutputFragment::LocalEcho("bye".to_owned()))
+    );
+}
+
+#[test]
+fn unregister_handler() {
+    let mut transformer = with_handler(Echo::default());
+    assert!(transformer.unregister_option_handler(OPT).is_some());
+    receive(&mut transformer, &[telnet::IAC, telnet::WILL, OPT]);
+    assert_eq!(transformer.input_bytes(), [telnet::IAC, telnet::DONT, OPT]);
+}
@@ -0,0 +1,133 @@
+use mud_transformer::output::TextStyle;
+use mud_transformer::term::Screen;
+use mud_transformer::{Transformer, TransformerConfig};
+
+fn render_with(mut screen: Screen, input: &str) -> Screen {
+    let mut transformer = Transformer::new(TransformerConfig {
+        console_controls: true,
+        ..Default::default()
+    });
+    transformer.receive(input.as_bytes(), &mut [0; 1024]);
+    for output in transformer.flush_output() {
+        screen.apply(&output);
+    }
+    screen
+}
+
+fn render(rows: u16, columns: u16, input: &str) -> Screen {
+    render_with(Screen::new(rows, columns), input)
+}
+
+#[test]
+fn autowrap() {
+    let screen = render(3, 5, "abcdefg");
+    assert_eq!(screen.text(), "abcde\nfg");
+    assert_eq!(screen.cursor(), (1, 2));
+}
+
+#[test]
+fn autowrap_disabled() {
+    let screen = render(3, 5, "\x1B[?7labcdefg");
+    assert_eq!(screen.text(), "abcdg");
+}
+
+#[test]
+fn scrolls_at_bottom() {
+    let screen = render(3, 5, "1\r\n2\r\n3\r\n4");
+    assert_eq!(screen.text(), "2\n3\n4");
+}
+
+#[test]
+fn scroll_region() {
+    let screen = render(4, 5, "top\r\na\r\nb\r\nbot\x1B[2;3r\x1B[3;1H\nc");
+    assert_eq!(screen.text(), "top\nb\nc\nbot");
+}
+
+#[test]
+fn insert_and_delete_characters() {
+    let screen = render(1, 8, "abcdef\x1B[1;2H\x1B[2P\x1B[1;3H\x1B[3@X");
+    assert_eq!(screen.text(), "adX  ef");
+}
+
+#[test]
+fn insert_mode() {
+    let screen = render(1, 8, "abc\x1B[1;2H\x1B[4hXY");
+    assert_eq!(screen.text(), "aXYbc");
+}
+
+#[test]
+fn insert_and_delete_lines() {
+    let screen = render(4, 5, "a\r\nb\r\nc\r\nd\x1B[2;1H\x1B[L\x1B[4;1H\x1B[M");
+    assert_eq!(screen.text(), "a\n\nb");
+}
+
+#[test]
+fn erase() {
+    let screen = render(3, 5, "aaaaa\r\nbbbbb\r\nccccc\x1B[2;3H\x1B[K\x1B[1J");
+    assert_eq!(screen.text(), "\n\nccccc");
+}
+
+#[test]
+fn tab_stops() {
+    let screen = render(1, 20, "\x1B[3g\x1B[1;5H\x1BH\x1B[1;1Ha\tb\x1B[1;15H\x1B[Zc");
+    assert_eq!(screen.text(), "a   c");
+}
+
+#[test]
+fn tab_stop_report() {
+    let mut screen = render(1, 20, "\x1B[2$w");
+    assert_eq!(screen.drain_replies().as_str(), "\x1BP2$u9/17\x1B\\");
+}
+
+#[test]
+fn alternate_screen() {
+    let screen = render(2, 5, "main\x1B[?1049h\x1B[Hedit");
+    assert!(screen.alternate_screen());
+    assert_eq!(screen.text(), "edit");
+    let screen = render_with(screen, "\x1B[?1049l!");
+    assert!(!screen.alternate_screen());
+    assert_eq!(screen.text(), "main!");
+}
+
+#[test]
+fn cursor_position_report() {
+    let mut screen = render(5, 10, "ab\r\ncd\x1B[6n");
+    assert_eq!(screen.drain_replies().as_str(), "\x1B[2;3R");
+    assert_eq!(screen.drain_replies().as_str(), "");
+}
+
+#[test]
+fn cursor_position_report_origin_mode() {
+    let mut screen = render(5, 10, "\x1B[2;4r\x1B[?6h\x1B[2;2H\x1B[6n\x1B[?6n");
+    assert_eq!(screen.cursor(), (2, 1));
+    assert_eq!(screen.drain_replies().as_str(), "\x1B[2;2R\x1B[2;2;1R");
+}
+
+#[test]
+fn save_and_restore_cursor() {
+    let screen = render(3, 10, "\x1B[2;3H\x1B7\x1B[H\x1B8x");
+    assert_eq!(screen.text(), "\n  x");
+}
+
+#[test]
+fn styles() {
+    let screen = render(1, 5, "a\x1B[1;31mb");
+    let plain = screen.cell(0, 0).unwrap();
+    let styled = screen.cell(0, 1).unwrap();
+    assert!(plain.flags.is_empty());
+    assert_eq!(plain.foreground, None);
+    assert!(styled.flags.contains(TextStyle::Bold));
+    assert_ne!(styled.foreground, None);
+}
+
+#[test]
+fn hidden_cursor() {
+    assert!(!render(1, 5, "\x1B[?25l").cursor_visible());
+}
+
+#[test]
+fn oversized_resize() {
+    let screen = render(3, 5, "\x1B[8;65535;65535t");
+    assert_eq!(screen.rows(), Screen::MAX_ROWS);
+    assert_eq!(screen.columns(), Screen::MAX_COLUMNS);
+}
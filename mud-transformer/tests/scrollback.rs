@@ -0,0 +1,126 @@
+mod common;
+use common::transform;
+use mud_transformer::output::{
+    MatchSpan, Output, OutputFragment, ScrollbackBuffer, ScrollbackLine, SearchMatch, TextFragment,
+};
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+fn scrollback(input: &str) -> ScrollbackBuffer {
+    let mut scrollback = ScrollbackBuffer::new(100);
+    for fragment in transform(input).output() {
+        scrollback.push(fragment.into());
+    }
+    scrollback
+}
+
+fn texts(scrollback: &ScrollbackBuffer) -> Vec<&str> {
+    scrollback.iter().map(ScrollbackLine::text).collect()
+}
+
+#[test]
+fn stores_completed_lines() {
+    let scrollback = scrollback("one\r\n\r\n\x1B[1mtwo\x1B[0m three\r\nprompt> ");
+    assert_eq!(texts(&scrollback), ["one", "", "two three"]);
+    assert_eq!(scrollback[2].fragments().len(), 2);
+    assert_eq!(scrollback.pending(None).unwrap().text(), "prompt> ");
+}
+
+#[test]
+fn capacity() {
+    let mut scrollback = scrollback("1\n2\n3\n4\n");
+    scrollback.set_capacity(2);
+    assert_eq!(texts(&scrollback), ["3", "4"]);
+    scrollback.push(Output::from(TextFragment::from("5")));
+    scrollback.push(Output::from(OutputFragment::LineBreak));
+    assert_eq!(texts(&scrollback), ["4", "5"]);
+}
+
+#[test]
+fn windows() {
+    let comm = Some(mxp::Dest {
+        name: Some("Comm".into()),
+        ..Default::default()
+    });
+    let mut scrollback = ScrollbackBuffer::new(100);
+    scrollback.push(TextFragment::from("main ").into());
+    scrollback.push(Output {
+        window: comm.clone(),
+        ..TextFragment::from("chat").into()
+    });
+    scrollback.push(Output {
+        window: comm,
+        ..OutputFragment::LineBreak.into()
+    });
+    scrollback.push(TextFragment::from("text").into());
+    scrollback.push(OutputFragment::LineBreak.into());
+    assert_eq!(texts(&scrollback), ["chat", "main text"]);
+    let window = scrollback[0].window().and_then(|dest| dest.name.as_deref());
+    assert_eq!(window, Some("Comm"));
+    assert_eq!(scrollback[1].window(), None);
+}
+
+#[test]
+fn ignores_gagged_output() {
+    let mut scrollback = ScrollbackBuffer::new(100);
+    scrollback.push(Output {
+        gag: true,
+        ..TextFragment::from("secret").into()
+    });
+    scrollback.push(OutputFragment::LineBreak.into());
+    assert_eq!(texts(&scrollback), [""]);
+}
+
+#[test]
+fn search_across_fragments() {
+    let scrollback = scrollback("no match\r\nthe \x1B[31mred\x1B[0m dragon\r\n");
+    let expected = vec![SearchMatch {
+        line: 1,
+        range: 5..9,
+        spans: vec![
+            MatchSpan {
+                fragment: 1,
+                range: 1..3,
+            },
+            MatchSpan {
+                fragment: 2,
+                range: 0..2,
+            },
+        ],
+    }];
+    assert_eq!(scrollback.search("ed d"), expected);
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn search_regex() {
+    let scrollback = scrollback("You have 12 gold.\r\nYou have 340 gold.\r\n");
+    let found: Vec<_> = scrollback
+        .search_regex(&Regex::new(r"\d+").unwrap())
+        .into_iter()
+        .map(|found| (found.line, found.range))
+        .collect();
+    assert_eq!(found, [(0, 9..11), (1, 9..12)]);
+}
+
+#[test]
+fn remove_lines() {
+    let mut scrollback = scrollback("keep\r\nspam\r\nkeep\r\nspam\r\n");
+    scrollback.retain(|line| line.text() != "spam");
+    assert_eq!(texts(&scrollback), ["keep", "keep"]);
+    assert_eq!(scrollback.remove(0).unwrap().text(), "keep");
+    assert_eq!(scrollback.len(), 1);
+}
+
+#[test]
+fn expire_links() {
+    let scrollback = scrollback(
+        "\x1B[6z<SEND href=north expire=exits>north</SEND> <SEND href=look>look</SEND>\n<EXPIRE exits>",
+    );
+    let links: Vec<_> = scrollback[0]
+        .fragments()
+        .iter()
+        .map(|fragment| fragment.link.is_some())
+        .collect();
+    assert_eq!(links, [false, false, true]);
+}
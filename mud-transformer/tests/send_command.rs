@@ -0,0 +1,75 @@
+mod common;
+use common::transform_with;
+use mud_transformer::output::OutputFragment;
+use mud_transformer::{Encoding, TransformerConfig};
+
+#[test]
+fn line_endings() {
+    let mut transformer = transform_with(TransformerConfig::default(), "");
+    transformer.send_command("north\nsouth\r\n");
+    transformer.send_command("");
+    assert_eq!(transformer.input_bytes(), b"north\r\nsouth\r\n\r\n");
+}
+
+#[test]
+fn escapes_iac() {
+    let config = TransformerConfig {
+        encoding: Encoding::Latin1,
+        ..Default::default()
+    };
+    let mut transformer = transform_with(config, "");
+    transformer.send_command("say ÿes");
+    assert_eq!(transformer.input_bytes(), b"say \xFF\xFFes\r\n");
+}
+
+#[test]
+fn encodes_negotiated_charset() {
+    let mut transformer = transform_with(
+        TransformerConfig::default(),
+        b"\xFF\xFA\x2A\x01;KOI8-R\xFF\xF0",
+    );
+    transformer.input_bytes();
+    transformer.send_command("say привет ☃");
+    assert_eq!(
+        transformer.input_bytes(),
+        b"say \xD0\xD2\xC9\xD7\xC5\xD4 ?\r\n"
+    );
+}
+
+#[test]
+fn ascii_only() {
+    let config = TransformerConfig {
+        disable_utf8: true,
+        ..Default::default()
+    };
+    let mut transformer = transform_with(config, "");
+    transformer.send_command("say café");
+    assert_eq!(transformer.input_bytes(), b"say caf?\r\n");
+}
+
+#[test]
+fn local_echo() {
+    let config = TransformerConfig {
+        local_echo: true,
+        ..Default::default()
+    };
+    let mut transformer = transform_with(config, "");
+    transformer.send_command("look");
+    assert_eq!(
+        transformer.output(),
+        &[OutputFragment::LocalEcho("look".to_owned())]
+    );
+}
+
+#[test]
+fn local_echo_disabled_by_server_echo() {
+    let config = TransformerConfig {
+        local_echo: true,
+        ..Default::default()
+    };
+    let mut transformer = transform_with(config, b"\xFF\xFB\x01");
+    transformer.output();
+    transformer.send_command("hunter2");
+    assert!(transformer.output().is_empty());
+    assert_eq!(transformer.input_bytes(), b"\xFF\xFD\x01hunter2\r\n");
+}
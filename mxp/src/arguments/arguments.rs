@@ -3,12 +3,14 @@ use std::collections::hash_map;
 use std::fmt;
 use std::str::FromStr;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use uncased::Uncased;
 
 use super::iter::{Named, Positional};
 use crate::CaseFoldMap;
-use crate::parse::{ArgumentParser, OwnedScan, Scan, validate};
-use crate::{Error, ErrorKind};
+use crate::parse::{ArgumentParser, OwnedScan, Scan, span_of, validate};
+use crate::{Error, ErrorKind, ParseOptions};
 
 /// Parsed arguments of an MXP command.
 ///
@@ -20,6 +22,7 @@ use crate::{Error, ErrorKind};
 /// anything with `Arguments` beyond parsing them from text and passing them to mxp functions.
 ///
 /// See [MXP specification: Attributes](https://www.zuggsoft.com/zmud/mxp.htm#ATTLIST).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Arguments<'a, S = &'a str> {
     positional: Vec<S>,
@@ -141,6 +144,32 @@ impl<'a> Arguments<'a> {
         ArgumentParser::new(source).try_into()
     }
 
+    /// Parses arguments from a string slice without cloning the data, applying the recoveries
+    /// enabled in `options`. Each error that is recovered from is appended to `warnings`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mxp::{Arguments, ErrorKind, ParseOptions};
+    ///
+    /// let source = "say #1=hi fore=";
+    /// assert!(Arguments::parse(source).is_err());
+    ///
+    /// let mut warnings = Vec::new();
+    /// let args = Arguments::parse_with(source, ParseOptions::LENIENT, &mut warnings).unwrap();
+    /// assert_eq!(args.at(1), Some(&"#1=hi"));
+    /// assert_eq!(args.get("fore"), Some(&""));
+    /// let kinds: Vec<ErrorKind> = warnings.iter().map(mxp::Error::kind).collect();
+    /// assert_eq!(kinds, [ErrorKind::InvalidArgumentName, ErrorKind::EmptyArgument]);
+    /// ```
+    pub fn parse_with(
+        source: &'a str,
+        options: ParseOptions,
+        warnings: &mut Vec<Error>,
+    ) -> crate::Result<Self> {
+        Self::from_parser(ArgumentParser::new(source), options, warnings)
+    }
+
     pub(crate) fn into_scan(self) -> OwnedScan<'a> {
         OwnedScan::new(self.positional, self.named)
     }
@@ -153,22 +182,68 @@ where
     type Error = Error;
 
     fn try_from(args: ArgumentParser<'b>) -> crate::Result<Self> {
+        Self::from_parser(args, ParseOptions::STRICT, &mut Vec::new())
+    }
+}
+
+impl<'a, S> Arguments<'a, S> {
+    fn from_parser<'b>(
+        args: ArgumentParser<'b>,
+        options: ParseOptions,
+        warnings: &mut Vec<Error>,
+    ) -> crate::Result<Self>
+    where
+        S: From<&'b str> + Into<Cow<'a, str>>,
+    {
         let generous_size_guess = args.size_hint().1.unwrap();
+        let source = args.source();
         let mut positional = Vec::with_capacity(generous_size_guess);
         let mut named = CaseFoldMap::with_capacity(generous_size_guess);
         for entry in args {
-            let (name, value) = entry?;
-            if let Some(value) = value {
-                validate(name, ErrorKind::InvalidArgumentName)?;
-                named.insert(S::from(name).into(), S::from(value));
-            } else {
+            let (name, value) = match entry {
+                Ok(entry) => entry,
+                Err(e) if e.kind() == ErrorKind::EmptyArgument && options.recovers(e.kind()) => {
+                    // Empty argument: the span covers `name=`.
+                    let span = e.span().expect("argument errors have spans");
+                    warnings.push(e);
+                    (
+                        &source[span.start..span.end - 1],
+                        Some(&source[span.end..span.end]),
+                    )
+                }
+                Err(e) => return Err(e),
+            };
+            let Some(value) = value else {
                 positional.push(S::from(name));
+                continue;
+            };
+            if let Err(e) = validate(name, ErrorKind::InvalidArgumentName) {
+                let e = e.within(source, name);
+                if !options.recovers(e.kind()) {
+                    return Err(e);
+                }
+                warnings.push(e);
+                positional.push(S::from(verbatim(source, name, value)));
+                continue;
             }
+            named.insert(S::from(name).into(), S::from(value));
         }
         Ok(Self { positional, named })
     }
 }
 
+/// Returns the full text of a `name=value` argument, including quotes around the value.
+fn verbatim<'a>(source: &'a str, name: &str, value: &str) -> &'a str {
+    let start = span_of(source, name).start;
+    let value = span_of(source, value);
+    let bytes = source.as_bytes();
+    let end = match bytes[value.start - 1] {
+        quote @ (b'"' | b'\'') if bytes.get(value.end) == Some(&quote) => value.end + 1,
+        _ => value.end,
+    };
+    &source[start..end]
+}
+
 impl<S: AsRef<str>> fmt::Display for Arguments<'_, S> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use crate::display::{DelimAfterFirst, MaybeQuote};
@@ -209,4 +284,24 @@ mod tests {
         };
         assert_eq!(args, expected);
     }
+
+    #[test]
+    fn recover_invalid_name_quoted() {
+        let mut warnings = Vec::new();
+        let args = Arguments::parse_with("#a='b c' d", ParseOptions::LENIENT, &mut warnings);
+        assert_eq!(args.unwrap().positional, vec!["#a='b c'", "d"]);
+        assert_eq!(warnings[0].span(), Some(0..2));
+    }
+
+    #[test]
+    fn recover_empty_invalid_name() {
+        let mut warnings = Vec::new();
+        let args = Arguments::parse_with("#a=", ParseOptions::LENIENT, &mut warnings);
+        assert_eq!(args.unwrap().positional, vec!["#a="]);
+        let kinds: Vec<_> = warnings.iter().map(Error::kind).collect();
+        assert_eq!(
+            kinds,
+            [ErrorKind::EmptyArgument, ErrorKind::InvalidArgumentName]
+        );
+    }
 }
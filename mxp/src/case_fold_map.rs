@@ -6,11 +6,19 @@ use std::{fmt, iter};
 
 use uncased::{Uncased, UncasedStr};
 
-#[derive(Default)]
 pub(crate) struct CaseFoldMap<'a, V, S = RandomState> {
     base: HashMap<Uncased<'a>, V, S>,
 }
 
+impl<V, S: Default> Default for CaseFoldMap<'_, V, S> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            base: HashMap::default(),
+        }
+    }
+}
+
 impl<V, S> PartialEq for CaseFoldMap<'_, V, S>
 where
     V: PartialEq,
@@ -151,3 +159,30 @@ impl<'a, V, S> IntoIterator for CaseFoldMap<'a, V, S> {
         self.base.into_iter()
     }
 }
+
+#[cfg(feature = "serde")]
+impl<V, S> serde::Serialize for CaseFoldMap<'_, V, S>
+where
+    V: serde::Serialize,
+{
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_map(self.base.iter().map(|(k, v)| (k.as_str(), v)))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, V, S> serde::Deserialize<'de> for CaseFoldMap<'_, V, S>
+where
+    V: serde::Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let base = HashMap::<String, V, S>::deserialize(deserializer)?;
+        Ok(Self {
+            base: base
+                .into_iter()
+                .map(|(key, value)| (Uncased::from_owned(key), value))
+                .collect(),
+        })
+    }
+}
@@ -5,8 +5,8 @@ use super::action_kind::ActionKind;
 use super::atomic_tag::AtomicTag;
 use crate::arguments::{ArgumentScanner, Arguments, ExpectArg};
 use crate::elements::{
-    Color, Dest, Expire, Filter, Font, Frame, Gauge, Heading, Hyperlink, Image, Music, Relocate,
-    Send, Sound, Stat, StyleVersion, Support, Var,
+    Color, Custom, Dest, Expire, Filter, Font, Frame, Gauge, Heading, Hyperlink, Image, Music,
+    Relocate, Send, Sound, Stat, StyleVersion, Support, Var,
 };
 use crate::parse::{FromStrError, IntoOwnedString, split_name, validate};
 use crate::{Error, ErrorKind};
@@ -23,6 +23,8 @@ pub enum Action<S = String> {
     ///[`<COLOR>`](https://www.zuggsoft.com/zmud/mxp.htm#Text%20Formatting):
     /// Change text color.
     Color(Color),
+    /// Client-specific tag registered with [`State::register_tag`](crate::State::register_tag).
+    Custom(Custom<S>),
     /// [`<DEST>`](https://www.zuggsoft.com/zmud/mxp.htm#Cursor%20Control):
     /// Set destination frame.
     Dest(Dest<S>),
@@ -221,6 +223,7 @@ impl<S> Action<S> {
             Self::Bold => Action::Bold,
             Self::Br => Action::Br,
             Self::Color(color) => Action::Color(color),
+            Self::Custom(custom) => Action::Custom(custom.map_text(f)),
             Self::Dest(dest) => Action::Dest(dest.map_text(f)),
             Self::Expire(expire) => Action::Expire(expire.map_text(f)),
             Self::Filter(filter) => Action::Filter(filter.map_text(f)),
@@ -262,16 +265,16 @@ impl<S> Action<S> {
 impl FromStr for Action<String> {
     type Err = FromStrError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = crate::parse::cleanup_source(s)?;
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let s = crate::parse::cleanup_source(source)?;
         let (name, args) = split_name(s);
         if name.is_empty() {
             return Err(Error::new(s, ErrorKind::EmptyElement).into());
         }
-        validate(name, ErrorKind::InvalidElementName)?;
+        validate(name, ErrorKind::InvalidElementName).map_err(|e| e.within(source, name))?;
         let tag = AtomicTag::well_known(name)
             .ok_or_else(|| FromStrError::UnexpectedTag(name.to_owned()))?;
-        let args = Arguments::parse(args)?;
+        let args = Arguments::parse(args).map_err(|e| e.within(source, args))?;
         tag.check_arguments(&args)?;
         Ok(Action::decode(tag.action, args.scan().with_decoder(()))?.into_owned())
     }
@@ -283,6 +286,7 @@ impl<S: AsRef<str>> fmt::Display for Action<S> {
             Self::Bold => f.write_str("<B>"),
             Self::Br => f.write_str("<BR>"),
             Self::Color(color) => color.fmt(f),
+            Self::Custom(custom) => custom.fmt(f),
             Self::Dest(dest) => dest.fmt(f),
             Self::Expire(expire) => expire.fmt(f),
             Self::Filter(filter) => filter.fmt(f),
@@ -298,3 +298,22 @@ impl AtomicTag {
         Self::VERSION,
     ];
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AtomicTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for &'static AtomicTag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de;
+
+        let name = String::deserialize(deserializer)?;
+        AtomicTag::well_known(&name).ok_or_else(|| {
+            de::Error::invalid_value(de::Unexpected::Str(&name), &"a standard MXP tag name")
+        })
+    }
+}
@@ -200,11 +200,13 @@ impl AttributeList {
     /// Adds attributes to the list from parsed arguments.
     fn append_args(&mut self, args: ArgumentParser) -> crate::Result<()> {
         self.reserve(args.size_hint().1.unwrap());
+        let source = args.source();
         for entry in args {
             let (name, value) = entry?;
-            validate(name, ErrorKind::InvalidArgumentName)?;
+            validate(name, ErrorKind::InvalidArgumentName).map_err(|e| e.within(source, name))?;
             if self.attributes.contains_key(name) {
-                return Err(Error::new(name, ErrorKind::DuplicateAttributeInAttlist));
+                let error = Error::new(name, ErrorKind::DuplicateAttributeInAttlist);
+                return Err(error.within(source, name));
             }
             self.push(name.to_owned(), value.map(ToOwned::to_owned));
         }
@@ -232,6 +234,33 @@ impl fmt::Display for AttributeList {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for AttributeList {
+    /// Serializes the list as a sequence of `(name, default_value)` pairs, ordered by position.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut attributes: Vec<_> = self.attributes.iter().collect();
+        attributes.sort_unstable_by_key(|(_, attr)| attr.position);
+        serializer.collect_seq(
+            attributes
+                .into_iter()
+                .map(|(name, attr)| (name.as_str(), &attr.value)),
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AttributeList {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let attributes = Vec::<(String, Option<String>)>::deserialize(deserializer)?;
+        let mut list = Self::new();
+        list.reserve(attributes.len());
+        for (name, default_value) in attributes {
+            list.push(name, default_value);
+        }
+        Ok(list)
+    }
+}
+
 impl TryFrom<ArgumentParser<'_>> for AttributeList {
     type Error = Error;
 
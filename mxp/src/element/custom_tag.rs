@@ -0,0 +1,168 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use super::action::Action;
+use crate::arguments::Arguments;
+use crate::elements::Custom;
+use crate::parse::Decoder;
+use crate::{Error, ErrorKind};
+
+/// Signature of [`CustomTag::decode`]. Receives the tag and its arguments, with entities already
+/// decoded, and produces the contents of an [`Action::Custom`].
+pub type DecodeCustom =
+    for<'a> fn(&CustomTag, &Arguments<'a, Cow<'a, str>>) -> crate::Result<Custom<Cow<'a, str>>>;
+
+/// Client-specific atomic tag, registered with [`State::register_tag`].
+///
+/// Custom tags behave like the built-in [`AtomicTag`](super::AtomicTag)s: they are subject to the
+/// same secure/open checks, they are listed in `<SUPPORT>` responses, and they resolve into an
+/// [`Action`]—specifically, [`Action::Custom`].
+///
+/// Custom tags cannot be used inside element definitions, since element definitions are parsed
+/// without access to the [`State`].
+///
+/// [`State`]: crate::State
+/// [`State::register_tag`]: crate::State::register_tag
+///
+/// # Examples
+///
+/// ```
+/// use mxp::element::CustomTag;
+///
+/// const MAPPER: CustomTag = CustomTag {
+///     name: "MAPPER",
+///     args: &["room", "exits"],
+///     open: false,
+///     command: true,
+///     decode: CustomTag::decode_args,
+/// };
+///
+/// let mut state = mxp::State::with_globals();
+/// state.register_tag(MAPPER).unwrap();
+/// ```
+#[derive(Copy, Clone)]
+pub struct CustomTag {
+    /// Tag name, such as `"MAPPER"`.
+    pub name: &'static str,
+    /// Arguments supported by the tag, such as `"room"`.
+    pub args: &'static [&'static str],
+    /// OPEN tags can be used in any [`Mode`](crate::Mode). Otherwise, the tag can only be used if
+    /// the current line mode is secure.
+    pub open: bool,
+    /// True if the tag is a command tag. Command tags do not have content, so they have no closing
+    /// tag.
+    pub command: bool,
+    /// Function that resolves the tag's arguments into an [`Action::Custom`]. For most tags,
+    /// [`CustomTag::decode_args`] is sufficient.
+    pub decode: DecodeCustom,
+}
+
+impl fmt::Debug for CustomTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CustomTag")
+            .field("name", &self.name)
+            .field("args", &self.args)
+            .field("open", &self.open)
+            .field("command", &self.command)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Tags are compared by name and definition. [`decode`](CustomTag::decode) is not compared, since
+/// function pointers have no reliable identity.
+impl PartialEq for CustomTag {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.args == other.args
+            && self.open == other.open
+            && self.command == other.command
+    }
+}
+
+impl Eq for CustomTag {}
+
+impl CustomTag {
+    /// Default implementation for [`decode`](Self::decode). Each of the tag's supported
+    /// [`args`](Self::args) is matched to a named argument or, failing that, the next positional
+    /// argument. Arguments that are not supplied are omitted from the result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use mxp::element::CustomTag;
+    ///
+    /// const MAPPER: CustomTag = CustomTag {
+    ///     name: "MAPPER",
+    ///     args: &["room", "exits"],
+    ///     open: false,
+    ///     command: true,
+    ///     decode: CustomTag::decode_args,
+    /// };
+    ///
+    /// let args = mxp::Arguments::parse("exits='n s' 1024").unwrap();
+    /// let action = MAPPER.decode(&args, ()).unwrap();
+    /// assert_eq!(
+    ///     action,
+    ///     mxp::Action::Custom(mxp::Custom {
+    ///         name: "MAPPER",
+    ///         values: vec![("room", Cow::Borrowed("1024")), ("exits", Cow::Borrowed("n s"))],
+    ///     }),
+    /// );
+    /// ```
+    pub fn decode_args<'a>(
+        &self,
+        args: &Arguments<'a, Cow<'a, str>>,
+    ) -> crate::Result<Custom<Cow<'a, str>>> {
+        let mut positional = args.positional();
+        let values = self
+            .args
+            .iter()
+            .filter_map(|&name| {
+                let value = args.get(name).or_else(|| positional.next())?;
+                Some((name, value.clone()))
+            })
+            .collect();
+        if let Some(extra) = positional.next() {
+            return Err(Error::new(extra.as_ref(), ErrorKind::UnexpectedArgument));
+        }
+        Ok(Custom {
+            name: self.name,
+            values,
+        })
+    }
+
+    /// Resolves a `CustomTag` into an [`Action`] by decoding arguments and supplying them to the
+    /// tag's [`decode`](Self::decode) function.
+    pub fn decode<'a, D: Decoder>(
+        &self,
+        args: &'a Arguments<'a>,
+        decoder: D,
+    ) -> crate::Result<Action<Cow<'a, str>>> {
+        self.check_arguments(args)?;
+        let mut values = Arguments::new();
+        for &arg in args.positional() {
+            values.push(decoder.decode_string(arg)?);
+        }
+        for (name, &value) in args.named() {
+            values.insert(name, decoder.decode_string(value)?);
+        }
+        (self.decode)(self, &values)
+            .map(Action::Custom)
+            .map_err(|e| e.with_context(format_args!(" for <{}>", self.name)))
+    }
+
+    /// Returns `true` if the tag supports a specific argument.
+    ///
+    /// Case-insensitive.
+    pub fn supports(&self, arg: &str) -> bool {
+        self.args.iter().any(|name| name.eq_ignore_ascii_case(arg))
+    }
+
+    fn check_arguments(&self, args: &Arguments) -> crate::Result<()> {
+        match args.keys().find(|arg| !self.supports(arg.as_str())) {
+            None => Ok(()),
+            Some(arg) => Err(Error::new(arg.as_str(), ErrorKind::UnexpectedArgument)),
+        }
+    }
+}
@@ -5,10 +5,11 @@ use std::slice;
 use super::action::Action;
 use super::element::Element;
 use super::item::ElementItem;
+use crate::Error;
 use crate::arguments::Arguments;
 use crate::element::AttributeList;
 use crate::entity::DecodedEntity;
-use crate::parse::Decoder;
+use crate::parse::{Decoder, ParseOptions};
 
 /// This struct is created by [`Element::decoder`](crate::element::Element::decoder).
 /// See its documentation for more.
@@ -46,6 +47,14 @@ impl<D: Decoder> Decoder for ElementDecoder<'_, D> {
             None => self.decoder.decode_entity(name),
         }
     }
+
+    fn parse_options(&self) -> ParseOptions {
+        self.decoder.parse_options()
+    }
+
+    fn warn(&self, warning: Error) {
+        self.decoder.warn(warning);
+    }
 }
 
 /// This struct is created by [`Element::decode`](crate::element::Element::decode).
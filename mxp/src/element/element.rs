@@ -1,5 +1,8 @@
 use std::fmt;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use super::atomic_tag::AtomicTag;
 use super::decoder::{ElementDecodeIter, ElementDecoder};
 use super::flag::ElementFlag;
@@ -25,6 +28,7 @@ use crate::parse::Decoder;
 /// [`Action`]: crate::Action
 /// [`ElementDefinition`]: crate::node::ElementDefinition
 /// [`Definition`]: crate::node::Definition
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Element {
     /// Tag name.
@@ -1,8 +1,12 @@
 use std::fmt;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use super::parse_as::ParseAs;
 use crate::parse::UnrecognizedVariant;
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, PartialEq, Eq)]
 /// The `FLAG` argument in an [`Element`] definition, which assigns an internal action to the
 /// element.
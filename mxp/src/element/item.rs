@@ -1,6 +1,9 @@
 use std::borrow::Cow;
 use std::fmt;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use super::action::Action;
 use super::atomic_tag::AtomicTag;
 use super::decoder::ElementDecoder;
@@ -9,6 +12,7 @@ use crate::parse::{Decoder, count_bytes, split_name, strip_terminating_slash, va
 use crate::{Error, ErrorKind};
 
 /// List of arguments to an MXP tag.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ElementItem {
     /// Standard atomic tag to apply. Determines the [`Action`](crate::Action) of this item.
@@ -45,12 +49,14 @@ impl ElementItem {
         }
         let (args, _) = strip_terminating_slash(body);
         if let Some(tag) = AtomicTag::well_known(tag_name) {
-            let arguments = args.parse()?;
+            let arguments = args.parse().map_err(|e: Error| e.within(source, args))?;
             tag.check_arguments(&arguments)?;
             return Ok(Self { tag, arguments });
         }
-        validate(tag_name, ErrorKind::InvalidElementName)?;
-        Err(Error::new(tag_name, ErrorKind::UnknownElementInDefinition))
+        validate(tag_name, ErrorKind::InvalidElementName)
+            .map_err(|e| e.within(source, tag_name))?;
+        let error = Error::new(tag_name, ErrorKind::UnknownElementInDefinition);
+        Err(error.within(source, tag_name))
     }
 
     pub(crate) fn parse_all(source: &str) -> crate::Result<Vec<Self>> {
@@ -61,22 +67,30 @@ impl ElementItem {
         let mut iter = bytes.iter().enumerate();
         while let Some((start, &startc)) = iter.next() {
             if startc != b'<' {
-                return Err(Error::new(source, ErrorKind::NoTagInDefinition));
+                let end = source[start..]
+                    .find('<')
+                    .map_or(source.len(), |i| start + i);
+                let error = Error::new(source, ErrorKind::NoTagInDefinition);
+                return Err(error.with_span(start..end));
             }
             loop {
                 let (end, &endc) = iter.next().ok_or_else(|| {
                     Error::new(source, ErrorKind::UnterminatedElementInDefinition)
+                        .with_span(start..source.len())
                 })?;
                 match endc {
                     b'<' => {
-                        return Err(Error::new(source, ErrorKind::UnexpectedSymbolInDefinition));
+                        let error = Error::new(source, ErrorKind::UnexpectedSymbolInDefinition);
+                        return Err(error.with_span(end..end + 1));
                     }
                     b'>' => {
-                        items.push(ElementItem::parse(&source[start + 1..end])?);
+                        let item = &source[start + 1..end];
+                        items.push(ElementItem::parse(item).map_err(|e| e.within(source, item))?);
                         break;
                     }
                     b'\'' | b'"' if !iter.any(|(_, &c)| c == endc) => {
-                        return Err(Error::new(source, ErrorKind::UnterminatedQuoteInDefinition));
+                        let error = Error::new(source, ErrorKind::UnterminatedQuoteInDefinition);
+                        return Err(error.with_span(end..source.len()));
                     }
                     _ => (),
                 }
@@ -12,6 +12,9 @@ pub use atomic_tag::AtomicTag;
 mod attribute_list;
 pub use attribute_list::AttributeList;
 
+mod custom_tag;
+pub use custom_tag::{CustomTag, DecodeCustom};
+
 mod decoder;
 pub use decoder::{ElementDecodeIter, ElementDecoder};
 
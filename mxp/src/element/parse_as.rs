@@ -1,10 +1,14 @@
 use std::fmt;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::parse::UnrecognizedVariant;
 
 /// The MUD server can tag a line to be parsed in a specific way by the client.
 ///
 /// See [MXP specification: Tag Properties](https://www.zuggsoft.com/zmud/mxp.htm#Tag%20Properties).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ParseAs {
     /// The text for the element is parsed by the automapper as the name of a room.
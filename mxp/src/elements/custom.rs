@@ -0,0 +1,70 @@
+use std::fmt;
+
+/// Effect of a client-specific [`CustomTag`], registered with [`State::register_tag`].
+///
+/// [`CustomTag`]: crate::element::CustomTag
+/// [`State::register_tag`]: crate::State::register_tag
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Custom<S = String> {
+    /// Name of the tag, as registered.
+    pub name: &'static str,
+    /// Values produced by the tag's decode function, keyed by argument name.
+    pub values: Vec<(&'static str, S)>,
+}
+
+impl<S> Custom<S> {
+    /// Applies a type transformation to all text, returning a new struct.
+    pub fn map_text<T, F>(self, mut f: F) -> Custom<T>
+    where
+        F: FnMut(S) -> T,
+    {
+        Custom {
+            name: self.name,
+            values: self
+                .values
+                .into_iter()
+                .map(|(key, value)| (key, f(value)))
+                .collect(),
+        }
+    }
+
+    /// Finds the value associated with an argument name.
+    ///
+    /// Case-insensitive.
+    pub fn get(&self, name: &str) -> Option<&S> {
+        self.values
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+}
+
+impl_into_owned!(Custom);
+
+impl<S: AsRef<str>> Custom<S> {
+    /// Returns a new struct that borrows text from this one.
+    pub fn borrow_text(&self) -> Custom<&str> {
+        Custom {
+            name: self.name,
+            values: self
+                .values
+                .iter()
+                .map(|(key, value)| (*key, value.as_ref()))
+                .collect(),
+        }
+    }
+}
+
+impl_partial_eq!(Custom);
+
+impl<S: AsRef<str>> fmt::Display for Custom<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use crate::display::Escape;
+
+        write!(f, "<{}", self.name)?;
+        for (key, value) in &self.values {
+            write!(f, " {key}={}", Escape(value.as_ref()))?;
+        }
+        f.write_str(">")
+    }
+}
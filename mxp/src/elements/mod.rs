@@ -7,6 +7,9 @@ mod color;
 
 pub use color::Color;
 
+mod custom;
+pub use custom::Custom;
+
 mod dest;
 pub use dest::Dest;
 
@@ -2,6 +2,9 @@ use std::collections::HashMap;
 use std::hash::{BuildHasher, RandomState};
 use std::sync::LazyLock;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use super::visibility::EntityVisibility;
 
 /// Stores information from the MUD (MUD variables).
@@ -9,6 +12,7 @@ use super::visibility::EntityVisibility;
 /// Once an entity is defined, its value can be referenced by using the `&Name;` syntax.
 ///
 /// See [MXP specification: Entities](https://www.zuggsoft.com/zmud/mxp.htm#ENTITY).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Entity {
     /// Value stored in the variable.
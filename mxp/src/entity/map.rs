@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
-use std::collections::hash_map::Entry;
+use std::collections::hash_map::{self, Entry};
 use std::fmt;
 use std::hash::BuildHasher;
 use std::hash::RandomState;
@@ -282,6 +282,11 @@ impl<S: BuildHasher> EntityMap<S> {
         })
     }
 
+    /// An iterator visiting all custom entities, including private ones, in arbitrary order.
+    pub(crate) fn custom_entities(&self) -> hash_map::Iter<'_, String, Entity> {
+        self.base.iter()
+    }
+
     /// Returns the value of a custom MXP entity, or `None` if there is no entity with the specified
     /// name or the entity with the specified name was marked as PRIVATE
     /// ([`EntityVisibility::Private`]) by the server.
@@ -439,6 +444,42 @@ impl<S> fmt::Debug for EntityMap<S> {
     }
 }
 
+/// Serializes custom entities along with a flag indicating whether global XML entities are
+/// recognized. Global entities themselves are not serialized.
+#[cfg(feature = "serde")]
+impl<S> serde::Serialize for EntityMap<S> {
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("EntityMap", 2)?;
+        state.serialize_field("entities", &self.base)?;
+        state.serialize_field("globals", &!self.globals.is_empty())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, S: BuildHasher + Default> serde::Deserialize<'de> for EntityMap<S> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "EntityMap", bound = "S: BuildHasher + Default")]
+        struct Fields<S> {
+            entities: HashMap<String, Entity, S>,
+            globals: bool,
+        }
+
+        let Fields { entities, globals } = Fields::<S>::deserialize(deserializer)?;
+        Ok(Self {
+            base: entities,
+            globals: if globals {
+                Entity::globals(S::default())
+            } else {
+                HashMap::default()
+            },
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use flagset::FlagSet;
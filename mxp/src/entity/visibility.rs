@@ -1,10 +1,13 @@
 use flagset::FlagSet;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::keyword::EntityKeyword;
 
 /// Visibility of an [`Entity`](crate::Entity), as defined by the server.
 ///
 /// See [MXP specification: Entities](https://www.zuggsoft.com/zmud/mxp.htm#ENTITY).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum EntityVisibility {
     /// By default, MXP entities can be queried, but are not listed.
@@ -60,7 +60,9 @@
 //! Unlike everything else in MXP, which uses XML syntax, line modes are set by ANSI escape
 //! sequences. For example, to set the MXP mode to 20, the MUD server would send `<ESC>[20z`.
 //! As such, it is up to the client to recognize MXP mode changes and apply them with
-//! [`ModeState::set`] and [`ModeState::revert`].
+//! [`ModeState::set`] and [`ModeState::revert`]. Alternatively, [`Tokenizer`] can be used to
+//! split incoming bytes into text, tags, entities, and line mode changes, tracking the line mode
+//! automatically.
 //!
 //! [`FromStr`]: std::str::FromStr
 //! [`Tag::parse`]: node::Tag::parse
@@ -105,6 +107,11 @@
 //!                 handle_action(&action?);
 //!             }
 //!         }
+//!         // server sent a tag registered by the client with `State::register_tag`
+//!         mxp::Component::Custom(custom) => {
+//!             let action = custom.decode(&tag.arguments, mxp_state)?;
+//!             handle_action(&action);
+//!         }
 //!     }
 //!     Ok(())
 //! }
@@ -202,11 +209,17 @@ pub mod escape;
 
 mod keyword;
 
+mod limits;
+pub use limits::Limits;
+
 mod line;
 pub use line::{LineTag, LineTagProperties, Mode, ModeRangeError, ModeState};
 
 mod parse;
-pub use parse::{Decoder, Error, ErrorKind, is_valid, validate, validate_utf8};
+pub use parse::{
+    Decoder, Diagnostic, Error, ErrorKind, LenientDecoder, ParseOptions, is_valid, validate,
+    validate_utf8,
+};
 
 pub mod node;
 
@@ -216,7 +229,10 @@ mod screen;
 pub use screen::{Align, Dimension, DimensionUnit};
 
 mod state;
-pub use state::{Component, State};
+pub use state::{Component, CustomTags, Definitions, DefinitionsIter, State};
+
+mod tokenizer;
+pub use tokenizer::{Token, Tokenizer, Tokens};
 
 /// Type alias for `Result<T, mxp::Error>`.
 pub type Result<T> = std::result::Result<T, Error>;
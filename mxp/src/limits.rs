@@ -0,0 +1,75 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Quotas on the resources a server can consume through MXP.
+///
+/// Without limits, a hostile or buggy server could define an unbounded number of elements and
+/// entities, store arbitrarily large entity values, or open tags without ever closing them. When a
+/// quota would be exceeded, the offending definition or tag is refused with an error, and
+/// everything stored so far is left intact.
+///
+/// [`State`](crate::State) enforces every limit except [`max_depth`](Self::max_depth), which
+/// clients must enforce themselves, since the state does not track open tags.
+///
+/// # Examples
+///
+/// ```
+/// use mxp::node::Tag;
+///
+/// let mut state = mxp::State::with_globals();
+/// state.set_limits(mxp::Limits {
+///     max_entity_len: 8,
+///     ..Default::default()
+/// });
+/// let Tag::Definition(definition) = Tag::parse("!EN name 'Dreadful Bob'", true).unwrap() else {
+///     unreachable!();
+/// };
+/// let error = state.define(definition).unwrap_err();
+/// assert_eq!(error.kind(), mxp::ErrorKind::EntityTooLong);
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Limits {
+    /// Maximum number of elements, including the elements predefined by
+    /// [`State::with_globals`](crate::State::with_globals).
+    /// Default: 1024.
+    pub max_elements: usize,
+    /// Maximum number of custom entities. Global XML entities are not counted.
+    /// Default: 4096.
+    pub max_entities: usize,
+    /// Maximum length of an entity value, in bytes.
+    /// Default: 8192.
+    pub max_entity_len: usize,
+    /// Maximum number of attributes belonging to an element.
+    /// Default: 64.
+    pub max_attributes: usize,
+    /// Maximum number of tags that can be open at once.
+    /// Default: 256.
+    pub max_depth: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl Limits {
+    /// Generous limits that no well-behaved server should reach.
+    pub const DEFAULT: Self = Self {
+        max_elements: 1024,
+        max_entities: 4096,
+        max_entity_len: 8192,
+        max_attributes: 64,
+        max_depth: 256,
+    };
+
+    /// No limits at all.
+    pub const UNLIMITED: Self = Self {
+        max_elements: usize::MAX,
+        max_entities: usize::MAX,
+        max_entity_len: usize::MAX,
+        max_attributes: usize::MAX,
+        max_depth: usize::MAX,
+    };
+}
@@ -1,10 +1,13 @@
 use super::mode::Mode;
 use super::tag::LineTag;
 use crate::element::ParseAs;
-use crate::{Error, ErrorKind};
+use crate::{Error, ErrorKind, ParseOptions};
 
 /// State tracker for [`Mode`].
 ///
+/// A `ModeState` also carries the [`ParseOptions`] for the connection, so that every component
+/// reading from it, such as a [`Tokenizer`](crate::Tokenizer), applies the same recoveries.
+///
 /// See [MXP specification: MXP Line Tags](https://www.zuggsoft.com/zmud/mxp.htm#User-defined%20Line%20Tags).
 //
 // Note: these modes are never PERM_LOCKED, PERM_OPEN, PERM_SECURE, or RESET.
@@ -13,6 +16,7 @@ pub struct ModeState {
     active_mode: Mode,
     default_mode: Mode,
     previous_mode: Mode,
+    options: ParseOptions,
 }
 
 impl Default for ModeState {
@@ -34,13 +38,39 @@ impl ModeState {
     /// assert_eq!(mode_state.get(), mxp::Mode::OPEN);
     /// ```
     pub const fn new() -> Self {
+        Self::with_options(ParseOptions::STRICT)
+    }
+
+    /// Constructs a new `ModeState` in [`Mode::OPEN`] mode with the specified parse options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mxp::{ModeState, ParseOptions};
+    ///
+    /// let mode_state = ModeState::with_options(ParseOptions::LENIENT);
+    /// assert_eq!(mode_state.options(), ParseOptions::LENIENT);
+    /// ```
+    pub const fn with_options(options: ParseOptions) -> Self {
         Self {
             active_mode: Mode::OPEN,
             default_mode: Mode::OPEN,
             previous_mode: Mode::OPEN,
+            options,
         }
     }
 
+    /// Returns the parse options for the connection.
+    #[inline]
+    pub const fn options(&self) -> ParseOptions {
+        self.options
+    }
+
+    /// Replaces the parse options for the connection.
+    pub const fn set_options(&mut self, options: ParseOptions) {
+        self.options = options;
+    }
+
     /// Gets the active mode.
     ///
     /// # Examples
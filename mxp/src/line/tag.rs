@@ -1,6 +1,9 @@
 use std::fmt;
 use std::ops::Deref;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::color::RgbColor;
 use crate::element::Element;
 use crate::node::LineTagDefinition;
@@ -26,6 +29,7 @@ impl Deref for LineTag<'_> {
 /// Properties defined for a line tag.
 ///
 /// See [MXP specification: Line Tags](https://www.zuggsoft.com/zmud/mxp.htm#User-defined%20Line%20Tags).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct LineTagProperties {
     /// Redirect output to another window.
@@ -1,3 +1,6 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use super::mode::Mode;
 use super::tag::{LineTag, LineTagProperties};
 use crate::CaseFoldMap;
@@ -7,12 +10,14 @@ use crate::{Error, ErrorKind};
 
 const OFFSET: usize = Mode::USER_DEFINED_MIN.0 as usize;
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub(crate) struct Line {
     pub element: String,
     pub properties: LineTagProperties,
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) struct LineTags {
     base: Vec<Line>,
@@ -75,6 +80,28 @@ impl LineTags {
         el.properties.enable = true;
     }
 
+    /// Rebuilds definitions for line tags, in order. Line tags that were never modified are
+    /// skipped, except for the last one, so that replaying the definitions allocates the same
+    /// number of line tags.
+    pub fn definitions(&self) -> impl Iterator<Item = LineTagDefinition<'_>> {
+        let last = self.base.len().saturating_sub(1);
+        (Mode::USER_DEFINED_MIN.0..)
+            .zip(&self.base)
+            .enumerate()
+            .filter(move |(i, (_, line))| *i == last || **line != Line::default())
+            .map(|(_, (index, line))| {
+                let properties = &line.properties;
+                LineTagDefinition {
+                    index: Mode(index),
+                    window: properties.window.as_deref(),
+                    fore: properties.fore,
+                    back: properties.back,
+                    gag: properties.gag.then_some(true),
+                    enable: Some(properties.enable),
+                }
+            })
+    }
+
     pub fn update(&mut self, update: LineTagDefinition) -> crate::Result<()> {
         fn create_error(update: &LineTagDefinition) -> Error {
             Error::new(update.index.0.to_string(), ErrorKind::IllegalLineTag)
@@ -41,7 +41,7 @@ impl<'a> AttributeListDefinition<'a> {
                 ErrorKind::IncompleteElement,
             ));
         }
-        validate(name, ErrorKind::InvalidElementName)?;
+        validate(name, ErrorKind::InvalidElementName).map_err(|e| e.within(source, name))?;
         Ok(Self {
             name,
             attributes: Self::unquote(attributes),
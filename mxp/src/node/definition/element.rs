@@ -52,18 +52,19 @@ impl<'a> ElementDefinition<'a> {
                 ErrorKind::IncompleteElement,
             ));
         }
-        validate(name, ErrorKind::InvalidElementName)?;
+        validate(name, ErrorKind::InvalidElementName).map_err(|e| e.within(source, name))?;
 
-        let args = Arguments::parse(args)?;
+        let args = Arguments::parse(args).map_err(|e| e.within(source, args))?;
         let mut scan = args.scan().with_keywords();
 
         let items = match scan.get_next()? {
-            Some(&arg) => ElementItem::parse_all(arg)?,
+            Some(&arg) => ElementItem::parse_all(arg).map_err(|e| e.within(source, arg))?,
             None => Vec::new(),
         };
 
         let attributes = match scan.get_named("att")? {
-            Some(&atts) => ArgumentParser::new(atts).try_into()?,
+            Some(&atts) => AttributeList::try_from(ArgumentParser::new(atts))
+                .map_err(|e| e.within(source, atts))?,
             None => AttributeList::default(),
         };
 
@@ -71,14 +72,17 @@ impl<'a> ElementDefinition<'a> {
             Some(&tag) => match tag.parse() {
                 Ok(tag) if Mode(tag).is_user_defined() => Some(Mode(tag)),
                 _ => {
-                    return Err(Error::new(tag, ErrorKind::IllegalLineTagInDefinition));
+                    let error = Error::new(tag, ErrorKind::IllegalLineTagInDefinition);
+                    return Err(error.within(source, tag));
                 }
             },
             None => None,
         };
 
         let flag = match scan.get_named("flag")? {
-            Some(&flag) => Some(ElementFlag::parse(flag)?),
+            Some(&flag) => {
+                Some(ElementFlag::parse(flag).map_err(|e| Error::from(e).within(source, flag))?)
+            }
             None => None,
         };
 
@@ -65,8 +65,8 @@ impl<'a> EntityDefinition<'a> {
                 ErrorKind::IncompleteElement,
             ));
         }
-        validate(name, ErrorKind::InvalidEntityName)?;
-        let args = Arguments::parse(args)?;
+        validate(name, ErrorKind::InvalidEntityName).map_err(|e| e.within(source, name))?;
+        let args = Arguments::parse(args).map_err(|e| e.within(source, args))?;
         let mut scanner = args.scan().with_keywords();
         let Some(value) = scanner.get_next()? else {
             return Err(Error::new(source, ErrorKind::EmptyElementInDefinition));
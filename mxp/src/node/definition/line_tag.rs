@@ -75,13 +75,25 @@ impl<'a> LineTagDefinition<'a> {
     pub(super) fn parse(source: &'a str) -> crate::Result<Self> {
         let args = Arguments::parse(source)?;
         let mut scanner = args.scan().with_keywords();
-        let index = Mode(scanner.get_next()?.expect_number()?.expect_some("Tag")?);
+        let index_arg = scanner.get_next()?;
+        let locate = |e: Error, arg: Option<&&str>| match arg {
+            Some(arg) => e.within(source, arg),
+            None => e,
+        };
+        let index = index_arg
+            .expect_number()
+            .map_err(|e| locate(e, index_arg))?
+            .expect_some("Tag")?;
+        let index = Mode(index);
         if !index.is_user_defined() {
-            return Err(Error::new(index.to_string(), ErrorKind::IllegalLineTag));
+            let error = Error::new(index.to_string(), ErrorKind::IllegalLineTag);
+            return Err(locate(error, index_arg));
         }
         let window = scanner.get_named("windowname")?.copied();
-        let fore = scanner.get_named("fore")?.expect_color()?;
-        let back = scanner.get_named("back")?.expect_color()?;
+        let fore_arg = scanner.get_named("fore")?;
+        let fore = fore_arg.expect_color().map_err(|e| locate(e, fore_arg))?;
+        let back_arg = scanner.get_named("back")?;
+        let back = back_arg.expect_color().map_err(|e| locate(e, back_arg))?;
         let keywords = scanner.into_keywords()?;
         let gag = if keywords.contains(LineTagKeyword::Gag) {
             Some(true)
@@ -83,15 +83,14 @@ impl<'a> Definition<'a> {
         if name.is_empty() {
             return Err(Error::new("empty definition", ErrorKind::IncompleteElement));
         }
-        let kind = name.parse()?;
-        Ok(match kind {
-            DefinitionKind::AttributeList => {
-                Self::AttributeList(AttributeListDefinition::parse(rest)?)
-            }
-            DefinitionKind::Element => Self::Element(ElementDefinition::parse(rest)?),
-            DefinitionKind::Entity => Self::Entity(EntityDefinition::parse(rest)?),
-            DefinitionKind::LineTag => Self::LineTag(LineTagDefinition::parse(rest)?),
-        })
+        let kind = name.parse().map_err(|e: Error| e.within(source, name))?;
+        match kind {
+            DefinitionKind::AttributeList => AttributeListDefinition::parse(rest).map(Self::from),
+            DefinitionKind::Element => ElementDefinition::parse(rest).map(Self::from),
+            DefinitionKind::Entity => EntityDefinition::parse(rest).map(Self::from),
+            DefinitionKind::LineTag => LineTagDefinition::parse(rest).map(Self::from),
+        }
+        .map_err(|e| e.within(source, rest))
     }
 }
 
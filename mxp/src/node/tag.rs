@@ -4,7 +4,7 @@ use super::definition::Definition;
 use super::error::TryFromNodeError;
 use crate::arguments::Arguments;
 use crate::parse::{split_name, strip_terminating_slash, validate};
-use crate::{Error, ErrorKind};
+use crate::{Error, ErrorKind, ParseOptions};
 
 /// The three types of MXP tag elements sent by the server.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -45,17 +45,75 @@ impl<'a> Tag<'a> {
     ///
     /// Important note: this function expects `source` to omit the starting `<` and ending `>`.
     ///
+    /// If parsing fails, the [span](Error::span) of the returned error is located within `source`.
+    ///
     /// [`Mode::is_open`]: crate::Mode::is_open
     pub fn parse(source: &'a str, secure: bool) -> crate::Result<Self> {
-        let source = source.trim_ascii();
+        Self::parse_with(source, secure, ParseOptions::STRICT, &mut Vec::new())
+    }
+
+    /// Parses an element from data sent by the server, applying the recoveries enabled in
+    /// `options`. Each error that is recovered from is appended to `warnings`, with its
+    /// [span](Error::span) located within `source`.
+    ///
+    /// Definitions are always parsed strictly.
+    ///
+    /// See [`Tag::parse`] for more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mxp::node::{Tag, TagClose};
+    /// use mxp::{ErrorKind, ParseOptions};
+    ///
+    /// let source = "/send href=go";
+    /// assert!(Tag::parse(source, true).is_err());
+    ///
+    /// let mut warnings = Vec::new();
+    /// let tag = Tag::parse_with(source, true, ParseOptions::LENIENT, &mut warnings).unwrap();
+    /// assert_eq!(tag, Tag::Close(TagClose { name: "send" }));
+    /// assert_eq!(warnings[0].kind(), ErrorKind::ArgumentsToClosingTag);
+    /// assert_eq!(&source[warnings[0].span().unwrap()], "href=go");
+    /// ```
+    pub fn parse_with(
+        source: &'a str,
+        secure: bool,
+        options: ParseOptions,
+        warnings: &mut Vec<Error>,
+    ) -> crate::Result<Self> {
+        let trimmed = source.trim_ascii();
+        let start = warnings.len();
+        let tag = Self::parse_trimmed(trimmed, secure, options, warnings)
+            .map_err(|e| e.within(source, trimmed))?;
+        for warning in &mut warnings[start..] {
+            warning.relocate(source, trimmed);
+        }
+        Ok(tag)
+    }
 
+    fn parse_trimmed(
+        source: &'a str,
+        secure: bool,
+        options: ParseOptions,
+        warnings: &mut Vec<Error>,
+    ) -> crate::Result<Self> {
         match source.split_at_checked(1) {
             None if source.is_empty() => Err(Error::braced("", ErrorKind::EmptyElement)),
             Some(("!" | "/", "")) => Err(Error::braced(source, ErrorKind::IncompleteElement)),
             Some(("!", _)) if !secure => Err(Error::braced(source, ErrorKind::UnsecuredDefinition)),
-            Some(("!", body)) => Ok(Self::Definition(Definition::parse(body)?)),
-            Some(("/", body)) => Ok(Self::Close(TagClose::parse(body)?)),
-            _ => Ok(Self::Open(TagOpen::parse(source)?)),
+            Some(("!", body)) => Definition::parse(body)
+                .map(Self::Definition)
+                .map_err(|e| e.within(source, body)),
+            Some(("/", body)) => {
+                let start = warnings.len();
+                let tag =
+                    TagClose::parse(body, options, warnings).map_err(|e| e.within(source, body))?;
+                for warning in &mut warnings[start..] {
+                    warning.relocate(source, body);
+                }
+                Ok(Self::Close(tag))
+            }
+            _ => Ok(Self::Open(TagOpen::parse(source, options, warnings)?)),
         }
     }
 }
@@ -75,15 +133,23 @@ impl fmt::Display for TagClose<'_> {
 }
 
 impl<'a> TagClose<'a> {
-    fn parse(source: &'a str) -> crate::Result<Self> {
+    fn parse(
+        source: &'a str,
+        options: ParseOptions,
+        warnings: &mut Vec<Error>,
+    ) -> crate::Result<Self> {
         let (name, args) = split_name(source);
         if name.is_empty() {
             return Err(Error::new("</>", ErrorKind::IncompleteElement));
         }
-        validate(name, ErrorKind::InvalidElementName)?;
+        validate(name, ErrorKind::InvalidElementName).map_err(|e| e.within(source, name))?;
         let args = args.trim_ascii();
         if !args.is_empty() {
-            return Err(Error::new(args, ErrorKind::ArgumentsToClosingTag));
+            let error = Error::new(args, ErrorKind::ArgumentsToClosingTag).within(source, args);
+            if !options.recovers(error.kind()) {
+                return Err(error);
+            }
+            warnings.push(error);
         }
         Ok(Self { name })
     }
@@ -116,16 +182,26 @@ impl<S: AsRef<str>> fmt::Display for TagOpen<'_, S> {
 }
 
 impl<'a> TagOpen<'a> {
-    fn parse(source: &'a str) -> crate::Result<Self> {
+    fn parse(
+        source: &'a str,
+        options: ParseOptions,
+        warnings: &mut Vec<Error>,
+    ) -> crate::Result<Self> {
         let (name, body) = split_name(source);
         if name.is_empty() {
             return Err(Error::new("", ErrorKind::EmptyElement));
         }
-        validate(name, ErrorKind::InvalidElementName)?;
+        validate(name, ErrorKind::InvalidElementName).map_err(|e| e.within(source, name))?;
         let (args, empty) = strip_terminating_slash(body);
+        let start = warnings.len();
+        let arguments =
+            Arguments::parse_with(args, options, warnings).map_err(|e| e.within(source, args))?;
+        for warning in &mut warnings[start..] {
+            warning.relocate(source, args);
+        }
         Ok(Self {
             name,
-            arguments: Arguments::parse(args)?,
+            arguments,
             empty,
         })
     }
@@ -214,4 +290,73 @@ mod tests {
         };
         assert_eq!(tag.to_string(), "<custom muffled col=red>");
     }
+
+    fn error_target(source: &str) -> (ErrorKind, &str) {
+        let error = Tag::parse(source, true).unwrap_err();
+        (error.kind(), &source[error.span().unwrap()])
+    }
+
+    #[test]
+    fn error_spans() {
+        assert_eq!(
+            error_target(" /b c "),
+            (ErrorKind::ArgumentsToClosingTag, "c")
+        );
+        assert_eq!(
+            error_target("color 1=red"),
+            (ErrorKind::InvalidArgumentName, "1")
+        );
+        assert_eq!(
+            error_target("!EL custom '<b><unknown x>'"),
+            (ErrorKind::UnknownElementInDefinition, "unknown")
+        );
+        assert_eq!(
+            error_target("!EL custom '<b><color fore=>'"),
+            (ErrorKind::EmptyArgument, "fore=")
+        );
+        assert_eq!(
+            error_target("!EL custom '<b> <i>'"),
+            (ErrorKind::NoTagInDefinition, " ")
+        );
+        assert_eq!(
+            error_target("!EL custom '<b>' ATT='x y x'"),
+            (ErrorKind::DuplicateAttributeInAttlist, "x")
+        );
+        assert_eq!(
+            error_target("!EL custom '<b>' TAG=5"),
+            (ErrorKind::IllegalLineTagInDefinition, "5")
+        );
+        assert_eq!(
+            error_target("!TAG 25 FORE=octarine"),
+            (ErrorKind::UnknownColor, "octarine")
+        );
+        assert_eq!(
+            error_target("!FOO bar"),
+            (ErrorKind::InvalidDefinition, "FOO")
+        );
+    }
+
+    fn warning_targets(source: &str) -> Vec<(ErrorKind, &str)> {
+        let mut warnings = Vec::new();
+        Tag::parse_with(source, true, ParseOptions::LENIENT, &mut warnings).unwrap();
+        warnings
+            .iter()
+            .map(|warning| (warning.kind(), &source[warning.span().unwrap()]))
+            .collect()
+    }
+
+    #[test]
+    fn warning_spans() {
+        assert_eq!(
+            warning_targets(" send href=go #1=x hint= "),
+            [
+                (ErrorKind::InvalidArgumentName, "#1"),
+                (ErrorKind::EmptyArgument, "hint="),
+            ]
+        );
+        assert_eq!(
+            warning_targets(" /p x "),
+            [(ErrorKind::ArgumentsToClosingTag, "x")]
+        );
+    }
 }
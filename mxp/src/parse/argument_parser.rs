@@ -1,23 +1,30 @@
 use std::iter::FusedIterator;
 use std::slice;
 
-use crate::parse::count_bytes;
+use crate::parse::{count_bytes, span_of};
 use crate::{Error, ErrorKind};
 
 /// Iterator over the word units of an MXP string, converted into named or unnamed arguments.
 #[must_use = "iterators are lazy and do nothing unless consumed"]
 #[derive(Clone)]
 pub(crate) struct ArgumentParser<'a> {
+    source: &'a str,
     iter: slice::Iter<'a, u8>,
 }
 
 impl<'a> ArgumentParser<'a> {
     pub fn new(source: &'a str) -> Self {
         Self {
+            source,
             iter: source.as_bytes().iter(),
         }
     }
 
+    /// Returns the full string being parsed.
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
     fn next_arg(&mut self, positional: bool) -> Option<(&'a [u8], bool)> {
         let mut slice = self.iter.as_slice();
         if positional {
@@ -57,14 +64,17 @@ impl<'a> Iterator for ArgumentParser<'a> {
         // SAFETY: Valid UTF-8.
         let arg1 = unsafe { str::from_utf8_unchecked(slice1) };
         if matches!(slice1, [b'=', ..]) {
-            return Some(Err(Error::new(arg1, ErrorKind::MissingArgumentName)));
+            let error = Error::new(arg1, ErrorKind::MissingArgumentName);
+            return Some(Err(error.within(self.source, arg1)));
         }
         if !is_named {
             return Some(Ok((arg1, None)));
         }
         let Some((slice2, _)) = self.next_arg(false) else {
             let target = format!("{arg1}=");
-            return Some(Err(Error::new(target, ErrorKind::EmptyArgument)));
+            let span = span_of(self.source, arg1);
+            let error = Error::new(target, ErrorKind::EmptyArgument);
+            return Some(Err(error.with_span(span.start..span.end + 1)));
         };
         // SAFETY: Valid UTF-8.
         let arg2 = unsafe { str::from_utf8_unchecked(slice2) };
@@ -105,4 +115,14 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn error_spans() {
+        let spans = ["a =b", "a b= c", "a b c="].map(|source| {
+            ArgumentParser::new(source)
+                .find_map(Result::err)
+                .and_then(|e| e.span())
+        });
+        assert_eq!(spans, [Some(2..4), Some(2..4), Some(4..6)]);
+    }
 }
@@ -1,6 +1,8 @@
 use std::borrow::Cow;
+use std::cell::RefCell;
 use std::{slice, str, vec};
 
+use super::options::ParseOptions;
 use super::validation::is_valid;
 use crate::CaseFoldMap;
 use crate::arguments::ArgumentScanner;
@@ -38,24 +40,49 @@ pub trait Decoder {
         }
     }
 
+    /// Returns the options that determine which errors [`decode_string`](Self::decode_string)
+    /// recovers from. By default, decoding is strict.
+    fn parse_options(&self) -> ParseOptions {
+        ParseOptions::STRICT
+    }
+
+    /// Receives an error that was recovered from as a result of
+    /// [`parse_options`](Self::parse_options). By default, the error is discarded.
+    fn warn(&self, warning: Error) {
+        let _ = warning;
+    }
+
     /// Decodes an entire string, replacing all entities inside it with their values resolved by
     /// [`decode_entity`](Self::decode_entity). If the string does not contain any entities, it is
     /// returned unchanged as a borrowed string slice. Otherwise, an owned string containing the
     /// replacements is returned.
     fn decode_string<'a>(&self, mut s: &'a str) -> crate::Result<Cow<'a, str>> {
+        let options = self.parse_options();
         let mut res = String::new();
         while let Some((before, rest)) = s.split_once('&') {
             if !before.is_empty() {
                 res.push_str(before);
             }
-            let Some((entity, after)) = rest.split_once(';') else {
-                return Err(Error::new(
+            let decoded = match rest.split_once(';') {
+                Some((entity, after)) => self.decode_entity(entity).map(|entity| (entity, after)),
+                None => Err(Error::new(
                     format!("&{rest}"),
                     ErrorKind::NoClosingSemicolon,
-                ));
+                )),
             };
-            self.decode_entity(entity)?.push_to(&mut res);
-            s = after;
+            match decoded {
+                Ok((entity, after)) => {
+                    entity.push_to(&mut res);
+                    s = after;
+                }
+                Err(error) if options.recovers(error.kind()) => {
+                    // Keep the '&' as text and resume scanning after it.
+                    self.warn(error);
+                    res.push('&');
+                    s = rest;
+                }
+                Err(error) => return Err(error),
+            }
         }
         if res.is_empty() {
             return Ok(Cow::Borrowed(s));
@@ -75,6 +102,77 @@ impl<D: Decoder> Decoder for &D {
     fn decode_entity(&self, entity: &str) -> crate::Result<DecodedEntity<'_>> {
         D::decode_entity(self, entity)
     }
+
+    fn parse_options(&self) -> ParseOptions {
+        D::parse_options(self)
+    }
+
+    fn warn(&self, warning: Error) {
+        D::warn(self, warning);
+    }
+}
+
+/// [`Decoder`] that applies the recoveries enabled by a set of [`ParseOptions`], collecting the
+/// errors it recovers from as warnings.
+///
+/// # Examples
+///
+/// ```
+/// use mxp::{Decoder, ErrorKind, LenientDecoder, ParseOptions};
+///
+/// let state = mxp::State::with_globals();
+/// assert!(state.decode_string("fish & chips &lt;").is_err());
+///
+/// let decoder = LenientDecoder::new(&state, ParseOptions::LENIENT);
+/// assert_eq!(decoder.decode_string("fish & chips &lt;").unwrap(), "fish & chips <");
+/// let warnings = decoder.take_warnings();
+/// assert_eq!(warnings.len(), 1);
+/// assert_eq!(warnings[0].kind(), ErrorKind::InvalidEntityName);
+/// ```
+#[derive(Debug)]
+pub struct LenientDecoder<D> {
+    decoder: D,
+    options: ParseOptions,
+    warnings: RefCell<Vec<Error>>,
+}
+
+impl<D: Decoder> LenientDecoder<D> {
+    /// Wraps a decoder in order to apply the specified options.
+    pub const fn new(decoder: D, options: ParseOptions) -> Self {
+        Self {
+            decoder,
+            options,
+            warnings: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Removes and returns the warnings collected so far.
+    pub fn take_warnings(&self) -> Vec<Error> {
+        self.warnings.take()
+    }
+
+    /// Unwraps the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.decoder
+    }
+}
+
+impl<D: Decoder> Decoder for LenientDecoder<D> {
+    fn get_entity(&self, name: &str) -> Option<&str> {
+        self.decoder.get_entity(name)
+    }
+
+    fn decode_entity(&self, name: &str) -> crate::Result<DecodedEntity<'_>> {
+        self.decoder.decode_entity(name)
+    }
+
+    fn parse_options(&self) -> ParseOptions {
+        self.options
+    }
+
+    fn warn(&self, warning: Error) {
+        self.warnings.borrow_mut().push(warning);
+    }
 }
 
 /// Fallback `Decoder` that only looks up global entities (with [`Entity::global`]).
@@ -0,0 +1,120 @@
+use std::fmt;
+use std::ops::Range;
+
+use super::error::Error;
+
+/// An [`Error`] paired with the source it was parsed from, for reporting its position.
+///
+/// The `Display` implementation renders the line containing the error, with carets underlining
+/// the error's [span](Error::span). If the error has no span, only the error itself is rendered.
+///
+/// This struct is created by [`Error::diagnostic`].
+///
+/// # Examples
+///
+/// ```
+/// use mxp::node::Tag;
+///
+/// let source = "SEND href=go hint=";
+/// let error = Tag::parse(source, false).unwrap_err();
+/// let diagnostic = error.diagnostic(source);
+/// assert_eq!(diagnostic.line(), Some(1));
+/// assert_eq!(diagnostic.column(), Some(14));
+/// assert_eq!(
+///     diagnostic.to_string(),
+///     "empty argument: \"hint=\"\n  |\n1 | SEND href=go hint=\n  |              ^^^^^"
+/// );
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Diagnostic<'a> {
+    error: &'a Error,
+    source: &'a str,
+}
+
+impl<'a> Diagnostic<'a> {
+    pub(super) fn new(error: &'a Error, source: &'a str) -> Self {
+        Self { error, source }
+    }
+
+    /// Returns the error being reported.
+    pub fn error(&self) -> &'a Error {
+        self.error
+    }
+
+    /// Returns the line number (starting at 1) where the error's span begins, if the error has
+    /// a span within the source.
+    pub fn line(&self) -> Option<usize> {
+        let before = self.before()?;
+        Some(before.bytes().filter(|&c| c == b'\n').count() + 1)
+    }
+
+    /// Returns the column number (starting at 1, and counted in characters) where the error's
+    /// span begins, if the error has a span within the source.
+    pub fn column(&self) -> Option<usize> {
+        let before = self.before()?;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Some(before[line_start..].chars().count() + 1)
+    }
+
+    fn span(&self) -> Option<Range<usize>> {
+        let span = self.error.span()?;
+        self.source.get(span.clone())?;
+        Some(span)
+    }
+
+    fn before(&self) -> Option<&'a str> {
+        Some(&self.source[..self.span()?.start])
+    }
+}
+
+impl fmt::Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Self { error, source } = self;
+        let (Some(span), Some(line), Some(column)) = (self.span(), self.line(), self.column())
+        else {
+            return error.fmt(f);
+        };
+        let line_start = source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[span.start..]
+            .find('\n')
+            .map_or(source.len(), |i| span.start + i);
+        let text = &source[line_start..line_end];
+        let underlined = source[span.start..span.end.min(line_end)].chars().count();
+        let gutter = line.to_string().len();
+        write!(
+            f,
+            "{error}\n{:gutter$} |\n{line} | {text}\n{:gutter$} | {:indent$}{:^<carets$}",
+            "",
+            "",
+            "",
+            "",
+            indent = column - 1,
+            carets = underlined.max(1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ErrorKind;
+
+    #[test]
+    fn multiline() {
+        let source = "!EL custom '<send \"a\né\"><unknown>'";
+        let error = crate::node::Tag::parse(source, true).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnknownElementInDefinition);
+        let diagnostic = error.diagnostic(source);
+        assert_eq!(diagnostic.line(), Some(2));
+        assert_eq!(diagnostic.column(), Some(5));
+        assert_eq!(
+            diagnostic.to_string(),
+            "unrecognized element in element definition: \"unknown\"\n  |\n2 | é\"><unknown>'\n  |     ^^^^^^^"
+        );
+    }
+
+    #[test]
+    fn no_span() {
+        let error = crate::Error::new("x", ErrorKind::UnknownEntity);
+        assert_eq!(error.diagnostic("x").to_string(), error.to_string());
+    }
+}
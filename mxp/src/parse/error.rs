@@ -1,7 +1,10 @@
 use std::fmt::{self, Write};
 use std::marker::PhantomData;
+use std::ops::Range;
 use std::string::FromUtf8Error;
 
+use super::diagnostic::Diagnostic;
+use super::validation::span_of;
 use crate::ErrorKind;
 
 /// Error encountered during parsing and processing of MXP data.
@@ -9,11 +12,12 @@ use crate::ErrorKind;
 pub struct Error {
     kind: ErrorKind,
     target: String,
+    span: Option<Range<usize>>,
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let Self { kind, target } = self;
+        let Self { kind, target, .. } = self;
         if target.is_empty() {
             write!(f, "{kind}")
         } else {
@@ -34,6 +38,7 @@ impl Error {
         Self {
             kind: error,
             target,
+            span: None,
         }
     }
 
@@ -41,6 +46,7 @@ impl Error {
         Self {
             kind: error,
             target: format!("<{target}>"),
+            span: None,
         }
     }
 
@@ -49,6 +55,57 @@ impl Error {
         self.kind
     }
 
+    /// Returns the byte range of the error's target within the parsed source, if known.
+    ///
+    /// Errors returned by [`Tag::parse`] are located relative to the `source` argument.
+    ///
+    /// [`Tag::parse`]: crate::node::Tag::parse
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mxp::node::Tag;
+    ///
+    /// let source = "SEND href=go hint=";
+    /// let error = Tag::parse(source, false).unwrap_err();
+    /// assert_eq!(error.kind(), mxp::ErrorKind::EmptyArgument);
+    /// assert_eq!(error.span(), Some(13..18));
+    /// assert_eq!(&source[13..18], "hint=");
+    /// ```
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.span.clone()
+    }
+
+    /// Sets the byte range of the error's target within the parsed source.
+    #[must_use = "returns self"]
+    pub fn with_span(mut self, span: Range<usize>) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Renders the error against the source it was parsed from, underlining its span.
+    /// See [`Diagnostic`] for more.
+    pub fn diagnostic<'a>(&'a self, source: &'a str) -> Diagnostic<'a> {
+        Diagnostic::new(self, source)
+    }
+
+    /// Rebases the error's span from `inner` to `source`, where `inner` is a substring of `source`.
+    /// If the error does not have a span, it is located at `inner`.
+    #[must_use = "returns self"]
+    pub(crate) fn within(mut self, source: &str, inner: &str) -> Self {
+        self.relocate(source, inner);
+        self
+    }
+
+    /// In-place version of [`Error::within`].
+    pub(crate) fn relocate(&mut self, source: &str, inner: &str) {
+        let range = span_of(source, inner);
+        self.span = Some(match self.span.take() {
+            Some(span) => span.start + range.start..span.end + range.start,
+            None => range,
+        });
+    }
+
     /// Appends additional context to the error message.
     #[must_use = "returns self"]
     pub fn with_context(mut self, context: fmt::Arguments) -> Self {
@@ -83,6 +83,11 @@ pub enum ErrorKind {
     /// Example: `<!ENTITY lt lessthan>`
     CannotRedefineGlobalEntity,
 
+    /// A custom tag was registered with the name of a standard atomic tag.
+    ///
+    /// Example: registering a [`CustomTag`](crate::element::CustomTag) named `"SEND"`
+    CannotRedefineAtomicTag,
+
     /// Definition tag (`<!...>`) does not begin begin with a recognized prefix
     /// (`!ATTLIST`, `!AT`, `!ELEMENT`, `!EL`, `!ENTITY`, `!EN`, or `!TAG`).
     ///
@@ -200,6 +205,41 @@ pub enum ErrorKind {
     /// [148 CSS colors]: https://www.w3.org/wiki/CSS/Properties/color/keywords
     UnknownColor,
 
+    /// Element definition would exceed [`Limits::max_elements`].
+    ///
+    /// Example: `<!ELEMENT foo '<b>'>`, when the maximum number of elements has been reached
+    ///
+    /// [`Limits::max_elements`]: crate::Limits::max_elements
+    TooManyElements,
+
+    /// Entity definition would exceed [`Limits::max_entities`].
+    ///
+    /// Example: `<!ENTITY foo bar>`, when the maximum number of entities has been reached
+    ///
+    /// [`Limits::max_entities`]: crate::Limits::max_entities
+    TooManyEntities,
+
+    /// Entity value would exceed [`Limits::max_entity_len`].
+    ///
+    /// Example: `<!ENTITY foo '...'>`, with a value longer than the maximum length
+    ///
+    /// [`Limits::max_entity_len`]: crate::Limits::max_entity_len
+    EntityTooLong,
+
+    /// Element definition or attribute list would exceed [`Limits::max_attributes`].
+    ///
+    /// Example: `<!ATTLIST foo 'a b c ...'>`, with more attributes than the maximum
+    ///
+    /// [`Limits::max_attributes`]: crate::Limits::max_attributes
+    TooManyAttributes,
+
+    /// Opening a tag would exceed [`Limits::max_depth`]. This is for clients to use.
+    ///
+    /// Example: `<b><b><b>...`, when the maximum number of tags are open
+    ///
+    /// [`Limits::max_depth`]: crate::Limits::max_depth
+    TagsNestedTooDeeply,
+
     /// Bytes are not valid UTF-8. This is a convenience error kind so that clients can handle UTF-8
     /// parsing while using `mxp::Result`. It is returned by [`mxp::validate_utf8`] on error.
     ///
@@ -249,6 +289,7 @@ impl fmt::Display for ErrorKind {
                 "received unexpected symbol after setting SECURE_ONCE mode"
             }
             Self::CannotRedefineGlobalEntity => "cannot redefine global entity",
+            Self::CannotRedefineAtomicTag => "cannot redefine standard tag",
             Self::InvalidDefinition => "invalid definition type",
             Self::IllegalLineTag => "mode out of bounds for user-defined line tags",
             Self::DuplicateAttributeInAttlist => "duplicate attribute name in attribute list",
@@ -272,6 +313,11 @@ impl fmt::Display for ErrorKind {
             Self::InvalidNumber => "invalid number",
             Self::UnexpectedArgument => "found unexpected argument",
             Self::UnknownColor => "unrecognized color",
+            Self::TooManyElements => "too many elements defined",
+            Self::TooManyEntities => "too many entities defined",
+            Self::EntityTooLong => "entity value too long",
+            Self::TooManyAttributes => "too many attributes for element",
+            Self::TagsNestedTooDeeply => "too many nested tags",
             Self::InvalidUtf8 => "invalid UTF-8",
             Self::UnterminatedComment => "reached end of line without terminating comment",
             Self::UnterminatedElement => "reached end of line without terminating element",
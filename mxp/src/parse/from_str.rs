@@ -87,15 +87,14 @@ pub(crate) fn parse_element<'a, T>(source: &'a str) -> Result<T, FromStrError>
 where
     T: HasActionKind + FromArgs<'a, Cow<'a, str>>,
 {
-    let source = cleanup_source(source)?;
-    let (name, args) = split_name(source);
+    let (name, args) = split_name(cleanup_source(source)?);
     if name.is_empty() {
         return Err(crate::Error::new("", ErrorKind::EmptyElement).into());
     }
-    validate(name, ErrorKind::InvalidElementName)?;
+    validate(name, ErrorKind::InvalidElementName).map_err(|e| e.within(source, name))?;
     AtomicTag::well_known(name)
         .filter(|tag| tag.action == T::ACTION_KIND)
         .ok_or_else(|| FromStrError::UnexpectedTag(name.to_owned()))?;
-    let args = Arguments::parse(args)?;
+    let args = Arguments::parse(args).map_err(|e| e.within(source, args))?;
     Ok(args.into_scan().with_decoder(()).parse()?)
 }
@@ -2,6 +2,9 @@ mod error;
 pub(crate) use error::StringVariant;
 pub use error::{Error, UnrecognizedVariant};
 
+mod diagnostic;
+pub use diagnostic::Diagnostic;
+
 mod error_kind;
 pub use error_kind::ErrorKind;
 
@@ -9,16 +12,19 @@ mod from_str;
 pub use from_str::FromStrError;
 pub(crate) use from_str::{cleanup_source, parse_element};
 
+mod options;
+pub use options::ParseOptions;
+
 mod into_owned_string;
 pub use into_owned_string::IntoOwnedString;
 
 mod decoder;
-pub use decoder::Decoder;
+pub use decoder::{Decoder, LenientDecoder};
 pub(crate) use decoder::{OwnedScan, Scan};
 
 mod validation;
 pub use validation::{is_valid, validate, validate_utf8};
-pub(crate) use validation::{split_name, strip_terminating_slash};
+pub(crate) use validation::{span_of, split_name, strip_terminating_slash};
 
 mod argument_parser;
 pub(crate) use argument_parser::ArgumentParser;
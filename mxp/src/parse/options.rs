@@ -0,0 +1,178 @@
+use std::fmt;
+
+use crate::ErrorKind;
+
+/// Error kinds that [`ParseOptions`] can recover from.
+const RECOVERABLE: [ErrorKind; 9] = [
+    ErrorKind::InvalidArgumentName,
+    ErrorKind::EmptyArgument,
+    ErrorKind::ArgumentsToClosingTag,
+    ErrorKind::NoClosingSemicolon,
+    ErrorKind::InvalidEntityName,
+    ErrorKind::UnknownEntity,
+    ErrorKind::UnterminatedEntity,
+    ErrorKind::UnmatchedCloseTag,
+    ErrorKind::TagOpenedInSecureMode,
+];
+
+// Each recoverable error kind is assigned a bit by its position in `RECOVERABLE`.
+const _: () = assert!(RECOVERABLE.len() <= u64::BITS as usize);
+
+/// Returns the bit assigned to an error kind, or 0 if the error kind is not recoverable.
+const fn bit(kind: ErrorKind) -> u64 {
+    let mut i = 0;
+    while i < RECOVERABLE.len() {
+        if RECOVERABLE[i] as u8 == kind as u8 {
+            return 1 << i;
+        }
+        i += 1;
+    }
+    0
+}
+
+/// Determines which parsing errors are downgraded into recoveries.
+///
+/// By default, parsing is strict: malformed input produces an error, and the tag or entity
+/// containing it is discarded. Older servers, written against the lenient parsers of zMUD and
+/// MUSHclient, routinely send MXP that the strict parser rejects. Enabling recovery for an
+/// [`ErrorKind`] makes the parser apply the same fix-up those clients do, and report the error as
+/// a warning instead.
+///
+/// | Error kind | Recovery |
+/// | --- | --- |
+/// | [`InvalidArgumentName`] | The argument is kept verbatim as a positional argument. |
+/// | [`EmptyArgument`] | The argument's value is an empty string. |
+/// | [`ArgumentsToClosingTag`] | The arguments are ignored. |
+/// | [`NoClosingSemicolon`] | The `&` is kept as literal text. |
+/// | [`InvalidEntityName`], [`UnknownEntity`] | The entity is kept as literal text. |
+/// | [`UnterminatedEntity`] | The entity is kept as literal text. |
+/// | [`UnmatchedCloseTag`] | The closing tag is ignored. |
+/// | [`TagOpenedInSecureMode`] | The tag is closed anyway. |
+///
+/// Recovery cannot be enabled for any other error kind.
+///
+/// Options are applied by [`Tag::parse_with`], [`Arguments::parse_with`], [`LenientDecoder`], and
+/// [`Tokenizer`]. The remaining recoveries are the responsibility of the client, which can check
+/// them with [`ParseOptions::recovers`].
+///
+/// [`InvalidArgumentName`]: ErrorKind::InvalidArgumentName
+/// [`EmptyArgument`]: ErrorKind::EmptyArgument
+/// [`ArgumentsToClosingTag`]: ErrorKind::ArgumentsToClosingTag
+/// [`NoClosingSemicolon`]: ErrorKind::NoClosingSemicolon
+/// [`InvalidEntityName`]: ErrorKind::InvalidEntityName
+/// [`UnknownEntity`]: ErrorKind::UnknownEntity
+/// [`UnterminatedEntity`]: ErrorKind::UnterminatedEntity
+/// [`UnmatchedCloseTag`]: ErrorKind::UnmatchedCloseTag
+/// [`TagOpenedInSecureMode`]: ErrorKind::TagOpenedInSecureMode
+/// [`Tag::parse_with`]: crate::node::Tag::parse_with
+/// [`Arguments::parse_with`]: crate::Arguments::parse_with
+/// [`LenientDecoder`]: crate::LenientDecoder
+/// [`Tokenizer`]: crate::Tokenizer
+///
+/// # Examples
+///
+/// ```
+/// use mxp::{ErrorKind, ParseOptions};
+///
+/// let options = ParseOptions::LENIENT.reject(ErrorKind::UnknownEntity);
+/// assert!(options.recovers(ErrorKind::EmptyArgument));
+/// assert!(!options.recovers(ErrorKind::UnknownEntity));
+/// assert!(!options.recovers(ErrorKind::UnknownElement));
+/// ```
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    recover: u64,
+}
+
+impl fmt::Debug for ParseOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entries(RECOVERABLE.iter().filter(|&&kind| self.recovers(kind)))
+            .finish()
+    }
+}
+
+impl ParseOptions {
+    /// Rejects all malformed input. This is the default.
+    pub const STRICT: Self = Self { recover: 0 };
+
+    /// Recovers from every error kind that can be recovered from.
+    pub const LENIENT: Self = {
+        let mut options = Self::STRICT;
+        let mut i = 0;
+        while i < RECOVERABLE.len() {
+            options = options.recover(RECOVERABLE[i]);
+            i += 1;
+        }
+        options
+    };
+
+    /// Constructs strict options. Equivalent to [`ParseOptions::STRICT`].
+    pub const fn new() -> Self {
+        Self::STRICT
+    }
+
+    /// Enables recovery for an error kind. Has no effect if recovery is not possible for the
+    /// error kind.
+    #[must_use = "function returns a new value"]
+    pub const fn recover(self, kind: ErrorKind) -> Self {
+        Self {
+            recover: self.recover | bit(kind),
+        }
+    }
+
+    /// Disables recovery for an error kind.
+    #[must_use = "function returns a new value"]
+    pub const fn reject(self, kind: ErrorKind) -> Self {
+        Self {
+            recover: self.recover & !bit(kind),
+        }
+    }
+
+    /// Returns `true` if errors of the specified kind should be recovered from.
+    pub const fn recovers(self, kind: ErrorKind) -> bool {
+        self.recover & bit(kind) != 0
+    }
+
+    /// Returns `true` if recovery can be enabled for the specified error kind.
+    pub const fn is_recoverable(kind: ErrorKind) -> bool {
+        bit(kind) != 0
+    }
+
+    /// Returns `true` if no recoveries are enabled.
+    pub const fn is_strict(self) -> bool {
+        self.recover == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lenient_recovers_all() {
+        for kind in RECOVERABLE {
+            assert!(ParseOptions::LENIENT.recovers(kind), "{kind:?}");
+            assert!(!ParseOptions::STRICT.recovers(kind), "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn unrecoverable() {
+        let options = ParseOptions::STRICT.recover(ErrorKind::UnknownElement);
+        assert!(options.is_strict());
+        assert!(!ParseOptions::LENIENT.recovers(ErrorKind::UnterminatedQuote));
+        assert_eq!(
+            ParseOptions::LENIENT.reject(ErrorKind::UnterminatedQuote),
+            ParseOptions::LENIENT
+        );
+    }
+
+    #[test]
+    fn fmt_debug() {
+        let options = ParseOptions::STRICT
+            .recover(ErrorKind::UnknownEntity)
+            .recover(ErrorKind::EmptyArgument);
+        assert_eq!(format!("{options:?}"), "{EmptyArgument, UnknownEntity}");
+    }
+}
@@ -1,5 +1,14 @@
+use std::ops::Range;
+
 use crate::{Error, ErrorKind};
 
+/// Returns the byte range of `target` within `source`. `target` must be a substring of `source`.
+pub(crate) fn span_of(source: &str, target: &str) -> Range<usize> {
+    let start = target.as_ptr().addr() - source.as_ptr().addr();
+    debug_assert!(start + target.len() <= source.len());
+    start..start + target.len()
+}
+
 pub(crate) fn split_name(s: &str) -> (&str, &str) {
     let s = s.trim_ascii_start();
     let Some(split_at) = s.as_bytes().iter().position(|&c| c == b' ') else {
@@ -1,7 +1,26 @@
-//! Types for generating client responses to MXP queries.
+//! Types for generating client responses to MXP queries, and for parsing them on the server side.
+
+use crate::arguments::Arguments;
+use crate::node::Tag;
+use crate::parse::cleanup_source;
+use crate::{Error, ErrorKind};
 
 mod support;
-pub use support::SupportResponse;
+pub use support::{SupportEntry, SupportResponse, Supports};
 
 mod version;
 pub use version::VersionResponse;
+
+/// Parses the arguments of a client response in the form of `\x1B[1z<{name} ...>\r\n`. The
+/// leading escape sequence and the trailing newline are optional.
+fn parse_response<'a>(source: &'a str, name: &str) -> crate::Result<Arguments<'a>> {
+    let inner = cleanup_source(source.strip_prefix("\x1B[1z").unwrap_or(source))?;
+    match Tag::parse(inner, true).map_err(|e| e.within(source, inner))? {
+        Tag::Open(tag) if tag.name.eq_ignore_ascii_case(name) => Ok(tag.arguments),
+        Tag::Open(tag) => {
+            let error = Error::new(tag.name, ErrorKind::UnknownElement);
+            Err(error.within(source, tag.name))
+        }
+        _ => Err(Error::braced(inner, ErrorKind::UnknownElement).within(source, inner)),
+    }
+}
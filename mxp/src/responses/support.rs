@@ -2,7 +2,8 @@ use std::fmt;
 
 use flagset::FlagSet;
 
-use crate::element::{ActionKind, AtomicTag};
+use crate::element::{ActionKind, AtomicTag, CustomTag};
+use crate::{Error, ErrorKind};
 
 /// Formats a [`<SUPPORT>`] response.
 ///
@@ -28,6 +29,7 @@ where
 {
     iter: I,
     supported: FlagSet<ActionKind>,
+    custom_tags: Vec<CustomTag>,
 }
 
 impl<I> SupportResponse<I>
@@ -38,7 +40,57 @@ where
     /// Constructs a response from an iterator of questions from the server and a set of supported
     /// actions.
     pub fn new(iter: I, supported: FlagSet<ActionKind>) -> Self {
-        Self { iter, supported }
+        Self {
+            iter,
+            supported,
+            custom_tags: Vec::new(),
+        }
+    }
+
+    /// Includes client-specific tags in the response, such as those listed by
+    /// [`State::custom_tags`](crate::State::custom_tags). Custom tags are always reported as
+    /// supported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mxp::element::CustomTag;
+    /// use mxp::responses::SupportResponse;
+    ///
+    /// const MAPPER: CustomTag = CustomTag {
+    ///     name: "MAPPER",
+    ///     args: &["room", "exits"],
+    ///     open: false,
+    ///     command: true,
+    ///     decode: CustomTag::decode_args,
+    /// };
+    ///
+    /// let response = SupportResponse::new(&["mapper.*", "image"], mxp::ActionKind::Bold.into())
+    ///     .with_custom_tags([&MAPPER]);
+    /// assert_eq!(
+    ///     response.to_string(),
+    ///     "\x1B[1z<SUPPORTS +mapper.room +mapper.exits -image>\r\n"
+    /// );
+    /// ```
+    #[must_use = "function returns a new response"]
+    pub fn with_custom_tags<'a, T>(mut self, tags: T) -> Self
+    where
+        T: IntoIterator<Item = &'a CustomTag>,
+    {
+        self.custom_tags.extend(tags.into_iter().copied());
+        self
+    }
+
+    fn supported_args(&self, name: &str) -> Option<&'static [&'static str]> {
+        match AtomicTag::well_known(name) {
+            Some(tag) if self.supported.contains(tag.action) => Some(tag.args),
+            Some(_) => None,
+            None => self
+                .custom_tags
+                .iter()
+                .find(|tag| tag.name.eq_ignore_ascii_case(name))
+                .map(|tag| tag.args),
+        }
     }
 
     fn write_supported(&self, f: &mut fmt::Formatter, query: &str) -> fmt::Result {
@@ -46,14 +98,16 @@ where
             Some((name, arg)) => (name, Some(arg)),
             None => (query, None),
         };
-        match AtomicTag::well_known(name) {
-            Some(tag) if self.supported.contains(tag.action) => match arg {
-                None => Self::write_can(f, name),
-                Some("*") => Self::write_can_args(f, name, tag),
-                Some(arg) if tag.supports(arg) => Self::write_can_arg(f, name, arg),
-                Some(arg) => Self::write_cant_arg(f, name, arg),
-            },
-            _ => Self::write_cant(f, name),
+        let Some(args) = self.supported_args(name) else {
+            return Self::write_cant(f, name);
+        };
+        match arg {
+            None => Self::write_can(f, name),
+            Some("*") => Self::write_can_args(f, name, args),
+            Some(arg) if args.iter().any(|a| a.eq_ignore_ascii_case(arg)) => {
+                Self::write_can_arg(f, name, arg)
+            }
+            Some(arg) => Self::write_cant_arg(f, name, arg),
         }
     }
 
@@ -61,9 +115,13 @@ where
         for tag in AtomicTag::supported() {
             if self.supported.contains(tag.action) {
                 Self::write_can(f, tag.name)?;
-                Self::write_can_args(f, tag.name, tag)?;
+                Self::write_can_args(f, tag.name, tag.args)?;
             }
         }
+        for tag in &self.custom_tags {
+            Self::write_can(f, tag.name)?;
+            Self::write_can_args(f, tag.name, tag.args)?;
+        }
         if !self.supported.contains(ActionKind::Font) && self.supported.contains(ActionKind::Color)
         {
             // Alternative `<font>` definition with only color arguments.
@@ -88,8 +146,8 @@ where
         write!(f, " -{tag}.{arg}")
     }
 
-    fn write_can_args(f: &mut fmt::Formatter, name: &str, tag: &AtomicTag) -> fmt::Result {
-        for arg in tag.args {
+    fn write_can_args(f: &mut fmt::Formatter, name: &str, args: &[&str]) -> fmt::Result {
+        for arg in args {
             write!(f, " +{name}.{arg}")?;
         }
         Ok(())
@@ -114,3 +172,214 @@ where
         write!(f, ">\r\n")
     }
 }
+
+/// Single item of a client's `<SUPPORTS>` response, such as `+color.fore` or `-image`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SupportEntry<'a> {
+    /// Tag name, such as `"color"`.
+    pub tag: &'a str,
+    /// Tag argument, such as `"fore"`, or `None` if the entry refers to the tag itself.
+    pub argument: Option<&'a str>,
+    /// `true` if the entry was prefixed with `+`, or `false` if it was prefixed with `-`.
+    pub supported: bool,
+}
+
+impl<'a> SupportEntry<'a> {
+    fn parse(source: &'a str) -> crate::Result<Self> {
+        let (supported, body) = match source.split_at_checked(1) {
+            Some(("+", body)) => (true, body),
+            Some(("-", body)) => (false, body),
+            _ => return Err(Error::new(source, ErrorKind::UnexpectedArgument)),
+        };
+        let (tag, argument) = match body.split_once('.') {
+            Some((tag, argument)) => (tag, Some(argument)),
+            None => (body, None),
+        };
+        Ok(Self {
+            tag,
+            argument,
+            supported,
+        })
+    }
+}
+
+impl fmt::Display for SupportEntry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Self {
+            tag,
+            argument,
+            supported,
+        } = self;
+        let sign = if *supported { '+' } else { '-' };
+        match argument {
+            Some(argument) => write!(f, "{sign}{tag}.{argument}"),
+            None => write!(f, "{sign}{tag}"),
+        }
+    }
+}
+
+/// Parsed representation of a client's response to a [`<SUPPORT>`] query, in the form of
+/// `<SUPPORTS +tag -tag +tag.arg -tag.arg ...>`. This is the server-side counterpart of
+/// [`SupportResponse`].
+///
+/// [`<SUPPORT>`]: https://www.zuggsoft.com/zmud/mxp.htm#Version%20Control
+///
+/// # Examples
+///
+/// ```
+/// use mxp::ActionKind;
+/// use mxp::responses::Supports;
+///
+/// let supports = Supports::parse("\x1B[1z<SUPPORTS +color.fore -image +send.expire>\r\n").unwrap();
+/// assert_eq!(supports.actions(), ActionKind::Color | ActionKind::Send);
+/// assert_eq!(supports.tag("image"), Some(false));
+/// assert_eq!(supports.argument("send", "expire"), Some(true));
+/// assert_eq!(supports.argument("send", "hint"), None);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Supports<'a> {
+    entries: Vec<SupportEntry<'a>>,
+}
+
+impl<'a> Supports<'a> {
+    /// Parses a client's `<SUPPORTS>` response. The leading `\x1B[1z` and trailing `\r\n` are
+    /// optional.
+    pub fn parse(source: &'a str) -> crate::Result<Self> {
+        let arguments = super::parse_response(source, "supports")?;
+        if let Some(name) = arguments.keys().next() {
+            return Err(Error::new(name.as_str(), ErrorKind::UnexpectedArgument));
+        }
+        let entries = arguments
+            .positional()
+            .map(|&entry| SupportEntry::parse(entry).map_err(|e| e.within(source, entry)))
+            .collect::<crate::Result<_>>()?;
+        Ok(Self { entries })
+    }
+
+    /// Returns the entries of the response, in the order they were received.
+    pub fn entries(&self) -> &[SupportEntry<'a>] {
+        &self.entries
+    }
+
+    /// Returns the set of actions whose tags the client reported as supported.
+    pub fn actions(&self) -> FlagSet<ActionKind> {
+        let mut actions = FlagSet::default();
+        for entry in &self.entries {
+            if let Some(tag) = AtomicTag::well_known(entry.tag)
+                && self.tag(entry.tag) == Some(true)
+            {
+                actions |= tag.action;
+            }
+        }
+        actions
+    }
+
+    /// Returns `Some(true)` if the client reported support for a tag, `Some(false)` if it reported
+    /// a lack of support, or `None` if the tag was not mentioned in the response.
+    ///
+    /// If the tag was only mentioned alongside arguments (e.g. `-color.fore`), the tag itself is
+    /// considered supported, since clients respond with `-color` rather than `-color.fore` for
+    /// unsupported tags.
+    ///
+    /// Case-insensitive.
+    pub fn tag(&self, name: &str) -> Option<bool> {
+        let mut mentioned = false;
+        for entry in &self.entries {
+            if !entry.tag.eq_ignore_ascii_case(name) {
+                continue;
+            }
+            if entry.argument.is_none() {
+                return Some(entry.supported);
+            }
+            mentioned = true;
+        }
+        if mentioned { Some(true) } else { None }
+    }
+
+    /// Returns `Some(true)` if the client reported support for an argument of a tag, `Some(false)`
+    /// if it reported a lack of support, or `None` if the argument was not mentioned in the
+    /// response.
+    ///
+    /// Case-insensitive.
+    pub fn argument(&self, tag: &str, argument: &str) -> Option<bool> {
+        self.entries
+            .iter()
+            .find(|entry| {
+                entry.tag.eq_ignore_ascii_case(tag)
+                    && entry
+                        .argument
+                        .is_some_and(|arg| arg.eq_ignore_ascii_case(argument))
+            })
+            .map(|entry| entry.supported)
+    }
+}
+
+impl fmt::Display for Supports<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\x1B[1z<SUPPORTS")?;
+        for entry in &self.entries {
+            write!(f, " {entry}")?;
+        }
+        write!(f, ">\r\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn supported() -> FlagSet<ActionKind> {
+        ActionKind::Bold | ActionKind::Color | ActionKind::Image | ActionKind::Send
+    }
+
+    #[test]
+    fn round_trip_queries() {
+        let response = SupportResponse::new(
+            &["color.*", "send.expire", "image.foo", "font"],
+            supported(),
+        )
+        .to_string();
+        let supports = Supports::parse(&response).unwrap();
+        assert_eq!(supports.to_string(), response);
+        assert_eq!(
+            supports.actions(),
+            ActionKind::Color | ActionKind::Send | ActionKind::Image
+        );
+        assert_eq!(supports.tag("font"), Some(false));
+        assert_eq!(supports.argument("color", "back"), Some(true));
+        assert_eq!(supports.argument("image", "foo"), Some(false));
+    }
+
+    #[test]
+    fn round_trip_all() {
+        let response = SupportResponse::new(&[] as &[&str], supported()).to_string();
+        let supports = Supports::parse(&response).unwrap();
+        assert_eq!(supports.to_string(), response);
+        // Clients offer a color-only <FONT> if <COLOR> is supported.
+        assert_eq!(supports.actions(), supported() | ActionKind::Font);
+        for tag in AtomicTag::supported() {
+            if tag.action == ActionKind::Font {
+                continue;
+            }
+            let expected = supported().contains(tag.action);
+            assert_eq!(supports.tag(tag.name).is_some(), expected, "{}", tag.name);
+            for arg in tag.args {
+                assert_eq!(supports.argument(tag.name, arg).is_some(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_entry() {
+        let source = "<SUPPORTS +b color>";
+        let error = Supports::parse(source).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedArgument);
+        assert_eq!(error.span(), Some(13..18));
+    }
+
+    #[test]
+    fn wrong_tag() {
+        let error = Supports::parse("<VERSION MXP=1.0>").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnknownElement);
+    }
+}
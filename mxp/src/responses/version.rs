@@ -1,5 +1,8 @@
 use std::fmt;
 
+use crate::arguments::{ArgumentScanner, ExpectArg};
+use crate::{Error, ErrorKind};
+
 /// Formats a [`<VERSION>`] response.
 ///
 /// [`<VERSION>`]: https://www.zuggsoft.com/zmud/mxp.htm#Version%20Control
@@ -21,6 +24,7 @@ use std::fmt;
 /// );
 ///
 /// let advanced_response = VersionResponse {
+///     mxp: "1.0",
 ///     client: "myclient",
 ///     version: "6.07",
 ///     style: Some("1.05"),
@@ -32,9 +36,11 @@ use std::fmt;
 ///     "\x1B[1z<VERSION MXP=1.0 STYLE=1.05 CLIENT=myclient VERSION=6.07 REGISTERED=yes>\r\n"
 /// );
 /// ```
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct VersionResponse<'a> {
-    /// Name of the MUD client. If more than one word, the value should be in quotes.
+    /// Version of MXP supported by the client. Defaults to `"1.0"`.
+    pub mxp: &'a str,
+    /// Name of the MUD client. If more than one word, the value is quoted when formatted.
     pub client: &'a str,
     /// Version of the MUD client.
     pub version: &'a str,
@@ -46,9 +52,78 @@ pub struct VersionResponse<'a> {
     pub registered: Option<bool>,
 }
 
+impl Default for VersionResponse<'_> {
+    fn default() -> Self {
+        Self {
+            mxp: "1.0",
+            client: "",
+            version: "",
+            style: None,
+            registered: None,
+        }
+    }
+}
+
+impl<'a> VersionResponse<'a> {
+    /// Parses a client's `<VERSION>` response, in the form of
+    /// `<VERSION MXP=1.0 [STYLE=style] CLIENT=client VERSION=version [REGISTERED=yes/no]>`.
+    /// The leading `\x1B[1z` and trailing `\r\n` are optional. If `MXP` is omitted, it defaults
+    /// to `"1.0"`.
+    ///
+    /// This is the server-side counterpart of the `Display` implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mxp::responses::VersionResponse;
+    ///
+    /// let response = VersionResponse::parse("<VERSION MXP=1.0 CLIENT=\"my client\" VERSION=6.07>");
+    /// assert_eq!(
+    ///     response,
+    ///     Ok(VersionResponse {
+    ///         mxp: "1.0",
+    ///         client: "my client",
+    ///         version: "6.07",
+    ///         style: None,
+    ///         registered: None,
+    ///     }),
+    /// );
+    /// ```
+    pub fn parse(source: &'a str) -> crate::Result<Self> {
+        let arguments = super::parse_response(source, "version")?;
+        let mut scanner = arguments.scan();
+        let mxp = scanner.get_named("mxp")?.copied().unwrap_or("1.0");
+        let client = scanner
+            .get_named("client")?
+            .copied()
+            .expect_some("client")?;
+        let version = scanner
+            .get_named("version")?
+            .copied()
+            .expect_some("version")?;
+        let style = scanner.get_named("style")?.copied();
+        let registered = match scanner.get_named("registered")?.copied() {
+            None => None,
+            Some(registered) => Some(match_ci! {registered,
+                "yes" => true,
+                "no" => false,
+                _ => return Err(Error::new(registered, ErrorKind::UnexpectedArgument)),
+            }),
+        };
+        Ok(Self {
+            mxp,
+            client,
+            version,
+            style,
+            registered,
+        })
+    }
+}
+
 impl fmt::Display for VersionResponse<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let Self {
+            mxp,
             client: name,
             version,
             style,
@@ -59,10 +134,71 @@ impl fmt::Display for VersionResponse<'_> {
             Some(false) => " REGISTERED=no",
             None => "",
         };
-        write!(f, "\x1B[1z<VERSION MXP=1.0 ")?;
+        write!(f, "\x1B[1z<VERSION MXP={} ", Value(mxp))?;
         if let Some(style) = style {
-            write!(f, "STYLE={style} ")?;
+            write!(f, "STYLE={} ", Value(style))?;
+        }
+        write!(
+            f,
+            "CLIENT={} VERSION={}{registered}>\r\n",
+            Value(name),
+            Value(version)
+        )
+    }
+}
+
+/// Argument value, quoted if it contains whitespace.
+struct Value<'a>(&'a str);
+
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.contains(char::is_whitespace) {
+            write!(f, "\"{}\"", self.0)
+        } else {
+            f.write_str(self.0)
         }
-        write!(f, "CLIENT={name} VERSION={version}{registered}>\r\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let response = VersionResponse {
+            mxp: "1.1",
+            client: "myclient",
+            version: "6.07",
+            style: Some("1.05"),
+            registered: Some(false),
+        };
+        let formatted = response.to_string();
+        assert_eq!(VersionResponse::parse(&formatted), Ok(response));
+        let response = VersionResponse {
+            client: "my client",
+            version: "6.07 beta",
+            ..Default::default()
+        };
+        let formatted = response.to_string();
+        assert_eq!(
+            formatted,
+            "\x1B[1z<VERSION MXP=1.0 CLIENT=\"my client\" VERSION=\"6.07 beta\">\r\n"
+        );
+        assert_eq!(VersionResponse::parse(&formatted), Ok(response));
+    }
+
+    #[test]
+    fn mxp_version() {
+        let response = VersionResponse::parse("<VERSION MXP=0.5 CLIENT=myclient VERSION=6.07>");
+        assert_eq!(response.map(|response| response.mxp), Ok("0.5"));
+        let response = VersionResponse::parse("<VERSION CLIENT=myclient VERSION=6.07>");
+        assert_eq!(response.map(|response| response.mxp), Ok("1.0"));
+    }
+
+    #[test]
+    fn missing_client() {
+        let error = VersionResponse::parse("<VERSION MXP=1.0 VERSION=6.07>").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::MissingArguments);
     }
 }
@@ -1,23 +1,40 @@
 use std::borrow::Cow;
+use std::collections::hash_map;
+use std::iter::FusedIterator;
+use std::slice;
+
+use flagset::FlagSet;
+use html_escape::encode_double_quoted_attribute as escape;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use uncased::Uncased;
 
 use crate::CaseFoldMap;
 use crate::arguments::Arguments;
-use crate::element::{Action, AtomicTag, Element, ElementFlag};
+use crate::element::{Action, AtomicTag, CustomTag, Element, ElementFlag};
 use crate::elements::Var;
-use crate::entity::{DecodedEntity, EntityEntry, EntityMap, PublishedIter};
+use crate::entity::{
+    DecodedEntity, Entity, EntityEntry, EntityMap, EntityVisibility, PublishedIter,
+};
+use crate::keyword::EntityKeyword;
 use crate::line::{LineTag, LineTags, Mode};
 use crate::node::{
     AttributeListDefinition, Definition, ElementDefinition, EntityDefinition, LineTagDefinition,
 };
-use crate::parse::Decoder;
-use crate::{Error, ErrorKind};
+use crate::parse::{Decoder, validate};
+use crate::{Error, ErrorKind, Limits};
 
 /// A store of MXP state: elements, entities, and line tags.
-#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, PartialEq, Eq)]
 pub struct State {
     elements: CaseFoldMap<'static, Element>,
     entities: EntityMap,
     line_tags: LineTags,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    custom_tags: CaseFoldMap<'static, CustomTag>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    limits: Limits,
 }
 
 impl Clone for State {
@@ -27,6 +44,8 @@ impl Clone for State {
             elements: self.elements.clone(),
             entities: self.entities.clone(),
             line_tags: self.line_tags.clone(),
+            custom_tags: self.custom_tags.clone(),
+            limits: self.limits,
         }
     }
 
@@ -35,6 +54,8 @@ impl Clone for State {
         self.elements.clone_from(&source.elements);
         self.entities.clone_from(&source.entities);
         self.line_tags.clone_from(&source.line_tags);
+        self.custom_tags.clone_from(&source.custom_tags);
+        self.limits = source.limits;
     }
 }
 
@@ -56,11 +77,28 @@ impl State {
             elements,
             entities: EntityMap::with_globals(),
             line_tags: LineTags::new(),
+            custom_tags: CaseFoldMap::new(),
+            limits: Limits::DEFAULT,
         }
     }
 
+    /// Returns the quotas enforced on definitions from the server.
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    /// Replaces the quotas enforced on definitions from the server. Definitions that were stored
+    /// before the change are unaffected, even if they exceed the new limits.
+    ///
+    /// Note: limits are not serialized, since they are client configuration rather than server
+    /// state.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
     /// Clears the state, removing all elements, entities, and line tags, except for predefined
-    /// globals.
+    /// globals. Custom tags registered with [`register_tag`](Self::register_tag) and
+    /// [limits](Self::limits) are kept.
     pub fn clear(&mut self) {
         self.elements.clear();
         self.entities.clear();
@@ -122,7 +160,9 @@ impl State {
         var: &Var<S>,
         value: &str,
     ) -> crate::Result<Option<EntityEntry<'a>>> {
-        let entity = self.entities.define(var.with_value(value))?;
+        let definition = var.with_value(value);
+        self.check_entity(&definition)?;
+        let entity = self.entities.define(definition)?;
         Ok(EntityEntry::new(entity))
     }
 
@@ -132,6 +172,75 @@ impl State {
         self.entities.published()
     }
 
+    /// Registers a client-specific atomic tag. Once registered, the tag can be retrieved with
+    /// [`get_component`](Self::get_component) and is listed by [`custom_tags`](Self::custom_tags)
+    /// for use in [`SupportResponse`]s. Returns the previously registered tag with the same name,
+    /// if any.
+    ///
+    /// Returns an error if the tag's name is invalid, or if it is the name of a standard
+    /// [`AtomicTag`].
+    ///
+    /// Note: registered tags are not serialized, since they contain a function pointer. They must
+    /// be registered again after deserializing a state.
+    ///
+    /// [`SupportResponse`]: crate::responses::SupportResponse
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mxp::element::CustomTag;
+    /// use mxp::node::Tag;
+    ///
+    /// const MAPPER: CustomTag = CustomTag {
+    ///     name: "MAPPER",
+    ///     args: &["room"],
+    ///     open: false,
+    ///     command: true,
+    ///     decode: CustomTag::decode_args,
+    /// };
+    ///
+    /// let mut state = mxp::State::with_globals();
+    /// state.register_tag(MAPPER).unwrap();
+    ///
+    /// let Ok(Tag::Open(tag)) = Tag::parse("mapper 1024", true) else {
+    ///     unreachable!();
+    /// };
+    /// let mxp::Component::Custom(custom) = state.get_component(tag.name, true).unwrap() else {
+    ///     unreachable!();
+    /// };
+    /// let action = custom.decode(&tag.arguments, &state).unwrap().into_owned();
+    /// assert_eq!(
+    ///     action,
+    ///     mxp::Action::Custom(mxp::Custom {
+    ///         name: "MAPPER",
+    ///         values: vec![("room", "1024".to_owned())],
+    ///     }),
+    /// );
+    ///
+    /// // custom tags are subject to the same secure checks as standard tags
+    /// assert!(state.get_component("mapper", false).is_err());
+    /// ```
+    pub fn register_tag(&mut self, tag: CustomTag) -> crate::Result<Option<CustomTag>> {
+        validate(tag.name, ErrorKind::InvalidElementName)?;
+        if AtomicTag::well_known(tag.name).is_some() {
+            return Err(Error::new(tag.name, ErrorKind::CannotRedefineAtomicTag));
+        }
+        Ok(self.custom_tags.insert(tag.name, tag))
+    }
+
+    /// Removes a client-specific atomic tag that was registered with
+    /// [`register_tag`](Self::register_tag). Returns the tag if it was registered.
+    pub fn unregister_tag(&mut self, name: &str) -> Option<CustomTag> {
+        self.custom_tags.remove(name)
+    }
+
+    /// An iterator visiting all registered custom tags in arbitrary order.
+    pub fn custom_tags(&self) -> CustomTags<'_> {
+        CustomTags {
+            inner: self.custom_tags.values(),
+        }
+    }
+
     /// Retrieves a tag or element by name. Returns an error if no tag or element is defined by
     /// that name, or if the tag or element is not OPEN (see [`Component::is_open`]) and `secure`
     /// is false.
@@ -140,6 +249,8 @@ impl State {
             Component::Element(custom)
         } else if let Some(tag) = AtomicTag::well_known(name) {
             Component::AtomicTag(tag)
+        } else if let Some(tag) = self.custom_tags.get(name) {
+            Component::Custom(tag)
         } else {
             return Err(Error::new(name, ErrorKind::UnknownElement));
         };
@@ -185,6 +296,9 @@ impl State {
     /// Handles an MXP definition from the server, which may define an [attribute list], [element],
     /// [entity], or [line tag].
     ///
+    /// Returns an error if the definition would exceed the state's [limits](Self::limits), in which
+    /// case the state is left unchanged.
+    ///
     /// Returns an [`EntityEntry`] if the operation alters the definition of an entity. The client
     /// can use this to keep track of entity updates, especially if the entity has
     /// [`EntityVisibility::Publish`].
@@ -200,30 +314,107 @@ impl State {
     ) -> crate::Result<Option<EntityEntry<'a>>> {
         match definition {
             Definition::AttributeList(def) => self.define_attributes(&def)?,
-            Definition::Element(def) => self.define_element(def),
+            Definition::Element(def) => self.define_element(def)?,
             Definition::Entity(def) => return self.define_entity(def),
             Definition::LineTag(def) => self.define_line_tag(def)?,
         }
         Ok(None)
     }
 
+    /// Rebuilds definitions for everything stored in the state: custom elements (including their
+    /// attribute lists), custom entities, and line tags. Predefined elements and global entities
+    /// are skipped.
+    ///
+    /// Formatting each definition from [`Definitions::iter`] with `Display` produces an MXP script
+    /// which, when passed back into [`define`](Self::define) on a state created by
+    /// [`State::with_globals`], reproduces the original state. Since entity values are stored in
+    /// decoded form, characters such as `&` and `"` in values and descriptions are escaped as
+    /// entities. Elements and entities are sorted by name, so that scripts from different
+    /// sessions can be compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mxp::node::Tag;
+    ///
+    /// let mut state = mxp::State::with_globals();
+    /// for source in ["!EL hp '<B>' ATT='max'", "!EN class Wizard PUBLISH"] {
+    ///     let Tag::Definition(definition) = Tag::parse(source, true).unwrap() else {
+    ///         unreachable!();
+    ///     };
+    ///     state.define(definition).unwrap();
+    /// }
+    ///
+    /// let definitions = state.definitions();
+    /// let script: Vec<String> = definitions.iter().map(|def| def.to_string()).collect();
+    /// assert_eq!(script, ["<!EL hp '<B>' ATT='max'>", "<!EN class \"Wizard\" PUBLISH>"]);
+    /// ```
+    pub fn definitions(&self) -> Definitions<'_> {
+        let well_known = Element::well_known();
+        let mut elements: Vec<&Element> = self
+            .elements
+            .values()
+            .filter(|el| !well_known.iter().any(|(_, known)| known == *el))
+            .collect();
+        elements.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+        let mut entities: Vec<(&String, &Entity)> = self.entities.custom_entities().collect();
+        entities.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+        let entities = entities
+            .into_iter()
+            .map(|(name, entity)| EscapedEntity {
+                name,
+                value: escape(&entity.value),
+                desc: (!entity.description.is_empty()).then(|| escape(&entity.description)),
+                keywords: match entity.visibility {
+                    EntityVisibility::Default => FlagSet::default(),
+                    EntityVisibility::Private => EntityKeyword::Private.into(),
+                    EntityVisibility::Publish => EntityKeyword::Publish.into(),
+                },
+            })
+            .collect();
+
+        Definitions {
+            elements,
+            entities,
+            line_tags: self.line_tags.definitions().collect(),
+        }
+    }
+
     fn define_attributes(&mut self, definition: &AttributeListDefinition) -> crate::Result<()> {
-        self.elements
+        let attributes = &mut self
+            .elements
             .get_mut(definition.name)
             .ok_or_else(|| Error::new(definition.name, ErrorKind::UnknownElementInAttlist))?
-            .attributes
-            .append(definition.attributes)
+            .attributes;
+        let len = attributes.len();
+        attributes.append(definition.attributes)?;
+        if attributes.len() > self.limits.max_attributes {
+            attributes.truncate(len);
+            return Err(Error::new(definition.name, ErrorKind::TooManyAttributes));
+        }
+        Ok(())
     }
 
-    fn define_element(&mut self, definition: ElementDefinition) {
+    fn define_element(&mut self, definition: ElementDefinition) -> crate::Result<()> {
         let Some(el) = definition.element else {
             self.elements.remove(definition.name);
-            return;
+            return Ok(());
         };
+        if el.attributes.len() > self.limits.max_attributes {
+            return Err(Error::new(definition.name, ErrorKind::TooManyAttributes));
+        }
+        if self.elements.len() >= self.limits.max_elements
+            && !self.elements.contains_key(definition.name)
+        {
+            return Err(Error::new(definition.name, ErrorKind::TooManyElements));
+        }
         if let Some(tag) = el.line_tag {
             self.line_tags.set(tag.0.into(), el.name.clone());
         }
         self.elements.insert(el.name.clone(), el);
+        Ok(())
     }
 
     fn define_entity<'a>(
@@ -241,15 +432,43 @@ impl State {
             None => None,
         };
         let value = self.decode_string(value)?;
-        let entity = self.entities.define(EntityDefinition {
+        let definition = EntityDefinition {
             name,
             desc: desc.as_deref(),
             value: &value,
             keywords,
-        })?;
+        };
+        self.check_entity(&definition)?;
+        let entity = self.entities.define(definition)?;
         Ok(EntityEntry::new(entity))
     }
 
+    fn check_entity(&self, definition: &EntityDefinition) -> crate::Result<()> {
+        let EntityDefinition {
+            name,
+            value,
+            keywords,
+            ..
+        } = *definition;
+        if keywords.contains(EntityKeyword::Delete) || keywords.contains(EntityKeyword::Remove) {
+            return Ok(());
+        }
+        let existing = self.entities.get_entity(name);
+        if existing.is_none() && self.entities.len() >= self.limits.max_entities {
+            return Err(Error::new(name, ErrorKind::TooManyEntities));
+        }
+        let len = match existing {
+            Some(existing) if keywords.contains(EntityKeyword::Add) => {
+                existing.len() + "|".len() + value.len()
+            }
+            _ => value.len(),
+        };
+        if len > self.limits.max_entity_len {
+            return Err(Error::new(name, ErrorKind::EntityTooLong));
+        }
+        Ok(())
+    }
+
     fn define_line_tag(&mut self, definition: LineTagDefinition) -> crate::Result<()> {
         self.line_tags.update(definition)
     }
@@ -261,6 +480,115 @@ impl Decoder for State {
     }
 }
 
+/// Definitions rebuilt from a [`State`], which can be iterated through by reference.
+///
+/// This struct is created by [`State::definitions`]. See its documentation for more.
+#[derive(Clone, Debug)]
+pub struct Definitions<'a> {
+    elements: Vec<&'a Element>,
+    entities: Vec<EscapedEntity<'a>>,
+    line_tags: Vec<LineTagDefinition<'a>>,
+}
+
+#[derive(Clone, Debug)]
+struct EscapedEntity<'a> {
+    name: &'a str,
+    value: Cow<'a, str>,
+    desc: Option<Cow<'a, str>>,
+    keywords: FlagSet<EntityKeyword>,
+}
+
+impl Definitions<'_> {
+    /// Returns the number of definitions.
+    pub fn len(&self) -> usize {
+        self.elements.len() + self.entities.len() + self.line_tags.len()
+    }
+
+    /// Returns `true` if there are no definitions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates through the definitions: elements first, then entities, then line tags.
+    pub fn iter(&self) -> DefinitionsIter<'_> {
+        DefinitionsIter {
+            elements: self.elements.iter(),
+            entities: self.entities.iter(),
+            line_tags: self.line_tags.iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Definitions<'_> {
+    type Item = Definition<'a>;
+    type IntoIter = DefinitionsIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// This struct is created by [`Definitions::iter`]. See its documentation for more.
+#[derive(Clone, Debug)]
+pub struct DefinitionsIter<'a> {
+    elements: slice::Iter<'a, &'a Element>,
+    entities: slice::Iter<'a, EscapedEntity<'a>>,
+    line_tags: slice::Iter<'a, LineTagDefinition<'a>>,
+}
+
+impl<'a> Iterator for DefinitionsIter<'a> {
+    type Item = Definition<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(el) = self.elements.next() {
+            return Some(Definition::Element(ElementDefinition {
+                name: &el.name,
+                element: Some((*el).clone()),
+            }));
+        }
+        if let Some(entity) = self.entities.next() {
+            return Some(Definition::Entity(EntityDefinition {
+                name: entity.name,
+                value: &entity.value,
+                desc: entity.desc.as_deref(),
+                keywords: entity.keywords,
+            }));
+        }
+        self.line_tags.next().copied().map(Definition::LineTag)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.elements.len() + self.entities.len() + self.line_tags.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for DefinitionsIter<'_> {}
+
+impl FusedIterator for DefinitionsIter<'_> {}
+
+/// This struct is created by [`State::custom_tags`]. See its documentation for more.
+#[derive(Clone, Debug)]
+pub struct CustomTags<'a> {
+    inner: hash_map::Values<'a, Uncased<'static>, CustomTag>,
+}
+
+impl<'a> Iterator for CustomTags<'a> {
+    type Item = &'a CustomTag;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for CustomTags<'_> {}
+
+impl FusedIterator for CustomTags<'_> {}
+
 /// This struct is created by [`State::get_component`]. See its documentation for more.
 #[derive(Copy, Clone, Debug)]
 pub enum Component<'a> {
@@ -268,6 +596,8 @@ pub enum Component<'a> {
     AtomicTag(&'static AtomicTag),
     /// A user-defined custom tag element.
     Element(&'a Element),
+    /// A client-specific tag registered with [`State::register_tag`].
+    Custom(&'a CustomTag),
 }
 
 impl Component<'_> {
@@ -278,6 +608,7 @@ impl Component<'_> {
         match self {
             Self::AtomicTag(tag) => tag.name,
             Self::Element(el) => el.name.as_str(),
+            Self::Custom(tag) => tag.name,
         }
     }
 
@@ -286,6 +617,7 @@ impl Component<'_> {
         match self {
             Self::AtomicTag(tag) => tag.action.is_command(),
             Self::Element(el) => el.empty,
+            Self::Custom(tag) => tag.command,
         }
     }
 
@@ -294,14 +626,130 @@ impl Component<'_> {
         match self {
             Self::AtomicTag(tag) => tag.action.is_open(),
             Self::Element(el) => el.open,
+            Self::Custom(tag) => tag.open,
         }
     }
 
     /// Returns the element's flag, if it has one.
     pub const fn flag(&self) -> Option<&ElementFlag> {
         match self {
-            Self::AtomicTag(_) => None,
+            Self::AtomicTag(_) | Self::Custom(_) => None,
             Self::Element(el) => el.flag.as_ref(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::try_from_node;
+
+    fn populated_state() -> State {
+        let mut state = State::with_globals();
+        for source in [
+            "<!EL custom '<COLOR &col;><B>' ATT='col=red' TAG=20 FLAG='SET myvar' OPEN>",
+            "<!ATTLIST custom 'size back'>",
+            "<!EL empty EMPTY>",
+            "<!EN hp 100 PRIVATE>",
+            "<!EN room 'Town Square' DESC='current room' PUBLISH>",
+            "<!EN guilds Wizards ADD>",
+            "<!EN quote '&quot;Hi&quot; &amp;amp; bye' DESC='say \"hi\" &amp; go'>",
+            "<!TAG 20 WINDOWNAME=status FORE=red GAG>",
+            "<!TAG 23 BACK=blue DISABLE>",
+        ] {
+            state.define(try_from_node(source)).unwrap();
+        }
+        state
+    }
+
+    #[test]
+    fn definitions_round_trip() {
+        let state = populated_state();
+        let definitions = state.definitions();
+        let mut script = Vec::new();
+        for definition in &definitions {
+            script.push(definition.to_string());
+        }
+        let mut restored = State::with_globals();
+        for source in &script {
+            restored.define(try_from_node(source)).unwrap();
+        }
+        assert_eq!(restored, state);
+        assert_eq!(
+            restored.decode_entity("quote"),
+            Ok("\"Hi\" &amp; bye".into())
+        );
+        assert!(script.contains(
+            &"<!EN quote \"&quot;Hi&quot; &amp;amp; bye\" DESC=\"say &quot;hi&quot; &amp; go\">"
+                .to_owned()
+        ));
+        assert_eq!(script.len(), definitions.len());
+        assert_eq!(script.last().unwrap(), "<!TAG 23 BACK=#0000ff DISABLE>");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let state = populated_state();
+        let json = serde_json::to_string(&state).unwrap();
+        let restored: State = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, state);
+        assert_eq!(restored.decode_entity("lt"), Ok("<".into()));
+        assert_eq!(restored.custom_entities_len(), 4);
+        assert_eq!(restored.published_entities().count(), 1);
+    }
+
+    fn define_all(state: &mut State, sources: &[&str]) -> Vec<crate::Result<()>> {
+        sources
+            .iter()
+            .map(|source| state.define(try_from_node(source)).map(|_| ()))
+            .collect()
+    }
+
+    #[test]
+    fn limits() {
+        let mut state = State::default();
+        state.set_limits(Limits {
+            max_elements: 1,
+            max_entities: 1,
+            max_entity_len: 5,
+            max_attributes: 2,
+            max_depth: 0,
+        });
+        let results = define_all(
+            &mut state,
+            &[
+                "<!EL a '<B>' ATT='x y z'>",
+                "<!EL a '<B>' ATT='x'>",
+                "<!EL b '<I>'>",
+                "<!ATTLIST a 'y z'>",
+                "<!ATTLIST a 'y'>",
+                "<!EN e 123456>",
+                "<!EN e 12>",
+                "<!EN e 345 ADD>",
+                "<!EN f 1>",
+            ],
+        );
+        let kinds: Vec<Option<ErrorKind>> = results
+            .iter()
+            .map(|result| result.as_ref().err().map(Error::kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                Some(ErrorKind::TooManyAttributes),
+                None,
+                Some(ErrorKind::TooManyElements),
+                Some(ErrorKind::TooManyAttributes),
+                None,
+                Some(ErrorKind::EntityTooLong),
+                None,
+                Some(ErrorKind::EntityTooLong),
+                Some(ErrorKind::TooManyEntities),
+            ]
+        );
+        assert_eq!(state.custom_elements_len(), 1);
+        assert_eq!(state.elements.get("a").unwrap().attributes.len(), 2);
+        assert_eq!(state.get_entity("e"), Some("12"));
+    }
+}
@@ -31,6 +31,7 @@ pub fn decode_actions(source: &str, state: &State) -> crate::Result<Vec<Action>>
     let tag: TagOpen = try_from_node(source);
     match state.get_component(tag.name, true)? {
         Component::AtomicTag(atom) => Ok(vec![atom.decode(&tag.arguments, state)?.into_owned()]),
+        Component::Custom(custom) => Ok(vec![custom.decode(&tag.arguments, state)?.into_owned()]),
         Component::Element(el) => el
             .decode(&tag.arguments, state)
             .map(|result| result.map(Action::into_owned))
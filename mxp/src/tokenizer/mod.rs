@@ -0,0 +1,5 @@
+mod token;
+pub use token::Token;
+
+mod tokenizer;
+pub use tokenizer::{Tokenizer, Tokens};
@@ -0,0 +1,56 @@
+use std::borrow::Cow;
+
+use crate::Mode;
+
+/// Unit of MXP output produced by a [`Tokenizer`](super::Tokenizer).
+///
+/// Tokens borrow from the input chunk whenever possible. Tags and entities that were split across
+/// multiple chunks are returned as owned values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token<'a> {
+    /// A run of text. Line breaks are included at the end of the run.
+    ///
+    /// Text is returned as raw bytes, because a chunk may end in the middle of a multi-byte
+    /// character. Escape sequences other than MXP line mode changes are passed through as text.
+    Text(Cow<'a, [u8]>),
+    /// A tag, i.e. anything surrounded by `<` and `>`, without its brackets. The source can be
+    /// parsed with [`Tag::parse`](crate::node::Tag::parse).
+    Tag {
+        /// Contents of the tag, excluding the `<` and `>` brackets.
+        source: Cow<'a, str>,
+        /// Whether the line mode was secure when the tag was received.
+        secure: bool,
+    },
+    /// An entity, i.e. anything surrounded by `&` and `;`, without its delimiters. The name can be
+    /// decoded with [`State::decode_entity`](crate::State::decode_entity).
+    Entity(Cow<'a, str>),
+    /// The line mode changed, either because the server sent an `ESC[#z` sequence or because a
+    /// line break reverted the line mode to its default.
+    ModeChange {
+        /// The new line mode.
+        mode: Mode,
+        /// Whether all tags since the most recent OPEN tag should be closed.
+        close_open_tags: bool,
+    },
+}
+
+impl Token<'_> {
+    /// Converts borrowed data in the token to owned data.
+    pub fn into_owned(self) -> Token<'static> {
+        match self {
+            Self::Text(text) => Token::Text(Cow::Owned(text.into_owned())),
+            Self::Tag { source, secure } => Token::Tag {
+                source: Cow::Owned(source.into_owned()),
+                secure,
+            },
+            Self::Entity(name) => Token::Entity(Cow::Owned(name.into_owned())),
+            Self::ModeChange {
+                mode,
+                close_open_tags,
+            } => Token::ModeChange {
+                mode,
+                close_open_tags,
+            },
+        }
+    }
+}
@@ -0,0 +1,623 @@
+use std::borrow::Cow;
+use std::iter::FusedIterator;
+use std::mem;
+
+use super::token::Token;
+use crate::escape::ansi;
+use crate::{Error, ErrorKind, Mode, ModeState, ParseOptions};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+enum Phase {
+    /// Normal text
+    #[default]
+    Text,
+    /// Received an escape
+    Esc,
+    /// Received `ESC [`, collecting digits
+    Csi,
+    /// Received `<`, matching the given number of bytes of `!--`
+    ElementStart(u8),
+    /// Collecting element, eg. < xxxxx >. Starts on <, stops on >
+    Element,
+    /// Collecting quote inside element, eg. <color='red'>
+    Quote(u8),
+    /// Collecting comment, eg. <!-- xxxxx -->. Tracks the number of trailing dashes.
+    Comment(u8),
+    /// Collecting entity, eg. &gt; . Starts on &, stops on ;
+    Entity,
+}
+
+impl Phase {
+    const fn unterminated(self) -> Option<ErrorKind> {
+        match self {
+            Self::Text | Self::Esc | Self::Csi => None,
+            Self::ElementStart(_) | Self::Element => Some(ErrorKind::UnterminatedElement),
+            Self::Quote(_) => Some(ErrorKind::UnterminatedQuote),
+            Self::Comment(_) => Some(ErrorKind::UnterminatedComment),
+            Self::Entity => Some(ErrorKind::UnterminatedEntity),
+        }
+    }
+}
+
+/// Incremental tokenizer for MXP text received from a server.
+///
+/// `Tokenizer` accepts input in arbitrary chunks, such as the packets read from a socket. It
+/// recognizes tags, entities, comments, and MXP line mode changes (`ESC[#z`), keeping track of the
+/// current line mode with a [`ModeState`]. Tags and entities that are split across chunks are
+/// buffered until they are complete.
+///
+/// Tags, entities, quotes, and comments that are interrupted by a line break or an escape
+/// sequence produce [`ErrorKind::UnterminatedElement`], [`ErrorKind::UnterminatedEntity`],
+/// [`ErrorKind::UnterminatedQuote`], and [`ErrorKind::UnterminatedComment`] errors, respectively.
+/// After an error, the tokenizer recovers and continues with the next token. If the
+/// [`ParseOptions`] of the tokenizer's mode state recover from [`ErrorKind::UnterminatedEntity`],
+/// the error is followed by a [`Token::Text`] containing the entity's raw text, instead of the
+/// text being discarded.
+///
+/// Telnet sequences are not handled by the tokenizer, and must be stripped beforehand.
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+/// use mxp::{Mode, Token, Tokenizer};
+///
+/// let mut tokenizer = Tokenizer::new();
+/// let mut tokens = Vec::new();
+/// for chunk in [&b"\x1B[1z<COLOR r"[..], b"ed>Hi&amp;", b"bye</COLOR>\n"] {
+///     for token in tokenizer.push(chunk) {
+///         tokens.push(token.unwrap().into_owned());
+///     }
+/// }
+/// assert_eq!(
+///     tokens,
+///     vec![
+///         Token::ModeChange { mode: Mode::SECURE, close_open_tags: true },
+///         Token::Tag { source: Cow::Borrowed("COLOR red"), secure: true },
+///         Token::Text(Cow::Borrowed(b"Hi")),
+///         Token::Entity(Cow::Borrowed("amp")),
+///         Token::Text(Cow::Borrowed(b"bye")),
+///         Token::Tag { source: Cow::Borrowed("/COLOR"), secure: true },
+///         Token::Text(Cow::Borrowed(b"\n")),
+///         Token::ModeChange { mode: Mode::OPEN, close_open_tags: true },
+///     ],
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Tokenizer {
+    phase: Phase,
+    mode: ModeState,
+    buf: Vec<u8>,
+    sequence: Vec<u8>,
+    recovered: Vec<u8>,
+    reverting: bool,
+}
+
+impl Tokenizer {
+    /// Constructs a new `Tokenizer` in [`Mode::OPEN`] mode.
+    pub const fn new() -> Self {
+        Self::with_options(ParseOptions::STRICT)
+    }
+
+    /// Constructs a new `Tokenizer` in [`Mode::OPEN`] mode with the specified parse options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use mxp::{ErrorKind, ParseOptions, Token, Tokenizer};
+    ///
+    /// let mut tokenizer = Tokenizer::with_options(ParseOptions::LENIENT);
+    /// let tokens: Vec<_> = tokenizer.push(b"fish & chips\n").collect();
+    /// assert_eq!(tokens[0], Ok(Token::Text(Cow::Borrowed(b"fish "))));
+    /// assert_eq!(tokens[1].as_ref().unwrap_err().kind(), ErrorKind::UnterminatedEntity);
+    /// assert_eq!(tokens[2], Ok(Token::Text(Cow::Borrowed(b"& chips"))));
+    /// ```
+    pub const fn with_options(options: ParseOptions) -> Self {
+        Self {
+            phase: Phase::Text,
+            mode: ModeState::with_options(options),
+            buf: Vec::new(),
+            sequence: Vec::new(),
+            recovered: Vec::new(),
+            reverting: false,
+        }
+    }
+
+    /// Returns the line mode state tracked by the tokenizer.
+    pub const fn mode(&self) -> &ModeState {
+        &self.mode
+    }
+
+    /// Applies a new line mode, as if the server had sent an `ESC[#z` sequence. Returns `true` if
+    /// the change in mode means all tags since the most recent OPEN tag should be closed.
+    ///
+    /// See [`ModeState::set`].
+    pub fn set_mode(&mut self, mode: Mode) -> bool {
+        self.mode.set(mode)
+    }
+
+    /// Returns `true` if the tokenizer is in the middle of a tag, entity, or escape sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tokenizer = mxp::Tokenizer::new();
+    /// tokenizer.push(b"text <B").for_each(drop);
+    /// assert!(tokenizer.is_pending());
+    /// tokenizer.push(b">").for_each(drop);
+    /// assert!(!tokenizer.is_pending());
+    /// ```
+    pub fn is_pending(&self) -> bool {
+        self.phase != Phase::Text
+    }
+
+    /// Discards any partially received input and resets the line mode to [`Mode::OPEN`]. Parse
+    /// options are retained.
+    pub fn reset(&mut self) {
+        self.phase = Phase::Text;
+        self.mode = ModeState::with_options(self.mode.options());
+        self.buf.clear();
+        self.sequence.clear();
+        self.recovered.clear();
+        self.reverting = false;
+    }
+
+    /// Feeds a chunk of input to the tokenizer, returning an iterator over the tokens it contains.
+    ///
+    /// The iterator must be exhausted in order for the entire chunk to be processed. Partial tags
+    /// and entities at the end of the chunk are completed by subsequent calls.
+    pub fn push<'a>(&'a mut self, input: &'a [u8]) -> Tokens<'a> {
+        Tokens {
+            tokenizer: self,
+            input,
+            pos: 0,
+            mark: 0,
+        }
+    }
+}
+
+/// Iterator over the tokens in a chunk of input.
+///
+/// This struct is created by [`Tokenizer::push`]. See its documentation for more.
+#[derive(Debug)]
+pub struct Tokens<'a> {
+    tokenizer: &'a mut Tokenizer,
+    input: &'a [u8],
+    /// Position of the next byte to process.
+    pos: usize,
+    /// Start of the current text run or tag.
+    mark: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn advance(&mut self) {
+        self.pos += 1;
+        self.mark = self.pos;
+    }
+
+    fn take_text(&mut self) -> Option<Token<'a>> {
+        if self.mark == self.pos {
+            return None;
+        }
+        let text = &self.input[self.mark..self.pos];
+        self.mark = self.pos;
+        Some(Token::Text(Cow::Borrowed(text)))
+    }
+
+    fn take_sequence(&mut self) -> Token<'a> {
+        self.tokenizer.phase = Phase::Text;
+        Token::Text(Cow::Owned(mem::take(&mut self.tokenizer.sequence)))
+    }
+
+    fn collect(&mut self) -> crate::Result<Cow<'a, str>> {
+        let bytes = &self.input[self.mark..self.pos];
+        let buf = &mut self.tokenizer.buf;
+        if buf.is_empty() {
+            return crate::validate_utf8(bytes).map(Cow::Borrowed);
+        }
+        buf.extend_from_slice(bytes);
+        Ok(Cow::Owned(String::from_utf8(mem::take(buf))?))
+    }
+
+    fn unterminated(&mut self, kind: ErrorKind) -> Error {
+        let buf = &mut self.tokenizer.buf;
+        buf.extend_from_slice(&self.input[self.mark..self.pos]);
+        let error = Error::new(String::from_utf8_lossy(buf), kind);
+        if kind == ErrorKind::UnterminatedEntity && self.tokenizer.mode.options().recovers(kind) {
+            self.tokenizer.recovered.push(b'&');
+            self.tokenizer.recovered.extend_from_slice(buf);
+        }
+        buf.clear();
+        self.mark = self.pos;
+        self.tokenizer.mode.use_secure(); // clear SECURE_ONCE
+        error
+    }
+
+    fn mode_change(&mut self) -> Option<Token<'a>> {
+        let digits = self.tokenizer.sequence.get(2..)?;
+        let mode: Mode = str::from_utf8(digits)
+            .ok()?
+            .parse::<u8>()
+            .ok()?
+            .try_into()
+            .ok()?;
+        self.tokenizer.sequence.clear();
+        self.tokenizer.phase = Phase::Text;
+        Some(Token::ModeChange {
+            mode,
+            close_open_tags: self.tokenizer.mode.set(mode),
+        })
+    }
+
+    /// Processes a single byte. Returns `None` if the byte did not complete a token. Bytes that
+    /// interrupt a token are left in place to be processed again.
+    fn step(&mut self, c: u8) -> Option<crate::Result<Token<'a>>> {
+        let phase = self.tokenizer.phase;
+        if matches!(c, b'\r' | b'\n' | ansi::ESC)
+            && let Some(kind) = phase.unterminated()
+        {
+            self.tokenizer.phase = Phase::Text;
+            return Some(Err(self.unterminated(kind)));
+        }
+
+        match phase {
+            Phase::Text => {
+                let locked = self.tokenizer.mode.is_locked();
+                match c {
+                    b'<' | b'&' | ansi::ESC
+                        if self.mark != self.pos && (c == ansi::ESC || !locked) =>
+                    {
+                        return self.take_text().map(Ok);
+                    }
+                    _ if c != b'<' && self.tokenizer.mode.is_secure_once() => {
+                        if let Some(text) = self.take_text() {
+                            return Some(Ok(text));
+                        }
+                        return self
+                            .tokenizer
+                            .mode
+                            .validate_next_character(c)
+                            .err()
+                            .map(Err);
+                    }
+                    b'<' if !locked => {
+                        self.advance();
+                        self.tokenizer.phase = Phase::ElementStart(0);
+                    }
+                    b'&' if !locked => {
+                        self.advance();
+                        self.tokenizer.phase = Phase::Entity;
+                    }
+                    ansi::ESC => {
+                        self.advance();
+                        self.tokenizer.sequence.push(c);
+                        self.tokenizer.phase = Phase::Esc;
+                    }
+                    b'\n' => {
+                        self.pos += 1;
+                        self.tokenizer.reverting = true;
+                        return self.take_text().map(Ok);
+                    }
+                    _ => self.pos += 1,
+                }
+            }
+
+            Phase::Esc if c == b'[' => {
+                self.advance();
+                self.tokenizer.sequence.push(c);
+                self.tokenizer.phase = Phase::Csi;
+            }
+
+            Phase::Csi if c.is_ascii_digit() => {
+                self.advance();
+                self.tokenizer.sequence.push(c);
+            }
+
+            Phase::Csi if c == b'z' => {
+                self.advance();
+                if let Some(token) = self.mode_change() {
+                    return Some(Ok(token));
+                }
+                self.tokenizer.sequence.push(c);
+                return Some(Ok(self.take_sequence()));
+            }
+
+            Phase::Esc | Phase::Csi => return Some(Ok(self.take_sequence())),
+
+            Phase::ElementStart(matched) => {
+                if c != b"!--"[usize::from(matched)] {
+                    self.tokenizer.phase = Phase::Element;
+                    return None;
+                }
+                self.pos += 1;
+                self.tokenizer.phase = if matched == 2 {
+                    Phase::Comment(2)
+                } else {
+                    Phase::ElementStart(matched + 1)
+                };
+            }
+
+            Phase::Element => match c {
+                b'>' => {
+                    let secure = self.tokenizer.mode.use_secure();
+                    let source = self.collect();
+                    self.advance();
+                    self.tokenizer.phase = Phase::Text;
+                    return Some(source.map(|source| Token::Tag { source, secure }));
+                }
+                b'<' => {
+                    let error = self.unterminated(ErrorKind::UnterminatedElement);
+                    self.advance();
+                    self.tokenizer.phase = Phase::ElementStart(0);
+                    return Some(Err(error));
+                }
+                b'\'' | b'"' => {
+                    self.pos += 1;
+                    self.tokenizer.phase = Phase::Quote(c);
+                }
+                _ => self.pos += 1,
+            },
+
+            Phase::Quote(terminator) => {
+                self.pos += 1;
+                if c == terminator {
+                    self.tokenizer.phase = Phase::Element;
+                }
+            }
+
+            Phase::Comment(dashes) => {
+                self.pos += 1;
+                self.tokenizer.phase = match c {
+                    b'>' if dashes >= 2 => {
+                        self.mark = self.pos;
+                        self.tokenizer.buf.clear();
+                        Phase::Text
+                    }
+                    b'-' => Phase::Comment(2.min(dashes + 1)),
+                    _ => Phase::Comment(0),
+                };
+            }
+
+            Phase::Entity => match c {
+                b';' => {
+                    let name = self.collect();
+                    self.advance();
+                    self.tokenizer.phase = Phase::Text;
+                    return Some(name.map(Token::Entity));
+                }
+                b'&' => {
+                    let error = self.unterminated(ErrorKind::UnterminatedEntity);
+                    self.advance();
+                    return Some(Err(error));
+                }
+                b'<' => {
+                    let error = self.unterminated(ErrorKind::UnterminatedEntity);
+                    self.advance();
+                    self.tokenizer.phase = Phase::ElementStart(0);
+                    return Some(Err(error));
+                }
+                _ => self.pos += 1,
+            },
+        }
+        None
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = crate::Result<Token<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if mem::take(&mut self.tokenizer.reverting) {
+            self.tokenizer.mode.revert();
+            return Some(Ok(Token::ModeChange {
+                mode: self.tokenizer.mode.get(),
+                close_open_tags: true,
+            }));
+        }
+        if !self.tokenizer.recovered.is_empty() {
+            let text = mem::take(&mut self.tokenizer.recovered);
+            return Some(Ok(Token::Text(Cow::Owned(text))));
+        }
+        while let Some(&c) = self.input.get(self.pos) {
+            if let Some(token) = self.step(c) {
+                return Some(token);
+            }
+        }
+        if self.tokenizer.phase == Phase::Text {
+            return self.take_text().map(Ok);
+        }
+        let rest = &self.input[self.mark..];
+        self.mark = self.input.len();
+        if !matches!(self.tokenizer.phase, Phase::Esc | Phase::Csi) {
+            self.tokenizer.buf.extend_from_slice(rest);
+        }
+        None
+    }
+}
+
+impl FusedIterator for Tokens<'_> {}
+
+#[cfg(test)]
+#[allow(clippy::unnecessary_wraps)]
+mod tests {
+    use super::*;
+
+    fn tokenize(tokenizer: &mut Tokenizer, chunks: &[&[u8]]) -> Vec<crate::Result<Token<'static>>> {
+        chunks
+            .iter()
+            .flat_map(|chunk| {
+                tokenizer
+                    .push(chunk)
+                    .map(|token| token.map(Token::into_owned))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn text(s: &str) -> crate::Result<Token<'static>> {
+        Ok(Token::Text(Cow::Owned(s.as_bytes().to_vec())))
+    }
+
+    fn tag(source: &str, secure: bool) -> crate::Result<Token<'static>> {
+        Ok(Token::Tag {
+            source: Cow::Owned(source.to_owned()),
+            secure,
+        })
+    }
+
+    fn entity(name: &str) -> crate::Result<Token<'static>> {
+        Ok(Token::Entity(Cow::Owned(name.to_owned())))
+    }
+
+    fn mode(mode: Mode, close_open_tags: bool) -> crate::Result<Token<'static>> {
+        Ok(Token::ModeChange {
+            mode,
+            close_open_tags,
+        })
+    }
+
+    fn error(target: &str, kind: ErrorKind) -> crate::Result<Token<'static>> {
+        Err(Error::new(target, kind))
+    }
+
+    #[test]
+    fn split_tag() {
+        let mut tokenizer = Tokenizer::new();
+        let tokens = tokenize(&mut tokenizer, &[b"a<col", b"or fore='re", b"d'>b"]);
+        assert_eq!(
+            tokens,
+            [text("a"), tag("color fore='red'", false), text("b")]
+        );
+    }
+
+    #[test]
+    fn split_entity() {
+        let mut tokenizer = Tokenizer::new();
+        let tokens = tokenize(&mut tokenizer, &[b"&l", b"t;&gt", b";"]);
+        assert_eq!(tokens, [entity("lt"), entity("gt")]);
+    }
+
+    #[test]
+    fn quoted_brackets() {
+        let mut tokenizer = Tokenizer::new();
+        let tokens = tokenize(&mut tokenizer, &[b"<send \"a>b\" '<c>'>"]);
+        assert_eq!(tokens, [tag("send \"a>b\" '<c>'", false)]);
+    }
+
+    #[test]
+    fn comments() {
+        let mut tokenizer = Tokenizer::new();
+        let tokens = tokenize(&mut tokenizer, &[b"a<!-- x > y -", b"->b<!-->c<!-x>"]);
+        assert_eq!(tokens, [text("a"), text("b"), text("c"), tag("!-x", false)]);
+    }
+
+    #[test]
+    fn mode_changes() {
+        let mut tokenizer = Tokenizer::new();
+        let tokens = tokenize(&mut tokenizer, &[b"\x1B[6z<b>\x1B[", b"1", b"z\n"]);
+        assert_eq!(
+            tokens,
+            [
+                mode(Mode::PERM_SECURE, true),
+                tag("b", true),
+                mode(Mode::SECURE, false),
+                text("\n"),
+                mode(Mode::SECURE, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn other_escape_sequences() {
+        let mut tokenizer = Tokenizer::new();
+        let tokens = tokenize(&mut tokenizer, &[b"a\x1B[1", b";31mb\x1B[100zc\x1B\x1B7"]);
+        assert_eq!(
+            tokens,
+            [
+                text("a"),
+                text("\x1B[1"),
+                text(";31mb"),
+                text("\x1B[100z"),
+                text("c"),
+                text("\x1B"),
+                text("\x1B"),
+                text("7"),
+            ]
+        );
+    }
+
+    #[test]
+    fn locked_mode() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.set_mode(Mode::LOCKED);
+        let tokens = tokenize(&mut tokenizer, &[b"<b>&amp;\n<b>"]);
+        assert_eq!(
+            tokens,
+            [text("<b>&amp;\n"), mode(Mode::OPEN, true), tag("b", false)]
+        );
+    }
+
+    #[test]
+    fn secure_once() {
+        let mut tokenizer = Tokenizer::new();
+        let tokens = tokenize(&mut tokenizer, &[b"\x1B[4z<b><b>\x1B[4zx"]);
+        assert_eq!(
+            tokens,
+            [
+                mode(Mode::SECURE_ONCE, true),
+                tag("b", true),
+                tag("b", false),
+                mode(Mode::SECURE_ONCE, true),
+                error("x", ErrorKind::TextAfterSecureOnce),
+                text("x"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated() {
+        let mut tokenizer = Tokenizer::new();
+        let tokens = tokenize(
+            &mut tokenizer,
+            &[b"<a\n<a 'b\n<!--", b"c\n&d\x1B[1z&e&f<g>"],
+        );
+        assert_eq!(
+            tokens,
+            [
+                error("a", ErrorKind::UnterminatedElement),
+                text("\n"),
+                mode(Mode::OPEN, true),
+                error("a 'b", ErrorKind::UnterminatedQuote),
+                text("\n"),
+                mode(Mode::OPEN, true),
+                error("!--c", ErrorKind::UnterminatedComment),
+                text("\n"),
+                mode(Mode::OPEN, true),
+                error("d", ErrorKind::UnterminatedEntity),
+                mode(Mode::SECURE, true),
+                error("e", ErrorKind::UnterminatedEntity),
+                error("f", ErrorKind::UnterminatedEntity),
+                tag("g", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn recover_unterminated_entity() {
+        let mut tokenizer = Tokenizer::with_options(ParseOptions::LENIENT);
+        let tokens = tokenize(&mut tokenizer, &[b"a &b", b" c&d;<e>\n"]);
+        assert_eq!(
+            tokens,
+            [
+                text("a "),
+                error("b c", ErrorKind::UnterminatedEntity),
+                text("&b c"),
+                entity("d"),
+                tag("e", false),
+                text("\n"),
+                mode(Mode::OPEN, true),
+            ]
+        );
+    }
+}